@@ -1,52 +1,350 @@
-use sqlx::types::BigDecimal;
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use sqlx::types::{BigDecimal, JsonValue};
+use std::fmt;
+use std::str::FromStr;
 use uuid::Uuid;
 
+/// Month keys in calendar order, January first.
+pub const MONTH_NAMES: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Employee {
     pub id: Uuid,
     pub first_name: String,
     pub last_name: String,
     pub email: String,
+    /// The annual salary in force today. Earlier and scheduled rates are kept as [Salary].
     pub salary: BigDecimal,
+    /// Bumped by every update; updates and deletes must name the version they replace.
+    pub version: i32,
+    /// The last day of someone who has left. Their row stays for the reports of the years they
+    /// worked, but default listings leave them out.
+    pub terminated_on: Option<NaiveDate>,
 }
 
-#[derive(Debug, Clone)]
+impl Employee {
+    /// Whether the employee was still around for at least part of `year`.
+    pub fn employed_in(&self, year: i32) -> bool {
+        self.terminated_on.is_none_or(|x| x.year() >= year)
+    }
+
+    /// Whether the employee was still around for at least part of `month` (January is 0) of
+    /// `year`. Someone leaving mid-month counts for that month and none after it.
+    pub fn employed_on(&self, year: i32, month: usize) -> bool {
+        self.terminated_on
+            .is_none_or(|x| (x.year(), x.month0() as usize) >= (year, month))
+    }
+}
+
+/// An annual salary an employee is paid from `effective_from` until their next rate starts.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Salary {
+    pub employee_id: Uuid,
+    pub effective_from: NaiveDate,
+    pub salary: BigDecimal,
+}
+
+impl Salary {
+    /// The rate in force on `day` among one employee's `history`, sorted by `effective_from`.
+    /// The earliest rate also covers the days before it, so only an empty history has none.
+    pub fn in_force(history: &[Salary], day: NaiveDate) -> Option<&Salary> {
+        history
+            .iter()
+            .rev()
+            .find(|x| x.effective_from <= day)
+            .or(history.first())
+    }
+}
+
+/// A permission granted by a role. Stored in `roles.scopes` as a JSON array of the strings
+/// returned by [Scope::as_str].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Scope {
+    EmployeesRead,
+    EmployeesWrite,
+    /// Seeing compensation.
+    SalaryRead,
+    /// Setting compensation; reserved for finance.
+    SalaryWrite,
+    /// Initiatives, tags, allocation curves and who is staffed on them.
+    AllocationsRead,
+    AllocationsWrite,
+    ReportsRead,
+    /// Managing users and roles.
+    UsersAdmin,
+    /// Reading the audit trail of every change.
+    AuditRead,
+}
+
+impl Scope {
+    pub const ALL: [Scope; 9] = [
+        Scope::EmployeesRead,
+        Scope::EmployeesWrite,
+        Scope::SalaryRead,
+        Scope::SalaryWrite,
+        Scope::AllocationsRead,
+        Scope::AllocationsWrite,
+        Scope::ReportsRead,
+        Scope::UsersAdmin,
+        Scope::AuditRead,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Scope::EmployeesRead => "employees:read",
+            Scope::EmployeesWrite => "employees:write",
+            Scope::SalaryRead => "salary:read",
+            Scope::SalaryWrite => "salary:write",
+            Scope::AllocationsRead => "allocations:read",
+            Scope::AllocationsWrite => "allocations:write",
+            Scope::ReportsRead => "reports:read",
+            Scope::UsersAdmin => "users:admin",
+            Scope::AuditRead => "audit:read",
+        }
+    }
+}
+
+impl fmt::Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Scope {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Scope::ALL
+            .into_iter()
+            .find(|x| x.as_str() == s)
+            .ok_or_else(|| format!("unknown scope {s}"))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Role {
+    pub id: Uuid,
+    pub name: String,
+    pub scopes: Vec<Scope>,
+}
+
+/// A person who can sign in, identified by the subject and issuer of their tokens.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct User {
+    pub id: Uuid,
+    pub first_name: String,
+    pub last_name: String,
+    pub email: String,
+    pub external_id: String,
+    pub external_id_source: String,
+    pub role_id: Option<Uuid>,
+    /// Deactivated users are kept but can no longer sign in.
+    pub active: bool,
+}
+
+/// The columns employees can be listed by. Every order falls back to the id, so ties between
+/// equal values are broken in creation order.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EmployeeSort {
+    #[default]
+    Id,
+    Name,
+    Email,
+    Salary,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SortOrder {
+    #[default]
+    Asc,
+    Desc,
+}
+
+/// Filters, ordering and position for listing employees one page at a time.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EmployeeQuery {
+    /// Case-insensitive prefix of either the first or the last name.
+    pub name_prefix: Option<String>,
+    pub min_salary: Option<BigDecimal>,
+    pub max_salary: Option<BigDecimal>,
+    pub sort: EmployeeSort,
+    pub order: SortOrder,
+    /// Only return employees that come after this one in the requested order.
+    pub after: Option<Uuid>,
+    /// Also list employees who have left.
+    pub include_terminated: bool,
+    pub limit: i64,
+}
+
+/// One page of results, with the id to continue after when more are available.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub next: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Tag {
     pub id: Uuid,
     pub tag: String,
+    pub version: i32,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Initiative {
     pub id: Uuid,
     pub initiative: String,
+    pub version: i32,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Assignment {
     pub id: Uuid,
-    pub year: i64,
-    pub jan: Option<f64>,
-    pub feb: Option<f64>,
-    pub mar: Option<f64>,
-    pub apr: Option<f64>,
-    pub may: Option<f64>,
-    pub jun: Option<f64>,
-    pub jul: Option<f64>,
-    pub aug: Option<f64>,
-    pub sep: Option<f64>,
-    pub oct: Option<f64>,
-    pub nov: Option<f64>,
-    pub dec: Option<f64>,
+    pub year: i32,
+    pub jan: Option<BigDecimal>,
+    pub feb: Option<BigDecimal>,
+    pub mar: Option<BigDecimal>,
+    pub apr: Option<BigDecimal>,
+    pub may: Option<BigDecimal>,
+    pub jun: Option<BigDecimal>,
+    pub jul: Option<BigDecimal>,
+    pub aug: Option<BigDecimal>,
+    pub sep: Option<BigDecimal>,
+    pub oct: Option<BigDecimal>,
+    pub nov: Option<BigDecimal>,
+    pub dec: Option<BigDecimal>,
+    pub version: i32,
 }
 
-#[derive(Debug, Clone)]
+impl Assignment {
+    /// The monthly fractions in calendar order, January first.
+    pub fn months(&self) -> [Option<&BigDecimal>; 12] {
+        [
+            self.jan.as_ref(),
+            self.feb.as_ref(),
+            self.mar.as_ref(),
+            self.apr.as_ref(),
+            self.may.as_ref(),
+            self.jun.as_ref(),
+            self.jul.as_ref(),
+            self.aug.as_ref(),
+            self.sep.as_ref(),
+            self.oct.as_ref(),
+            self.nov.as_ref(),
+            self.dec.as_ref(),
+        ]
+    }
+
+    /// A curve for `year` with the given fractions, January first, not stored yet.
+    pub fn from_months(year: i32, months: [Option<BigDecimal>; 12]) -> Self {
+        let [jan, feb, mar, apr, may, jun, jul, aug, sep, oct, nov, dec] = months;
+        Assignment {
+            id: Uuid::nil(),
+            year,
+            jan,
+            feb,
+            mar,
+            apr,
+            may,
+            jun,
+            jul,
+            aug,
+            sep,
+            oct,
+            nov,
+            dec,
+            version: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct EmployeeAssignment {
     pub id: Uuid,
     pub employee_id: Uuid,
     pub assignment_id: Uuid,
-    pub allocation: f64,
+    pub allocation: BigDecimal,
     pub initiative_id: Uuid,
+    pub version: i32,
+}
+
+/// An employee assignment together with the monthly curve it follows.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScheduledAssignment {
+    pub employee_assignment: EmployeeAssignment,
+    pub assignment: Assignment,
+}
+
+/// An employee's summed allocation for each month of a year, January first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmployeeAllocation {
+    pub employee: Employee,
+    pub year: i32,
+    pub months: [BigDecimal; 12],
+}
+
+/// One employee's effective allocation to one initiative for each month of a year, January first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlannedAllocation {
+    pub employee: Employee,
+    pub initiative: Initiative,
+    pub year: i32,
+    pub months: [BigDecimal; 12],
+}
+
+/// One row of an imported allocation plan: how much of a person an initiative gets each month.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlanRow {
+    /// Matched case-insensitively against employee emails.
+    pub email: String,
+    /// Matched case-insensitively against initiative names.
+    pub initiative: String,
+    /// Effective fractions, January first; blank cells are unplanned.
+    pub months: [Option<BigDecimal>; 12],
+}
+
+/// What importing a plan row does to the stored plan.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlanChangeKind {
+    Added,
+    Changed,
+    Unchanged,
+}
+
+/// The effect of one plan row: the effective allocation before and after it is applied.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlanChange {
+    pub employee: Employee,
+    pub initiative: Initiative,
+    pub year: i32,
+    pub kind: PlanChangeKind,
+    /// `None` when the employee was not on the initiative.
+    pub before: Option<[BigDecimal; 12]>,
+    pub after: [BigDecimal; 12],
+}
+
+/// A link to store for an imported plan row, together with the curve it follows.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlannedLink {
+    /// The link as it was read, which must still be at its version; `None` creates one.
+    pub link: Option<EmployeeAssignment>,
+    pub employee_id: Uuid,
+    pub initiative_id: Uuid,
+    pub allocation: BigDecimal,
+    /// A nil id stores a new curve; otherwise the curve is rewritten in place if it is still at
+    /// its version.
+    pub assignment: Assignment,
+}
+
+/// What an initiative costs in each month of a year, January first, and over the whole year.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InitiativeCost {
+    pub initiative: Initiative,
+    pub year: i32,
+    pub months: [BigDecimal; 12],
+    pub total: BigDecimal,
 }
 
 #[derive(Debug, Clone)]
@@ -62,3 +360,142 @@ pub struct EmployeeAssignmentTag {
     pub employee_assignment_id: Uuid,
     pub tag_id: Uuid,
 }
+
+#[derive(Debug, Clone)]
+pub struct TagUsage {
+    pub tag: Tag,
+    pub assignment_count: i64,
+    pub employee_assignment_count: i64,
+}
+
+/// The kinds of rows whose changes are written to the audit log. Stored in `audit_log.entity`
+/// as the string returned by [AuditEntity::as_str].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AuditEntity {
+    Employee,
+    Initiative,
+    Tag,
+    Assignment,
+    EmployeeAssignment,
+    User,
+    Role,
+}
+
+impl AuditEntity {
+    pub const ALL: [AuditEntity; 7] = [
+        AuditEntity::Employee,
+        AuditEntity::Initiative,
+        AuditEntity::Tag,
+        AuditEntity::Assignment,
+        AuditEntity::EmployeeAssignment,
+        AuditEntity::User,
+        AuditEntity::Role,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditEntity::Employee => "employee",
+            AuditEntity::Initiative => "initiative",
+            AuditEntity::Tag => "tag",
+            AuditEntity::Assignment => "assignment",
+            AuditEntity::EmployeeAssignment => "employee_assignment",
+            AuditEntity::User => "user",
+            AuditEntity::Role => "role",
+        }
+    }
+}
+
+impl fmt::Display for AuditEntity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for AuditEntity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        AuditEntity::ALL
+            .into_iter()
+            .find(|x| x.as_str() == s)
+            .ok_or_else(|| format!("unknown audit entity {s}"))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AuditAction {
+    Create,
+    Update,
+    Delete,
+    /// A tag folded into another one; `after` holds the tag it was merged into.
+    Merge,
+    /// An employee given a termination date.
+    Terminate,
+    /// A terminated employee made current again.
+    Restore,
+}
+
+impl AuditAction {
+    pub const ALL: [AuditAction; 6] = [
+        AuditAction::Create,
+        AuditAction::Update,
+        AuditAction::Delete,
+        AuditAction::Merge,
+        AuditAction::Terminate,
+        AuditAction::Restore,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditAction::Create => "create",
+            AuditAction::Update => "update",
+            AuditAction::Delete => "delete",
+            AuditAction::Merge => "merge",
+            AuditAction::Terminate => "terminate",
+            AuditAction::Restore => "restore",
+        }
+    }
+}
+
+impl fmt::Display for AuditAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for AuditAction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        AuditAction::ALL
+            .into_iter()
+            .find(|x| x.as_str() == s)
+            .ok_or_else(|| format!("unknown audit action {s}"))
+    }
+}
+
+/// One recorded write: who made it, to which row, and the row as JSON before and after.
+/// `before` is empty for creates and `after` for deletes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditEntry {
+    pub id: Uuid,
+    pub actor_id: Uuid,
+    /// The actor's email, while the user still exists.
+    pub actor_email: Option<String>,
+    pub entity: AuditEntity,
+    pub entity_id: Uuid,
+    pub action: AuditAction,
+    pub before: Option<JsonValue>,
+    pub after: Option<JsonValue>,
+    pub changed_at: DateTime<Utc>,
+}
+
+/// Selects the audit entries of one kind of row, optionally narrowed to a single row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditQuery {
+    pub entity: AuditEntity,
+    pub entity_id: Option<Uuid>,
+    /// Only return entries older than this one.
+    pub after: Option<Uuid>,
+    pub limit: i64,
+}
@@ -0,0 +1,2 @@
+-- Deactivated users keep their row, so history stays attributable, but can no longer sign in.
+alter table users add column if not exists active boolean not null default true;
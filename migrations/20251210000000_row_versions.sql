@@ -0,0 +1,7 @@
+-- Every update bumps the row's version, and writes must name the version they replace, so
+-- concurrent edits fail instead of silently overwriting each other.
+alter table employees add column if not exists version integer not null default 1;
+alter table initiatives add column if not exists version integer not null default 1;
+alter table tags add column if not exists version integer not null default 1;
+alter table assignments add column if not exists version integer not null default 1;
+alter table employee_assignments add column if not exists version integer not null default 1;
@@ -0,0 +1,26 @@
+-- Every write through the repository appends a row here in the same transaction, holding the
+-- row as JSON before and after the change. actor_id has no foreign key so entries outlive the
+-- users who made them.
+create table if not exists audit_log
+(
+  id uuid default uuidv7() primary key,
+  actor_id uuid not null,
+  entity text not null,
+  entity_id uuid not null,
+  action text not null,
+  before jsonb,
+  after jsonb,
+  changed_at timestamptz not null default CURRENT_TIMESTAMP
+);
+
+create index if not exists ix_audit_log_entity on audit_log(entity, entity_id, changed_at);
+
+create or replace function audit_log_append_only() returns trigger as $$
+begin
+  raise exception 'audit_log is append-only';
+end;
+$$ language plpgsql;
+
+drop trigger if exists tr_audit_log_append_only on audit_log;
+create trigger tr_audit_log_append_only before update or delete on audit_log
+  for each row execute function audit_log_append_only();
@@ -0,0 +1,8 @@
+-- Employees are no longer deleted: leaving records a termination date and keeps the row, so
+-- their allocation history stays available to reports.
+alter table employees add column if not exists terminated_on date;
+
+-- Refuse hard deletes that would still take allocation history with them.
+alter table employee_assignments drop constraint if exists employee_assignments_employee_id_fkey;
+alter table employee_assignments add constraint employee_assignments_employee_id_fkey
+  foreign key (employee_id) references employees(id) on delete restrict;
@@ -0,0 +1,40 @@
+-- Salaries change over time. Every rate is kept with the day it takes effect, so costs for a
+-- past month use the pay in force then rather than today's.
+create table if not exists employee_salaries
+(
+  employee_id uuid not null references employees(id) on delete cascade,
+  effective_from date not null,
+  salary numeric(15, 2) not null,
+
+  primary key (employee_id, effective_from)
+);
+
+-- Nothing earlier is known, so each employee's current salary starts the history.
+insert into employee_salaries(employee_id, effective_from, salary)
+select id, current_date, salary from employees
+on conflict do nothing;
+
+alter table employees drop column if exists salary;
+
+-- Employees with the salary in force today and their full history. An employee's earliest rate
+-- also covers the time before it, so nobody is without a salary.
+create or replace view employee_records as
+select
+  e.id,
+  e.first_name,
+  e.last_name,
+  e.email,
+  coalesce(
+    (select s.salary from employee_salaries s
+      where s.employee_id = e.id and s.effective_from <= current_date
+      order by s.effective_from desc limit 1),
+    (select s.salary from employee_salaries s
+      where s.employee_id = e.id
+      order by s.effective_from limit 1)
+  ) as salary,
+  e.version,
+  e.terminated_on,
+  (select jsonb_agg(jsonb_build_object('effective_from', s.effective_from, 'salary', s.salary)
+          order by s.effective_from)
+     from employee_salaries s where s.employee_id = e.id) as salaries
+from employees e;
@@ -0,0 +1,110 @@
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum CreateAssignmentError {
+    #[error("Failed to commit transactions: {source}")]
+    CommitFailed { source: sqlx::Error },
+
+    #[error("Unknown error for assignment: {source}")]
+    Unknown { source: sqlx::Error },
+}
+
+impl From<CreateAssignmentError> for sqlx::Error {
+    fn from(error: CreateAssignmentError) -> Self {
+        match error {
+            CreateAssignmentError::CommitFailed { source } => source,
+            CreateAssignmentError::Unknown { source } => source,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum UpdateAssignmentError {
+    #[error("Assignment with id {id} not found")]
+    NotFound { id: Uuid },
+
+    #[error("Assignment with id {id} was changed by someone else")]
+    Stale { id: Uuid },
+
+    /// Indexes (January is 0) of the months of `year` an employee following the assignment
+    /// would work above 100%.
+    #[error("Employee {employee_id} would be allocated above 100% in {year} for months {months:?}")]
+    Overallocated {
+        employee_id: Uuid,
+        year: i32,
+        months: Vec<usize>,
+    },
+
+    #[error("Failed to commit transactions: {source}")]
+    CommitFailed { source: sqlx::Error },
+
+    #[error("Unknown error for assignment: {source}")]
+    Unknown { source: sqlx::Error },
+}
+
+impl From<UpdateAssignmentError> for sqlx::Error {
+    fn from(error: UpdateAssignmentError) -> Self {
+        match error {
+            UpdateAssignmentError::NotFound { id: _ } => sqlx::Error::RowNotFound,
+            UpdateAssignmentError::Stale { id: _ } => sqlx::Error::RowNotFound,
+            UpdateAssignmentError::Overallocated { .. } => sqlx::Error::Protocol(error.to_string()),
+            UpdateAssignmentError::CommitFailed { source } => source,
+            UpdateAssignmentError::Unknown { source } => source,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum DeleteAssignmentError {
+    #[error("Assignment with id {id} not found")]
+    NotFound { id: Uuid },
+
+    #[error("Assignment with id {id} was changed by someone else")]
+    Stale { id: Uuid },
+
+    #[error("Failed to commit transactions: {source}")]
+    CommitFailed { source: sqlx::Error },
+
+    #[error("Unknown error for assignment: {source}")]
+    Unknown { source: sqlx::Error },
+}
+
+impl From<DeleteAssignmentError> for sqlx::Error {
+    fn from(error: DeleteAssignmentError) -> Self {
+        match error {
+            DeleteAssignmentError::NotFound { id: _ } => sqlx::Error::RowNotFound,
+            DeleteAssignmentError::Stale { id: _ } => sqlx::Error::RowNotFound,
+            DeleteAssignmentError::CommitFailed { source } => source,
+            DeleteAssignmentError::Unknown { source } => source,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum GetAssignmentsError {
+    #[error("Unknown error for assignments: {source}")]
+    Unknown { source: sqlx::Error },
+}
+
+impl From<GetAssignmentsError> for sqlx::Error {
+    fn from(error: GetAssignmentsError) -> Self {
+        match error {
+            GetAssignmentsError::Unknown { source } => source,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum GetAssignmentError {
+    #[error("Unknown error for assignment: {source}")]
+    Unknown { source: sqlx::Error },
+}
+
+impl From<GetAssignmentError> for sqlx::Error {
+    fn from(error: GetAssignmentError) -> Self {
+        match error {
+            GetAssignmentError::Unknown { source } => source,
+        }
+    }
+}
@@ -0,0 +1,385 @@
+use crate::assignment_errors::*;
+use crate::audit_repo::{record, snapshot};
+use crate::dao;
+use crate::employee_assignment_repo::{lock_employees, overallocated_months};
+use crate::in_memory_db::{InMemoryDb, months_over_capacity};
+use crate::postgres_db::PostgresDb;
+use domain::dto;
+use std::future::Future;
+use uuid::Uuid;
+
+pub trait AssignmentRepo: Send + Sync + Clone + 'static {
+    fn create(
+        &self,
+        actor: Uuid,
+        ri: &dto::Assignment,
+    ) -> impl Future<Output = Result<dto::Assignment, CreateAssignmentError>> + Send;
+    fn get(
+        &self,
+        id: Uuid,
+    ) -> impl Future<Output = Result<Option<dto::Assignment>, GetAssignmentError>> + Send;
+    /// Deletes the assignment if it is still at `version`.
+    fn delete(
+        &self,
+        actor: Uuid,
+        id: Uuid,
+        version: i32,
+    ) -> impl Future<Output = Result<(), DeleteAssignmentError>> + Send;
+    /// Stores `ri` if the assignment is still at `ri.version`, bumping the version. Fails with
+    /// nothing stored if anyone following it would then be allocated above 100% in a month.
+    fn update(
+        &self,
+        actor: Uuid,
+        ri: &dto::Assignment,
+    ) -> impl Future<Output = Result<dto::Assignment, UpdateAssignmentError>> + Send;
+    /// Returns every assignment, or only those for `year` when given.
+    fn get_all(
+        &self,
+        year: Option<i32>,
+    ) -> impl Future<Output = Result<Vec<dto::Assignment>, GetAssignmentsError>> + Send;
+}
+
+impl AssignmentRepo for PostgresDb {
+    async fn create(
+        &self,
+        actor: Uuid,
+        ri: &dto::Assignment,
+    ) -> Result<dto::Assignment, CreateAssignmentError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| CreateAssignmentError::Unknown { source: e })?;
+        let assignment = sqlx::query_as!(
+            dao::Assignment,
+            r#"INSERT INTO assignments(year, jan, feb, mar, apr, may, jun, jul, aug, sep, oct, nov, "dec")
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13) RETURNING *"#,
+            ri.year,
+            ri.jan,
+            ri.feb,
+            ri.mar,
+            ri.apr,
+            ri.may,
+            ri.jun,
+            ri.jul,
+            ri.aug,
+            ri.sep,
+            ri.oct,
+            ri.nov,
+            ri.dec,
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| CreateAssignmentError::Unknown { source: e })?;
+        let after = snapshot(&mut tx, dto::AuditEntity::Assignment, assignment.id)
+            .await
+            .map_err(|e| CreateAssignmentError::Unknown { source: e })?;
+        record(
+            &mut tx,
+            actor,
+            dto::AuditEntity::Assignment,
+            assignment.id,
+            dto::AuditAction::Create,
+            None,
+            after,
+        )
+        .await
+        .map_err(|e| CreateAssignmentError::Unknown { source: e })?;
+        tx.commit()
+            .await
+            .map_err(|e| CreateAssignmentError::CommitFailed { source: e })?;
+        Ok((&assignment).into())
+    }
+    async fn update(
+        &self,
+        actor: Uuid,
+        ri: &dto::Assignment,
+    ) -> Result<dto::Assignment, UpdateAssignmentError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| UpdateAssignmentError::Unknown { source: e })?;
+        let before = snapshot(&mut tx, dto::AuditEntity::Assignment, ri.id)
+            .await
+            .map_err(|e| UpdateAssignmentError::Unknown { source: e })?;
+        let ret = sqlx::query_as!(
+            dao::Assignment,
+            r#"UPDATE assignments SET
+                year = $1,
+                jan = $2,
+                feb = $3,
+                mar = $4,
+                apr = $5,
+                may = $6,
+                jun = $7,
+                jul = $8,
+                aug = $9,
+                sep = $10,
+                oct = $11,
+                nov = $12,
+                "dec" = $13,
+                version = version + 1
+               WHERE id = $14 AND version = $15 RETURNING *"#,
+            ri.year,
+            ri.jan,
+            ri.feb,
+            ri.mar,
+            ri.apr,
+            ri.may,
+            ri.jun,
+            ri.jul,
+            ri.aug,
+            ri.sep,
+            ri.oct,
+            ri.nov,
+            ri.dec,
+            ri.id,
+            ri.version
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| UpdateAssignmentError::Unknown { source: e })?;
+        let Some(ret) = ret else {
+            return Err(if before.is_some() {
+                UpdateAssignmentError::Stale { id: ri.id }
+            } else {
+                UpdateAssignmentError::NotFound { id: ri.id }
+            });
+        };
+        // The curve row stays locked until commit, so nobody can start following it meanwhile.
+        let followers = sqlx::query_scalar!(
+            "SELECT DISTINCT employee_id FROM employee_assignments WHERE assignment_id = $1",
+            ri.id
+        )
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| UpdateAssignmentError::Unknown { source: e })?;
+        lock_employees(&mut tx, followers.iter().copied())
+            .await
+            .map_err(|e| UpdateAssignmentError::Unknown { source: e })?;
+        for employee_id in followers {
+            let months = overallocated_months(&mut tx, employee_id, ret.year)
+                .await
+                .map_err(|e| UpdateAssignmentError::Unknown { source: e })?;
+            if !months.is_empty() {
+                return Err(UpdateAssignmentError::Overallocated {
+                    employee_id,
+                    year: ret.year,
+                    months,
+                });
+            }
+        }
+        let after = snapshot(&mut tx, dto::AuditEntity::Assignment, ri.id)
+            .await
+            .map_err(|e| UpdateAssignmentError::Unknown { source: e })?;
+        record(
+            &mut tx,
+            actor,
+            dto::AuditEntity::Assignment,
+            ri.id,
+            dto::AuditAction::Update,
+            before,
+            after,
+        )
+        .await
+        .map_err(|e| UpdateAssignmentError::Unknown { source: e })?;
+        tx.commit()
+            .await
+            .map_err(|e| UpdateAssignmentError::CommitFailed { source: e })?;
+        Ok((&ret).into())
+    }
+    async fn delete(
+        &self,
+        actor: Uuid,
+        id: Uuid,
+        version: i32,
+    ) -> Result<(), DeleteAssignmentError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| DeleteAssignmentError::Unknown { source: e })?;
+        let before = snapshot(&mut tx, dto::AuditEntity::Assignment, id)
+            .await
+            .map_err(|e| DeleteAssignmentError::Unknown { source: e })?;
+        let deleted = sqlx::query!(
+            "DELETE FROM assignments WHERE id = $1 AND version = $2",
+            id,
+            version
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| DeleteAssignmentError::Unknown { source: e })?;
+        if deleted.rows_affected() == 0 {
+            return Err(if before.is_some() {
+                DeleteAssignmentError::Stale { id }
+            } else {
+                DeleteAssignmentError::NotFound { id }
+            });
+        }
+        record(
+            &mut tx,
+            actor,
+            dto::AuditEntity::Assignment,
+            id,
+            dto::AuditAction::Delete,
+            before,
+            None,
+        )
+        .await
+        .map_err(|e| DeleteAssignmentError::Unknown { source: e })?;
+        tx.commit()
+            .await
+            .map_err(|e| DeleteAssignmentError::CommitFailed { source: e })?;
+        Ok(())
+    }
+    async fn get(&self, id: Uuid) -> Result<Option<dto::Assignment>, GetAssignmentError> {
+        let ri = sqlx::query_as!(
+            dao::Assignment,
+            r#"SELECT id, year, jan, feb, mar, apr, may, jun, jul, aug, sep, oct, nov, "dec", version
+               FROM assignments where id=$1"#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| GetAssignmentError::Unknown { source: e })?;
+        Ok(ri.map(|x: dao::Assignment| (&x).into()))
+    }
+    async fn get_all(
+        &self,
+        year: Option<i32>,
+    ) -> Result<Vec<dto::Assignment>, GetAssignmentsError> {
+        let ris = sqlx::query_as!(
+            dao::Assignment,
+            r#"SELECT id, year, jan, feb, mar, apr, may, jun, jul, aug, sep, oct, nov, "dec", version
+               FROM assignments WHERE $1::integer IS NULL OR year = $1 ORDER BY year, id"#,
+            year
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| GetAssignmentsError::Unknown { source: e })?;
+        Ok(ris.into_iter().map(|x| (&x).into()).collect())
+    }
+}
+
+impl AssignmentRepo for InMemoryDb {
+    async fn create(
+        &self,
+        _actor: Uuid,
+        ri: &dto::Assignment,
+    ) -> Result<dto::Assignment, CreateAssignmentError> {
+        let assignment = dto::Assignment {
+            id: Uuid::now_v7(),
+            version: 1,
+            ..ri.clone()
+        };
+        self.assignments
+            .write()
+            .expect("assignments lock poisoned")
+            .insert(assignment.id, assignment.clone());
+        Ok(assignment)
+    }
+    async fn get(&self, id: Uuid) -> Result<Option<dto::Assignment>, GetAssignmentError> {
+        Ok(self
+            .assignments
+            .read()
+            .expect("assignments lock poisoned")
+            .get(&id)
+            .cloned())
+    }
+    async fn delete(
+        &self,
+        _actor: Uuid,
+        id: Uuid,
+        version: i32,
+    ) -> Result<(), DeleteAssignmentError> {
+        let mut assignments = self.assignments.write().expect("assignments lock poisoned");
+        match assignments.get(&id) {
+            None => return Err(DeleteAssignmentError::NotFound { id }),
+            Some(current) if current.version != version => {
+                return Err(DeleteAssignmentError::Stale { id });
+            }
+            Some(_) => {}
+        }
+        assignments.remove(&id);
+        self.assignment_tags
+            .write()
+            .expect("assignment tags lock poisoned")
+            .retain(|&(assignment_id, _)| assignment_id != id);
+        let mut links = self
+            .employee_assignments
+            .write()
+            .expect("employee assignments lock poisoned");
+        let removed: Vec<Uuid> = links
+            .values()
+            .filter(|x| x.assignment_id == id)
+            .map(|x| x.id)
+            .collect();
+        links.retain(|_, x| x.assignment_id != id);
+        self.employee_assignment_tags
+            .write()
+            .expect("employee assignment tags lock poisoned")
+            .retain(|(link_id, _)| !removed.contains(link_id));
+        Ok(())
+    }
+    async fn update(
+        &self,
+        _actor: Uuid,
+        ri: &dto::Assignment,
+    ) -> Result<dto::Assignment, UpdateAssignmentError> {
+        let mut assignments = self.assignments.write().expect("assignments lock poisoned");
+        let links = self
+            .employee_assignments
+            .read()
+            .expect("employee assignments lock poisoned");
+        match assignments.get(&ri.id) {
+            None => return Err(UpdateAssignmentError::NotFound { id: ri.id }),
+            Some(current) if current.version != ri.version => {
+                return Err(UpdateAssignmentError::Stale { id: ri.id });
+            }
+            Some(_) => {}
+        }
+        let assignment = dto::Assignment {
+            version: ri.version + 1,
+            ..ri.clone()
+        };
+        let before = assignments.insert(ri.id, assignment.clone());
+        let mut followers: Vec<Uuid> = links
+            .values()
+            .filter(|x| x.assignment_id == ri.id)
+            .map(|x| x.employee_id)
+            .collect();
+        followers.sort();
+        followers.dedup();
+        for employee_id in followers {
+            let months = months_over_capacity(&links, &assignments, employee_id, ri.year);
+            if !months.is_empty() {
+                if let Some(before) = before {
+                    assignments.insert(ri.id, before);
+                }
+                return Err(UpdateAssignmentError::Overallocated {
+                    employee_id,
+                    year: ri.year,
+                    months,
+                });
+            }
+        }
+        Ok(assignment)
+    }
+    async fn get_all(
+        &self,
+        year: Option<i32>,
+    ) -> Result<Vec<dto::Assignment>, GetAssignmentsError> {
+        let mut ris: Vec<dto::Assignment> = self
+            .assignments
+            .read()
+            .expect("assignments lock poisoned")
+            .values()
+            .filter(|x| year.is_none_or(|year| x.year == year))
+            .cloned()
+            .collect();
+        ris.sort_by_key(|x| (x.year, x.id));
+        Ok(ris)
+    }
+}
@@ -0,0 +1,20 @@
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum GetAuditEntriesError {
+    #[error("Cursor {id} does not refer to an existing audit entry")]
+    InvalidCursor { id: Uuid },
+
+    #[error("Unknown error for audit entries: {source}")]
+    Unknown { source: sqlx::Error },
+}
+
+impl From<GetAuditEntriesError> for sqlx::Error {
+    fn from(error: GetAuditEntriesError) -> Self {
+        match error {
+            GetAuditEntriesError::InvalidCursor { id: _ } => sqlx::Error::RowNotFound,
+            GetAuditEntriesError::Unknown { source } => source,
+        }
+    }
+}
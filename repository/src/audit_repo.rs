@@ -0,0 +1,132 @@
+use crate::audit_errors::*;
+use crate::dao;
+use crate::employee_repo::into_page;
+use crate::in_memory_db::InMemoryDb;
+use crate::postgres_db::PostgresDb;
+use domain::dto;
+use sqlx::PgConnection;
+use sqlx::types::JsonValue;
+use std::future::Future;
+use uuid::Uuid;
+
+/// Reads the log every repository write appends to. Writes take the id of the user making them
+/// as `actor` and record the row before and after in the same transaction.
+pub trait AuditRepo: Send + Sync + Clone + 'static {
+    /// Returns one page of the entries matching `query`, newest first.
+    fn get_entries(
+        &self,
+        query: &dto::AuditQuery,
+    ) -> impl Future<Output = Result<dto::Page<dto::AuditEntry>, GetAuditEntriesError>> + Send;
+}
+
+impl AuditRepo for PostgresDb {
+    async fn get_entries(
+        &self,
+        query: &dto::AuditQuery,
+    ) -> Result<dto::Page<dto::AuditEntry>, GetAuditEntriesError> {
+        if let Some(after) = query.after {
+            let exists = sqlx::query_scalar!(
+                r#"SELECT EXISTS(SELECT 1 FROM audit_log WHERE id = $1) AS "exists!""#,
+                after
+            )
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| GetAuditEntriesError::Unknown { source: e })?;
+            if !exists {
+                return Err(GetAuditEntriesError::InvalidCursor { id: after });
+            }
+        }
+        // Keyset pagination on (changed_at, id), the order the entries are listed in.
+        let ris = sqlx::query_as!(
+            dao::AuditEntry,
+            r#"SELECT l.id, l.actor_id, u.email AS "actor_email?", l.entity, l.entity_id, l.action,
+                l.before, l.after, l.changed_at
+               FROM audit_log l LEFT JOIN users u ON u.id = l.actor_id
+               WHERE l.entity = $1 AND ($2::uuid IS NULL OR l.entity_id = $2)
+                 AND ($3::uuid IS NULL
+                      OR (l.changed_at, l.id) < (SELECT changed_at, id FROM audit_log WHERE id = $3))
+               ORDER BY l.changed_at DESC, l.id DESC
+               LIMIT $4"#,
+            query.entity.as_str(),
+            query.entity_id,
+            query.after,
+            query.limit + 1
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| GetAuditEntriesError::Unknown { source: e })?;
+        let items = ris
+            .iter()
+            .map(dto::AuditEntry::try_from)
+            .collect::<Result<_, _>>()
+            .map_err(|e| GetAuditEntriesError::Unknown { source: e })?;
+        Ok(into_page(items, query.limit, |x| x.id))
+    }
+}
+
+fn table(entity: dto::AuditEntity) -> &'static str {
+    match entity {
+        // The view adds the salary in force and its history to the row.
+        dto::AuditEntity::Employee => "employee_records",
+        dto::AuditEntity::Initiative => "initiatives",
+        dto::AuditEntity::Tag => "tags",
+        dto::AuditEntity::Assignment => "assignments",
+        dto::AuditEntity::EmployeeAssignment => "employee_assignments",
+        dto::AuditEntity::User => "users",
+        dto::AuditEntity::Role => "roles",
+    }
+}
+
+/// Locks row `id` of `entity` for the rest of the transaction and returns it as JSON, or `None`
+/// when there is no such row. Taken before a write it is the audit `before`, after it the `after`.
+pub(crate) async fn snapshot(
+    conn: &mut PgConnection,
+    entity: dto::AuditEntity,
+    id: Uuid,
+) -> Result<Option<JsonValue>, sqlx::Error> {
+    sqlx::query_scalar(&format!(
+        "SELECT to_jsonb(t) FROM {} t WHERE t.id = $1 FOR UPDATE",
+        table(entity)
+    ))
+    .bind(id)
+    .fetch_optional(conn)
+    .await
+}
+
+/// Appends an entry to the audit log. Call it inside the transaction making the change, so
+/// the entry is only kept when the change is.
+pub(crate) async fn record(
+    conn: &mut PgConnection,
+    actor: Uuid,
+    entity: dto::AuditEntity,
+    entity_id: Uuid,
+    action: dto::AuditAction,
+    before: Option<JsonValue>,
+    after: Option<JsonValue>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"INSERT INTO audit_log(actor_id, entity, entity_id, action, before, after)
+           VALUES ($1, $2, $3, $4, $5, $6)"#,
+        actor,
+        entity.as_str(),
+        entity_id,
+        action.as_str(),
+        before,
+        after
+    )
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+impl AuditRepo for InMemoryDb {
+    async fn get_entries(
+        &self,
+        _query: &dto::AuditQuery,
+    ) -> Result<dto::Page<dto::AuditEntry>, GetAuditEntriesError> {
+        Ok(dto::Page {
+            items: Vec::new(),
+            next: None,
+        })
+    }
+}
@@ -9,6 +9,8 @@ impl From<&dto::Employee> for dao::Employee {
             last_name: ri.last_name.clone(),
             email: ri.email.clone(),
             salary: ri.salary.clone(),
+            version: ri.version,
+            terminated_on: ri.terminated_on,
         }
     }
 }
@@ -21,6 +23,169 @@ impl From<&dao::Employee> for dto::Employee {
             last_name: ri.last_name.clone(),
             email: ri.email.clone(),
             salary: ri.salary.clone(),
+            version: ri.version,
+            terminated_on: ri.terminated_on,
         }
     }
 }
+
+impl From<&dao::Salary> for dto::Salary {
+    fn from(ri: &dao::Salary) -> Self {
+        dto::Salary {
+            employee_id: ri.employee_id,
+            effective_from: ri.effective_from,
+            salary: ri.salary.clone(),
+        }
+    }
+}
+
+impl From<&dao::Role> for dto::Role {
+    fn from(ri: &dao::Role) -> Self {
+        dto::Role {
+            id: ri.id,
+            name: ri.name.clone(),
+            // Scopes this build does not know about grant nothing.
+            scopes: ri.scopes.iter().filter_map(|x| x.parse().ok()).collect(),
+        }
+    }
+}
+
+impl From<&dao::User> for dto::User {
+    fn from(ri: &dao::User) -> Self {
+        dto::User {
+            id: ri.id,
+            first_name: ri.first_name.clone(),
+            last_name: ri.last_name.clone(),
+            email: ri.email.clone(),
+            external_id: ri.external_id.clone(),
+            external_id_source: ri.external_id_source.clone(),
+            role_id: ri.role_id,
+            active: ri.active,
+        }
+    }
+}
+
+impl From<&dao::Initiative> for dto::Initiative {
+    fn from(ri: &dao::Initiative) -> Self {
+        dto::Initiative {
+            id: ri.id,
+            initiative: ri.initiative.clone(),
+            version: ri.version,
+        }
+    }
+}
+
+impl From<&dao::Tag> for dto::Tag {
+    fn from(ri: &dao::Tag) -> Self {
+        dto::Tag {
+            id: ri.id,
+            tag: ri.tag.clone(),
+            version: ri.version,
+        }
+    }
+}
+
+impl From<&dao::TagUsage> for dto::TagUsage {
+    fn from(ri: &dao::TagUsage) -> Self {
+        dto::TagUsage {
+            tag: dto::Tag {
+                id: ri.id,
+                tag: ri.tag.clone(),
+                version: ri.version,
+            },
+            assignment_count: ri.assignment_count,
+            employee_assignment_count: ri.employee_assignment_count,
+        }
+    }
+}
+
+impl From<&dao::Assignment> for dto::Assignment {
+    fn from(ri: &dao::Assignment) -> Self {
+        dto::Assignment {
+            id: ri.id,
+            year: ri.year,
+            jan: ri.jan.clone(),
+            feb: ri.feb.clone(),
+            mar: ri.mar.clone(),
+            apr: ri.apr.clone(),
+            may: ri.may.clone(),
+            jun: ri.jun.clone(),
+            jul: ri.jul.clone(),
+            aug: ri.aug.clone(),
+            sep: ri.sep.clone(),
+            oct: ri.oct.clone(),
+            nov: ri.nov.clone(),
+            dec: ri.dec.clone(),
+            version: ri.version,
+        }
+    }
+}
+
+impl From<&dao::EmployeeAssignment> for dto::EmployeeAssignment {
+    fn from(ri: &dao::EmployeeAssignment) -> Self {
+        dto::EmployeeAssignment {
+            id: ri.id,
+            employee_id: ri.employee_id,
+            assignment_id: ri.assignment_id,
+            allocation: ri.allocation.clone(),
+            initiative_id: ri.initiative_id,
+            version: ri.version,
+        }
+    }
+}
+
+impl From<&dao::ScheduledAssignment> for dto::ScheduledAssignment {
+    fn from(ri: &dao::ScheduledAssignment) -> Self {
+        dto::ScheduledAssignment {
+            employee_assignment: dto::EmployeeAssignment {
+                id: ri.id,
+                employee_id: ri.employee_id,
+                assignment_id: ri.assignment_id,
+                allocation: ri.allocation.clone(),
+                initiative_id: ri.initiative_id,
+                version: ri.version,
+            },
+            assignment: dto::Assignment {
+                id: ri.assignment_id,
+                year: ri.year,
+                jan: ri.jan.clone(),
+                feb: ri.feb.clone(),
+                mar: ri.mar.clone(),
+                apr: ri.apr.clone(),
+                may: ri.may.clone(),
+                jun: ri.jun.clone(),
+                jul: ri.jul.clone(),
+                aug: ri.aug.clone(),
+                sep: ri.sep.clone(),
+                oct: ri.oct.clone(),
+                nov: ri.nov.clone(),
+                dec: ri.dec.clone(),
+                version: ri.assignment_version,
+            },
+        }
+    }
+}
+
+impl TryFrom<&dao::AuditEntry> for dto::AuditEntry {
+    type Error = sqlx::Error;
+
+    fn try_from(ri: &dao::AuditEntry) -> Result<Self, Self::Error> {
+        Ok(dto::AuditEntry {
+            id: ri.id,
+            actor_id: ri.actor_id,
+            actor_email: ri.actor_email.clone(),
+            entity: ri
+                .entity
+                .parse()
+                .map_err(|e: String| sqlx::Error::Decode(e.into()))?,
+            entity_id: ri.entity_id,
+            action: ri
+                .action
+                .parse()
+                .map_err(|e: String| sqlx::Error::Decode(e.into()))?,
+            before: ri.before.clone(),
+            after: ri.after.clone(),
+            changed_at: ri.changed_at,
+        })
+    }
+}
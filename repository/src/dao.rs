@@ -1,4 +1,10 @@
-use sqlx::{FromRow, types::BigDecimal};
+use sqlx::{
+    FromRow,
+    types::{
+        BigDecimal, Json, JsonValue,
+        chrono::{DateTime, NaiveDate, Utc},
+    },
+};
 use uuid::Uuid;
 
 #[derive(Debug, Clone, FromRow)]
@@ -8,57 +14,135 @@ pub struct Employee {
     pub last_name: String,
     pub email: String,
     pub salary: BigDecimal,
+    pub version: i32,
+    pub terminated_on: Option<NaiveDate>,
 }
 
 #[derive(Debug, Clone, FromRow)]
-pub(crate) struct Tag {
+pub struct Salary {
+    pub employee_id: Uuid,
+    pub effective_from: NaiveDate,
+    pub salary: BigDecimal,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct Role {
+    pub id: Uuid,
+    pub name: String,
+    pub scopes: Json<Vec<String>>,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct User {
+    pub id: Uuid,
+    pub first_name: String,
+    pub last_name: String,
+    pub email: String,
+    pub external_id: String,
+    pub external_id_source: String,
+    pub role_id: Option<Uuid>,
+    pub active: bool,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct Tag {
     pub id: Uuid,
     pub tag: String,
+    pub version: i32,
 }
 
 #[derive(Debug, Clone, FromRow)]
-pub(crate) struct Initiative {
+pub struct TagUsage {
+    pub id: Uuid,
+    pub tag: String,
+    pub assignment_count: i64,
+    pub employee_assignment_count: i64,
+    pub version: i32,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct Initiative {
     pub id: Uuid,
     pub initiative: String,
+    pub version: i32,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct Assignment {
+    pub id: Uuid,
+    pub year: i32,
+    pub jan: Option<BigDecimal>,
+    pub feb: Option<BigDecimal>,
+    pub mar: Option<BigDecimal>,
+    pub apr: Option<BigDecimal>,
+    pub may: Option<BigDecimal>,
+    pub jun: Option<BigDecimal>,
+    pub jul: Option<BigDecimal>,
+    pub aug: Option<BigDecimal>,
+    pub sep: Option<BigDecimal>,
+    pub oct: Option<BigDecimal>,
+    pub nov: Option<BigDecimal>,
+    pub dec: Option<BigDecimal>,
+    pub version: i32,
 }
 
 #[derive(Debug, Clone, FromRow)]
-pub(crate) struct Assignment {
+pub struct EmployeeAssignment {
     pub id: Uuid,
-    pub year: i64,
-    pub jan: Option<f64>,
-    pub feb: Option<f64>,
-    pub mar: Option<f64>,
-    pub apr: Option<f64>,
-    pub may: Option<f64>,
-    pub jun: Option<f64>,
-    pub jul: Option<f64>,
-    pub aug: Option<f64>,
-    pub sep: Option<f64>,
-    pub oct: Option<f64>,
-    pub nov: Option<f64>,
-    pub dec: Option<f64>,
+    pub employee_id: Uuid,
+    pub assignment_id: Uuid,
+    pub allocation: BigDecimal,
+    pub initiative_id: Uuid,
+    pub version: i32,
 }
 
 #[derive(Debug, Clone, FromRow)]
-pub(crate) struct EmployeeAssignment {
+pub struct ScheduledAssignment {
     pub id: Uuid,
     pub employee_id: Uuid,
     pub assignment_id: Uuid,
-    pub allocation: f64,
+    pub allocation: BigDecimal,
     pub initiative_id: Uuid,
+    pub year: i32,
+    pub jan: Option<BigDecimal>,
+    pub feb: Option<BigDecimal>,
+    pub mar: Option<BigDecimal>,
+    pub apr: Option<BigDecimal>,
+    pub may: Option<BigDecimal>,
+    pub jun: Option<BigDecimal>,
+    pub jul: Option<BigDecimal>,
+    pub aug: Option<BigDecimal>,
+    pub sep: Option<BigDecimal>,
+    pub oct: Option<BigDecimal>,
+    pub nov: Option<BigDecimal>,
+    pub dec: Option<BigDecimal>,
+    pub version: i32,
+    pub assignment_version: i32,
 }
 
 #[derive(Debug, Clone, FromRow)]
-pub(crate) struct AssignmentTag {
+pub struct AssignmentTag {
     pub id: Uuid,
     pub assignment_id: Uuid,
     pub tag_id: Uuid,
 }
 
 #[derive(Debug, Clone, FromRow)]
-pub(crate) struct EmployeeAssignmentTag {
+pub struct EmployeeAssignmentTag {
     pub id: Uuid,
     pub employee_assignment_id: Uuid,
     pub tag_id: Uuid,
 }
+
+#[derive(Debug, Clone, FromRow)]
+pub struct AuditEntry {
+    pub id: Uuid,
+    pub actor_id: Uuid,
+    pub actor_email: Option<String>,
+    pub entity: String,
+    pub entity_id: Uuid,
+    pub action: String,
+    pub before: Option<JsonValue>,
+    pub after: Option<JsonValue>,
+    pub changed_at: DateTime<Utc>,
+}
@@ -0,0 +1,253 @@
+use thiserror::Error;
+use uuid::Uuid;
+
+/// Postgres error code for `unique_violation`.
+const UNIQUE_VIOLATION: &str = "23505";
+/// Postgres error code for `foreign_key_violation`.
+const FOREIGN_KEY_VIOLATION: &str = "23503";
+/// Postgres error code for `check_violation`.
+const CHECK_VIOLATION: &str = "23514";
+
+#[derive(Debug, Error)]
+pub enum CreateEmployeeAssignmentError {
+    #[error("Employee {employee_id} is already assigned to initiative {initiative_id}: {source}")]
+    Duplicate {
+        employee_id: Uuid,
+        initiative_id: Uuid,
+        source: sqlx::Error,
+    },
+
+    #[error("Referenced employee, assignment or initiative does not exist: {source}")]
+    MissingReference { source: sqlx::Error },
+
+    #[error("Allocation must be between 0 and 1: {source}")]
+    InvalidAllocation { source: sqlx::Error },
+
+    /// Indexes (January is 0) of the months of `year` the employee would work above 100%.
+    #[error("Employee {employee_id} would be allocated above 100% in {year} for months {months:?}")]
+    Overallocated {
+        employee_id: Uuid,
+        year: i32,
+        months: Vec<usize>,
+    },
+
+    #[error("Failed to commit transactions: {source}")]
+    CommitFailed { source: sqlx::Error },
+
+    #[error("Unknown error for employee assignment: {source}")]
+    Unknown { source: sqlx::Error },
+}
+
+impl From<CreateEmployeeAssignmentError> for sqlx::Error {
+    fn from(error: CreateEmployeeAssignmentError) -> Self {
+        match error {
+            CreateEmployeeAssignmentError::Duplicate { source, .. } => source,
+            CreateEmployeeAssignmentError::MissingReference { source } => source,
+            CreateEmployeeAssignmentError::InvalidAllocation { source } => source,
+            CreateEmployeeAssignmentError::Overallocated { .. } => {
+                sqlx::Error::Protocol(error.to_string())
+            }
+            CreateEmployeeAssignmentError::CommitFailed { source } => source,
+            CreateEmployeeAssignmentError::Unknown { source } => source,
+        }
+    }
+}
+
+impl CreateEmployeeAssignmentError {
+    pub fn from_sqlx(error: sqlx::Error, employee_id: Uuid, initiative_id: Uuid) -> Self {
+        let code = error
+            .as_database_error()
+            .and_then(|e| e.code())
+            .map(|c| c.into_owned());
+        match code.as_deref() {
+            Some(UNIQUE_VIOLATION) => CreateEmployeeAssignmentError::Duplicate {
+                employee_id,
+                initiative_id,
+                source: error,
+            },
+            Some(FOREIGN_KEY_VIOLATION) => {
+                CreateEmployeeAssignmentError::MissingReference { source: error }
+            }
+            Some(CHECK_VIOLATION) => {
+                CreateEmployeeAssignmentError::InvalidAllocation { source: error }
+            }
+            _ => CreateEmployeeAssignmentError::Unknown { source: error },
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum UpdateEmployeeAssignmentError {
+    #[error("Employee assignment with id {id} not found")]
+    NotFound { id: Uuid },
+
+    #[error("Employee assignment with id {id} was changed by someone else")]
+    Stale { id: Uuid },
+
+    #[error("Employee {employee_id} is already assigned to initiative {initiative_id}: {source}")]
+    Duplicate {
+        employee_id: Uuid,
+        initiative_id: Uuid,
+        source: sqlx::Error,
+    },
+
+    #[error("Referenced assignment or initiative does not exist: {source}")]
+    MissingReference { source: sqlx::Error },
+
+    #[error("Allocation must be between 0 and 1: {source}")]
+    InvalidAllocation { source: sqlx::Error },
+
+    /// Indexes (January is 0) of the months of `year` the employee would work above 100%.
+    #[error("Employee {employee_id} would be allocated above 100% in {year} for months {months:?}")]
+    Overallocated {
+        employee_id: Uuid,
+        year: i32,
+        months: Vec<usize>,
+    },
+
+    #[error("Failed to commit transactions: {source}")]
+    CommitFailed { source: sqlx::Error },
+
+    #[error("Unknown error for employee assignment: {source}")]
+    Unknown { source: sqlx::Error },
+}
+
+impl From<UpdateEmployeeAssignmentError> for sqlx::Error {
+    fn from(error: UpdateEmployeeAssignmentError) -> Self {
+        match error {
+            UpdateEmployeeAssignmentError::NotFound { id: _ } => sqlx::Error::RowNotFound,
+            UpdateEmployeeAssignmentError::Stale { id: _ } => sqlx::Error::RowNotFound,
+            UpdateEmployeeAssignmentError::Duplicate { source, .. } => source,
+            UpdateEmployeeAssignmentError::MissingReference { source } => source,
+            UpdateEmployeeAssignmentError::InvalidAllocation { source } => source,
+            UpdateEmployeeAssignmentError::Overallocated { .. } => {
+                sqlx::Error::Protocol(error.to_string())
+            }
+            UpdateEmployeeAssignmentError::CommitFailed { source } => source,
+            UpdateEmployeeAssignmentError::Unknown { source } => source,
+        }
+    }
+}
+
+impl UpdateEmployeeAssignmentError {
+    pub fn from_sqlx(error: sqlx::Error, employee_id: Uuid, initiative_id: Uuid) -> Self {
+        let code = error
+            .as_database_error()
+            .and_then(|e| e.code())
+            .map(|c| c.into_owned());
+        match code.as_deref() {
+            Some(UNIQUE_VIOLATION) => UpdateEmployeeAssignmentError::Duplicate {
+                employee_id,
+                initiative_id,
+                source: error,
+            },
+            Some(FOREIGN_KEY_VIOLATION) => {
+                UpdateEmployeeAssignmentError::MissingReference { source: error }
+            }
+            Some(CHECK_VIOLATION) => {
+                UpdateEmployeeAssignmentError::InvalidAllocation { source: error }
+            }
+            _ => UpdateEmployeeAssignmentError::Unknown { source: error },
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum DeleteEmployeeAssignmentError {
+    #[error("Employee assignment with id {id} not found")]
+    NotFound { id: Uuid },
+
+    #[error("Employee assignment with id {id} was changed by someone else")]
+    Stale { id: Uuid },
+
+    #[error("Failed to commit transactions: {source}")]
+    CommitFailed { source: sqlx::Error },
+
+    #[error("Unknown error for employee assignment: {source}")]
+    Unknown { source: sqlx::Error },
+}
+
+impl From<DeleteEmployeeAssignmentError> for sqlx::Error {
+    fn from(error: DeleteEmployeeAssignmentError) -> Self {
+        match error {
+            DeleteEmployeeAssignmentError::NotFound { id: _ } => sqlx::Error::RowNotFound,
+            DeleteEmployeeAssignmentError::Stale { id: _ } => sqlx::Error::RowNotFound,
+            DeleteEmployeeAssignmentError::CommitFailed { source } => source,
+            DeleteEmployeeAssignmentError::Unknown { source } => source,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum SavePlanError {
+    #[error("Assignment with id {id} was changed by someone else")]
+    StaleAssignment { id: Uuid },
+
+    #[error("Employee assignment with id {id} was changed by someone else")]
+    StaleLink { id: Uuid },
+
+    /// Indexes (January is 0) of the months of `year` the employee would work above 100%.
+    #[error("Employee {employee_id} would be allocated above 100% in {year} for months {months:?}")]
+    Overallocated {
+        employee_id: Uuid,
+        year: i32,
+        months: Vec<usize>,
+    },
+
+    #[error("Employee {employee_id} is already assigned to initiative {initiative_id}: {source}")]
+    Duplicate {
+        employee_id: Uuid,
+        initiative_id: Uuid,
+        source: sqlx::Error,
+    },
+
+    #[error("Failed to commit transactions: {source}")]
+    CommitFailed { source: sqlx::Error },
+
+    #[error("Unknown error for allocation plan: {source}")]
+    Unknown { source: sqlx::Error },
+}
+
+impl From<SavePlanError> for sqlx::Error {
+    fn from(error: SavePlanError) -> Self {
+        match error {
+            SavePlanError::StaleAssignment { id: _ } => sqlx::Error::RowNotFound,
+            SavePlanError::StaleLink { id: _ } => sqlx::Error::RowNotFound,
+            SavePlanError::Overallocated { .. } => sqlx::Error::Protocol(error.to_string()),
+            SavePlanError::Duplicate { source, .. } => source,
+            SavePlanError::CommitFailed { source } => source,
+            SavePlanError::Unknown { source } => source,
+        }
+    }
+}
+
+impl SavePlanError {
+    pub fn from_sqlx(error: sqlx::Error, employee_id: Uuid, initiative_id: Uuid) -> Self {
+        let code = error
+            .as_database_error()
+            .and_then(|e| e.code())
+            .map(|c| c.into_owned());
+        match code.as_deref() {
+            Some(UNIQUE_VIOLATION) => SavePlanError::Duplicate {
+                employee_id,
+                initiative_id,
+                source: error,
+            },
+            _ => SavePlanError::Unknown { source: error },
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum GetEmployeeAssignmentsError {
+    #[error("Unknown error for employee assignments: {source}")]
+    Unknown { source: sqlx::Error },
+}
+
+impl From<GetEmployeeAssignmentsError> for sqlx::Error {
+    fn from(error: GetEmployeeAssignmentsError) -> Self {
+        match error {
+            GetEmployeeAssignmentsError::Unknown { source } => source,
+        }
+    }
+}
@@ -0,0 +1,1036 @@
+use crate::audit_repo::{record, snapshot};
+use crate::dao;
+use crate::employee_assignment_errors::*;
+use crate::in_memory_db::{
+    InMemoryDb, check_violation, foreign_key_violation, months_over_capacity, unique_violation,
+};
+use crate::postgres_db::PostgresDb;
+use bigdecimal::{BigDecimal, One, Zero};
+use domain::dto;
+use sqlx::{PgConnection, PgExecutor};
+use std::collections::{BTreeSet, HashMap};
+use std::future::Future;
+use uuid::Uuid;
+
+pub trait EmployeeAssignmentRepo: Send + Sync + Clone + 'static {
+    /// Creates the link unless it puts the employee above 100% in a month of its curve's year.
+    /// The capacity check runs in the same transaction as the write, so concurrent writes for
+    /// one employee cannot both pass it.
+    fn create(
+        &self,
+        actor: Uuid,
+        ri: &dto::EmployeeAssignment,
+    ) -> impl Future<Output = Result<dto::EmployeeAssignment, CreateEmployeeAssignmentError>> + Send;
+    /// Updates the allocation, assignment and initiative of a link owned by `ri.employee_id`
+    /// if it is still at `ri.version`, bumping the version. Like `create`, fails with nothing
+    /// stored if the employee would then be allocated above 100% in a month.
+    fn update(
+        &self,
+        actor: Uuid,
+        ri: &dto::EmployeeAssignment,
+    ) -> impl Future<Output = Result<dto::EmployeeAssignment, UpdateEmployeeAssignmentError>> + Send;
+    /// Deletes the link if it is owned by `employee_id` and still at `version`.
+    fn delete(
+        &self,
+        actor: Uuid,
+        employee_id: Uuid,
+        id: Uuid,
+        version: i32,
+    ) -> impl Future<Output = Result<(), DeleteEmployeeAssignmentError>> + Send;
+    fn get_for_employee(
+        &self,
+        employee_id: Uuid,
+    ) -> impl Future<Output = Result<Vec<dto::EmployeeAssignment>, GetEmployeeAssignmentsError>> + Send;
+    fn get_for_initiative(
+        &self,
+        initiative_id: Uuid,
+    ) -> impl Future<Output = Result<Vec<dto::EmployeeAssignment>, GetEmployeeAssignmentsError>> + Send;
+    fn get_for_assignment(
+        &self,
+        assignment_id: Uuid,
+    ) -> impl Future<Output = Result<Vec<dto::EmployeeAssignment>, GetEmployeeAssignmentsError>> + Send;
+    /// Returns every link whose monthly curve falls in `year`, optionally for a single employee.
+    fn get_scheduled(
+        &self,
+        year: i32,
+        employee_id: Option<Uuid>,
+    ) -> impl Future<Output = Result<Vec<dto::ScheduledAssignment>, GetEmployeeAssignmentsError>> + Send;
+    /// Like `get_scheduled`, but only for the links of `employee_ids`.
+    fn get_scheduled_for(
+        &self,
+        year: i32,
+        employee_ids: &[Uuid],
+    ) -> impl Future<Output = Result<Vec<dto::ScheduledAssignment>, GetEmployeeAssignmentsError>> + Send;
+    /// Returns `(employee_id, initiative_id, year)` of every link whose curve falls outside `year`.
+    fn get_planned_elsewhere(
+        &self,
+        year: i32,
+    ) -> impl Future<Output = Result<Vec<(Uuid, Uuid, i32)>, GetEmployeeAssignmentsError>> + Send;
+    /// Stores every link of an imported plan and the curve it follows in one transaction, or
+    /// nothing if any link or curve changed since it was read, a curve rewritten in place gained
+    /// another follower, or an employee would end up above 100% in a month.
+    fn save_plan(
+        &self,
+        actor: Uuid,
+        links: &[dto::PlannedLink],
+    ) -> impl Future<Output = Result<(), SavePlanError>> + Send;
+}
+
+impl EmployeeAssignmentRepo for PostgresDb {
+    async fn create(
+        &self,
+        actor: Uuid,
+        ri: &dto::EmployeeAssignment,
+    ) -> Result<dto::EmployeeAssignment, CreateEmployeeAssignmentError> {
+        let mut tx = self.pool.begin().await.map_err(|e| {
+            CreateEmployeeAssignmentError::from_sqlx(e, ri.employee_id, ri.initiative_id)
+        })?;
+        let Some(year) = lock_curve(&mut tx, ri.assignment_id)
+            .await
+            .map_err(|e| CreateEmployeeAssignmentError::Unknown { source: e })?
+        else {
+            return Err(CreateEmployeeAssignmentError::MissingReference {
+                source: sqlx::Error::RowNotFound,
+            });
+        };
+        lock_employees(&mut tx, [ri.employee_id])
+            .await
+            .map_err(|e| CreateEmployeeAssignmentError::Unknown { source: e })?;
+        let ret = sqlx::query_as!(
+            dao::EmployeeAssignment,
+            r#"INSERT INTO employee_assignments(employee_id, assignment_id, allocation, initiative_id)
+               VALUES ($1, $2, $3, $4) RETURNING *"#,
+            ri.employee_id,
+            ri.assignment_id,
+            ri.allocation,
+            ri.initiative_id,
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| CreateEmployeeAssignmentError::from_sqlx(e, ri.employee_id, ri.initiative_id))?;
+        let months = overallocated_months(&mut tx, ri.employee_id, year)
+            .await
+            .map_err(|e| CreateEmployeeAssignmentError::Unknown { source: e })?;
+        if !months.is_empty() {
+            return Err(CreateEmployeeAssignmentError::Overallocated {
+                employee_id: ri.employee_id,
+                year,
+                months,
+            });
+        }
+        let after = snapshot(&mut tx, dto::AuditEntity::EmployeeAssignment, ret.id)
+            .await
+            .map_err(|e| CreateEmployeeAssignmentError::Unknown { source: e })?;
+        record(
+            &mut tx,
+            actor,
+            dto::AuditEntity::EmployeeAssignment,
+            ret.id,
+            dto::AuditAction::Create,
+            None,
+            after,
+        )
+        .await
+        .map_err(|e| CreateEmployeeAssignmentError::Unknown { source: e })?;
+        tx.commit()
+            .await
+            .map_err(|e| CreateEmployeeAssignmentError::CommitFailed { source: e })?;
+        Ok((&ret).into())
+    }
+    async fn update(
+        &self,
+        actor: Uuid,
+        ri: &dto::EmployeeAssignment,
+    ) -> Result<dto::EmployeeAssignment, UpdateEmployeeAssignmentError> {
+        let mut tx = self.pool.begin().await.map_err(|e| {
+            UpdateEmployeeAssignmentError::from_sqlx(e, ri.employee_id, ri.initiative_id)
+        })?;
+        let Some(year) = lock_curve(&mut tx, ri.assignment_id)
+            .await
+            .map_err(|e| UpdateEmployeeAssignmentError::Unknown { source: e })?
+        else {
+            return Err(UpdateEmployeeAssignmentError::MissingReference {
+                source: sqlx::Error::RowNotFound,
+            });
+        };
+        lock_employees(&mut tx, [ri.employee_id])
+            .await
+            .map_err(|e| UpdateEmployeeAssignmentError::Unknown { source: e })?;
+        let before = snapshot(&mut tx, dto::AuditEntity::EmployeeAssignment, ri.id)
+            .await
+            .map_err(|e| UpdateEmployeeAssignmentError::Unknown { source: e })?;
+        let ret = sqlx::query_as!(
+            dao::EmployeeAssignment,
+            r#"UPDATE employee_assignments SET
+                assignment_id = $1,
+                allocation = $2,
+                initiative_id = $3,
+                version = version + 1
+               WHERE id = $4 AND employee_id = $5 AND version = $6 RETURNING *"#,
+            ri.assignment_id,
+            ri.allocation,
+            ri.initiative_id,
+            ri.id,
+            ri.employee_id,
+            ri.version,
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| {
+            UpdateEmployeeAssignmentError::from_sqlx(e, ri.employee_id, ri.initiative_id)
+        })?;
+        let Some(ret) = ret else {
+            return Err(
+                if link_exists(&mut *tx, ri.employee_id, ri.id)
+                    .await
+                    .map_err(|e| UpdateEmployeeAssignmentError::Unknown { source: e })?
+                {
+                    UpdateEmployeeAssignmentError::Stale { id: ri.id }
+                } else {
+                    UpdateEmployeeAssignmentError::NotFound { id: ri.id }
+                },
+            );
+        };
+        let months = overallocated_months(&mut tx, ri.employee_id, year)
+            .await
+            .map_err(|e| UpdateEmployeeAssignmentError::Unknown { source: e })?;
+        if !months.is_empty() {
+            return Err(UpdateEmployeeAssignmentError::Overallocated {
+                employee_id: ri.employee_id,
+                year,
+                months,
+            });
+        }
+        let after = snapshot(&mut tx, dto::AuditEntity::EmployeeAssignment, ri.id)
+            .await
+            .map_err(|e| UpdateEmployeeAssignmentError::Unknown { source: e })?;
+        record(
+            &mut tx,
+            actor,
+            dto::AuditEntity::EmployeeAssignment,
+            ri.id,
+            dto::AuditAction::Update,
+            before,
+            after,
+        )
+        .await
+        .map_err(|e| UpdateEmployeeAssignmentError::Unknown { source: e })?;
+        tx.commit()
+            .await
+            .map_err(|e| UpdateEmployeeAssignmentError::CommitFailed { source: e })?;
+        Ok((&ret).into())
+    }
+    async fn delete(
+        &self,
+        actor: Uuid,
+        employee_id: Uuid,
+        id: Uuid,
+        version: i32,
+    ) -> Result<(), DeleteEmployeeAssignmentError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| DeleteEmployeeAssignmentError::Unknown { source: e })?;
+        let before = snapshot(&mut tx, dto::AuditEntity::EmployeeAssignment, id)
+            .await
+            .map_err(|e| DeleteEmployeeAssignmentError::Unknown { source: e })?;
+        let deleted = sqlx::query!(
+            "DELETE FROM employee_assignments WHERE id = $1 AND employee_id = $2 AND version = $3",
+            id,
+            employee_id,
+            version
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| DeleteEmployeeAssignmentError::Unknown { source: e })?;
+        if deleted.rows_affected() == 0 {
+            return Err(
+                if link_exists(&mut *tx, employee_id, id)
+                    .await
+                    .map_err(|e| DeleteEmployeeAssignmentError::Unknown { source: e })?
+                {
+                    DeleteEmployeeAssignmentError::Stale { id }
+                } else {
+                    DeleteEmployeeAssignmentError::NotFound { id }
+                },
+            );
+        }
+        record(
+            &mut tx,
+            actor,
+            dto::AuditEntity::EmployeeAssignment,
+            id,
+            dto::AuditAction::Delete,
+            before,
+            None,
+        )
+        .await
+        .map_err(|e| DeleteEmployeeAssignmentError::Unknown { source: e })?;
+        tx.commit()
+            .await
+            .map_err(|e| DeleteEmployeeAssignmentError::CommitFailed { source: e })?;
+        Ok(())
+    }
+    async fn get_for_employee(
+        &self,
+        employee_id: Uuid,
+    ) -> Result<Vec<dto::EmployeeAssignment>, GetEmployeeAssignmentsError> {
+        let ris = sqlx::query_as!(
+            dao::EmployeeAssignment,
+            r#"SELECT id, employee_id, assignment_id, allocation, initiative_id, version
+               FROM employee_assignments WHERE employee_id = $1 ORDER BY id"#,
+            employee_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| GetEmployeeAssignmentsError::Unknown { source: e })?;
+        Ok(ris.into_iter().map(|x| (&x).into()).collect())
+    }
+    async fn get_for_initiative(
+        &self,
+        initiative_id: Uuid,
+    ) -> Result<Vec<dto::EmployeeAssignment>, GetEmployeeAssignmentsError> {
+        let ris = sqlx::query_as!(
+            dao::EmployeeAssignment,
+            r#"SELECT id, employee_id, assignment_id, allocation, initiative_id, version
+               FROM employee_assignments WHERE initiative_id = $1 ORDER BY id"#,
+            initiative_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| GetEmployeeAssignmentsError::Unknown { source: e })?;
+        Ok(ris.into_iter().map(|x| (&x).into()).collect())
+    }
+    async fn get_for_assignment(
+        &self,
+        assignment_id: Uuid,
+    ) -> Result<Vec<dto::EmployeeAssignment>, GetEmployeeAssignmentsError> {
+        let ris = sqlx::query_as!(
+            dao::EmployeeAssignment,
+            r#"SELECT id, employee_id, assignment_id, allocation, initiative_id, version
+               FROM employee_assignments WHERE assignment_id = $1 ORDER BY id"#,
+            assignment_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| GetEmployeeAssignmentsError::Unknown { source: e })?;
+        Ok(ris.into_iter().map(|x| (&x).into()).collect())
+    }
+    async fn get_scheduled(
+        &self,
+        year: i32,
+        employee_id: Option<Uuid>,
+    ) -> Result<Vec<dto::ScheduledAssignment>, GetEmployeeAssignmentsError> {
+        let ris = sqlx::query_as!(
+            dao::ScheduledAssignment,
+            r#"SELECT ea.id, ea.employee_id, ea.assignment_id, ea.allocation, ea.initiative_id, ea.version,
+                a.version AS assignment_version, a.year, a.jan, a.feb, a.mar, a.apr, a.may, a.jun, a.jul, a.aug, a.sep, a.oct, a.nov, a."dec"
+               FROM employee_assignments ea
+               JOIN assignments a ON a.id = ea.assignment_id
+               WHERE a.year = $1 AND ($2::uuid IS NULL OR ea.employee_id = $2)
+               ORDER BY ea.employee_id, ea.id"#,
+            year,
+            employee_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| GetEmployeeAssignmentsError::Unknown { source: e })?;
+        Ok(ris.into_iter().map(|x| (&x).into()).collect())
+    }
+    async fn get_scheduled_for(
+        &self,
+        year: i32,
+        employee_ids: &[Uuid],
+    ) -> Result<Vec<dto::ScheduledAssignment>, GetEmployeeAssignmentsError> {
+        let ris = sqlx::query_as!(
+            dao::ScheduledAssignment,
+            r#"SELECT ea.id, ea.employee_id, ea.assignment_id, ea.allocation, ea.initiative_id, ea.version,
+                a.version AS assignment_version, a.year, a.jan, a.feb, a.mar, a.apr, a.may, a.jun, a.jul, a.aug, a.sep, a.oct, a.nov, a."dec"
+               FROM employee_assignments ea
+               JOIN assignments a ON a.id = ea.assignment_id
+               WHERE a.year = $1 AND ea.employee_id = ANY($2)
+               ORDER BY ea.employee_id, ea.id"#,
+            year,
+            employee_ids
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| GetEmployeeAssignmentsError::Unknown { source: e })?;
+        Ok(ris.into_iter().map(|x| (&x).into()).collect())
+    }
+    async fn get_planned_elsewhere(
+        &self,
+        year: i32,
+    ) -> Result<Vec<(Uuid, Uuid, i32)>, GetEmployeeAssignmentsError> {
+        let ris = sqlx::query!(
+            r#"SELECT ea.employee_id, ea.initiative_id, a.year
+               FROM employee_assignments ea
+               JOIN assignments a ON a.id = ea.assignment_id
+               WHERE a.year <> $1
+               ORDER BY ea.employee_id, ea.initiative_id"#,
+            year
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| GetEmployeeAssignmentsError::Unknown { source: e })?;
+        Ok(ris
+            .into_iter()
+            .map(|x| (x.employee_id, x.initiative_id, x.year))
+            .collect())
+    }
+    async fn save_plan(
+        &self,
+        actor: Uuid,
+        links: &[dto::PlannedLink],
+    ) -> Result<(), SavePlanError> {
+        let unknown = |e| SavePlanError::Unknown { source: e };
+        let mut tx = self.pool.begin().await.map_err(unknown)?;
+        let mut reused: Vec<&dto::PlannedLink> =
+            links.iter().filter(|x| !x.assignment.id.is_nil()).collect();
+        reused.sort_by_key(|x| x.assignment.id);
+        for ri in reused {
+            let link_id = ri.link.as_ref().map(|x| x.id);
+            if lock_curve_followers(&mut tx, ri.assignment.id, link_id)
+                .await
+                .map_err(unknown)?
+                > 0
+            {
+                return Err(SavePlanError::StaleAssignment {
+                    id: ri.assignment.id,
+                });
+            }
+        }
+        lock_employees(&mut tx, links.iter().map(|x| x.employee_id))
+            .await
+            .map_err(unknown)?;
+        for link in links {
+            let assignment_id = save_curve(&mut tx, actor, &link.assignment).await?;
+            save_link(&mut tx, actor, link, assignment_id).await?;
+        }
+        let checks: BTreeSet<(Uuid, i32)> = links
+            .iter()
+            .map(|x| (x.employee_id, x.assignment.year))
+            .collect();
+        for (employee_id, year) in checks {
+            let months = overallocated_months(&mut tx, employee_id, year)
+                .await
+                .map_err(unknown)?;
+            if !months.is_empty() {
+                return Err(SavePlanError::Overallocated {
+                    employee_id,
+                    year,
+                    months,
+                });
+            }
+        }
+        tx.commit()
+            .await
+            .map_err(|e| SavePlanError::CommitFailed { source: e })
+    }
+}
+
+/// Inserts `ri` as a new curve, or rewrites it in place if it is still at its version.
+async fn save_curve(
+    conn: &mut PgConnection,
+    actor: Uuid,
+    ri: &dto::Assignment,
+) -> Result<Uuid, SavePlanError> {
+    let unknown = |e| SavePlanError::Unknown { source: e };
+    let (before, id) = if ri.id.is_nil() {
+        let id = sqlx::query_scalar!(
+            r#"INSERT INTO assignments(year, jan, feb, mar, apr, may, jun, jul, aug, sep, oct, nov, "dec")
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13) RETURNING id"#,
+            ri.year,
+            ri.jan,
+            ri.feb,
+            ri.mar,
+            ri.apr,
+            ri.may,
+            ri.jun,
+            ri.jul,
+            ri.aug,
+            ri.sep,
+            ri.oct,
+            ri.nov,
+            ri.dec,
+        )
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(unknown)?;
+        (None, id)
+    } else {
+        let before = snapshot(&mut *conn, dto::AuditEntity::Assignment, ri.id)
+            .await
+            .map_err(unknown)?;
+        sqlx::query_scalar!(
+            r#"UPDATE assignments SET
+                jan = $1, feb = $2, mar = $3, apr = $4, may = $5, jun = $6,
+                jul = $7, aug = $8, sep = $9, oct = $10, nov = $11, "dec" = $12,
+                version = version + 1
+               WHERE id = $13 AND year = $14 AND version = $15 RETURNING id"#,
+            ri.jan,
+            ri.feb,
+            ri.mar,
+            ri.apr,
+            ri.may,
+            ri.jun,
+            ri.jul,
+            ri.aug,
+            ri.sep,
+            ri.oct,
+            ri.nov,
+            ri.dec,
+            ri.id,
+            ri.year,
+            ri.version,
+        )
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(unknown)?
+        .ok_or(SavePlanError::StaleAssignment { id: ri.id })?;
+        (before, ri.id)
+    };
+    let after = snapshot(&mut *conn, dto::AuditEntity::Assignment, id)
+        .await
+        .map_err(unknown)?;
+    let action = if before.is_some() {
+        dto::AuditAction::Update
+    } else {
+        dto::AuditAction::Create
+    };
+    record(
+        &mut *conn,
+        actor,
+        dto::AuditEntity::Assignment,
+        id,
+        action,
+        before,
+        after,
+    )
+    .await
+    .map_err(unknown)?;
+    Ok(id)
+}
+
+/// Creates the link of `ri` following `assignment_id`, or repoints the existing one if it is
+/// still at its version.
+async fn save_link(
+    conn: &mut PgConnection,
+    actor: Uuid,
+    ri: &dto::PlannedLink,
+    assignment_id: Uuid,
+) -> Result<(), SavePlanError> {
+    let unknown = |e| SavePlanError::Unknown { source: e };
+    let (before, id) = match &ri.link {
+        None => {
+            let id = sqlx::query_scalar!(
+                r#"INSERT INTO employee_assignments(employee_id, assignment_id, allocation, initiative_id)
+                   VALUES ($1, $2, $3, $4) RETURNING id"#,
+                ri.employee_id,
+                assignment_id,
+                ri.allocation,
+                ri.initiative_id,
+            )
+            .fetch_one(&mut *conn)
+            .await
+            .map_err(|e| SavePlanError::from_sqlx(e, ri.employee_id, ri.initiative_id))?;
+            (None, id)
+        }
+        Some(link) => {
+            let before = snapshot(&mut *conn, dto::AuditEntity::EmployeeAssignment, link.id)
+                .await
+                .map_err(unknown)?;
+            sqlx::query_scalar!(
+                r#"UPDATE employee_assignments SET
+                    assignment_id = $1,
+                    allocation = $2,
+                    version = version + 1
+                   WHERE id = $3 AND version = $4 RETURNING id"#,
+                assignment_id,
+                ri.allocation,
+                link.id,
+                link.version,
+            )
+            .fetch_optional(&mut *conn)
+            .await
+            .map_err(unknown)?
+            .ok_or(SavePlanError::StaleLink { id: link.id })?;
+            (before, link.id)
+        }
+    };
+    let after = snapshot(&mut *conn, dto::AuditEntity::EmployeeAssignment, id)
+        .await
+        .map_err(unknown)?;
+    let action = if before.is_some() {
+        dto::AuditAction::Update
+    } else {
+        dto::AuditAction::Create
+    };
+    record(
+        &mut *conn,
+        actor,
+        dto::AuditEntity::EmployeeAssignment,
+        id,
+        action,
+        before,
+        after,
+    )
+    .await
+    .map_err(unknown)
+}
+
+/// Whether link `id` exists and belongs to `employee_id`, so a failed conditional write can
+/// tell a missing link from a stale one.
+async fn link_exists<'e>(
+    executor: impl PgExecutor<'e>,
+    employee_id: Uuid,
+    id: Uuid,
+) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar!(
+        r#"SELECT EXISTS(SELECT 1 FROM employee_assignments WHERE id = $1 AND employee_id = $2) AS "exists!""#,
+        id,
+        employee_id
+    )
+    .fetch_one(executor)
+    .await
+}
+
+/// Returns the year of curve `assignment_id` and keeps it from changing until the transaction
+/// ends, so a link written to it is checked against the fractions it will keep.
+async fn lock_curve(
+    conn: &mut PgConnection,
+    assignment_id: Uuid,
+) -> Result<Option<i32>, sqlx::Error> {
+    sqlx::query_scalar!(
+        "SELECT year FROM assignments WHERE id = $1 FOR SHARE",
+        assignment_id
+    )
+    .fetch_optional(conn)
+    .await
+}
+
+/// Keeps curve `assignment_id` from changing or gaining links until the transaction ends, and
+/// counts the links other than `link_id` that follow it. A curve rewritten in place must have
+/// none, since links do not bump the version of the curve they start following.
+async fn lock_curve_followers(
+    conn: &mut PgConnection,
+    assignment_id: Uuid,
+    link_id: Option<Uuid>,
+) -> Result<i64, sqlx::Error> {
+    sqlx::query!(
+        "SELECT id FROM assignments WHERE id = $1 FOR UPDATE",
+        assignment_id
+    )
+    .fetch_optional(&mut *conn)
+    .await?;
+    sqlx::query_scalar!(
+        r#"SELECT COUNT(*) AS "count!" FROM employee_assignments
+           WHERE assignment_id = $1 AND id IS DISTINCT FROM $2"#,
+        assignment_id,
+        link_id
+    )
+    .fetch_one(conn)
+    .await
+}
+
+/// Makes every other write to the allocations of `employee_ids` wait until the transaction ends,
+/// so a capacity check sees the rows the write it guards will commit alongside. Curves are
+/// always locked before employees, and employees in id order, so writers never deadlock.
+pub(crate) async fn lock_employees(
+    conn: &mut PgConnection,
+    employee_ids: impl IntoIterator<Item = Uuid>,
+) -> Result<(), sqlx::Error> {
+    let employee_ids: BTreeSet<Uuid> = employee_ids.into_iter().collect();
+    for employee_id in employee_ids {
+        sqlx::query!(
+            "SELECT pg_advisory_xact_lock(hashtextextended($1::uuid::text, 0))",
+            employee_id
+        )
+        .execute(&mut *conn)
+        .await?;
+    }
+    Ok(())
+}
+
+/// Indexes (January is 0) of the months of `year` in which the links of `employee_id` add up to
+/// more than one whole person.
+pub(crate) async fn overallocated_months(
+    conn: &mut PgConnection,
+    employee_id: Uuid,
+    year: i32,
+) -> Result<Vec<usize>, sqlx::Error> {
+    let months = sqlx::query_scalar!(
+        r#"SELECT m.month - 1 AS "month!"
+           FROM employee_assignments ea
+           JOIN assignments a ON a.id = ea.assignment_id
+           CROSS JOIN LATERAL unnest(ARRAY[a.jan, a.feb, a.mar, a.apr, a.may, a.jun, a.jul, a.aug, a.sep, a.oct, a.nov, a."dec"])
+               WITH ORDINALITY AS m(fraction, month)
+           WHERE ea.employee_id = $1 AND a.year = $2
+           GROUP BY m.month
+           HAVING SUM(ea.allocation * m.fraction) > 1
+           ORDER BY m.month"#,
+        employee_id,
+        year
+    )
+    .fetch_all(conn)
+    .await?;
+    Ok(months.into_iter().map(|x| x as usize).collect())
+}
+
+impl EmployeeAssignmentRepo for InMemoryDb {
+    async fn create(
+        &self,
+        _actor: Uuid,
+        ri: &dto::EmployeeAssignment,
+    ) -> Result<dto::EmployeeAssignment, CreateEmployeeAssignmentError> {
+        let employees = self.employees.read().expect("employees lock poisoned");
+        let initiatives = self.initiatives.read().expect("initiatives lock poisoned");
+        let assignments = self.assignments.read().expect("assignments lock poisoned");
+        let mut links = self
+            .employee_assignments
+            .write()
+            .expect("employee assignments lock poisoned");
+        let link = dto::EmployeeAssignment {
+            id: Uuid::now_v7(),
+            version: 1,
+            ..ri.clone()
+        };
+        check_link(&employees, &initiatives, &assignments, &links, &link).map_err(|e| match e {
+            LinkViolation::Duplicate(source) => CreateEmployeeAssignmentError::Duplicate {
+                employee_id: ri.employee_id,
+                initiative_id: ri.initiative_id,
+                source,
+            },
+            LinkViolation::MissingReference(source) => {
+                CreateEmployeeAssignmentError::MissingReference { source }
+            }
+            LinkViolation::InvalidAllocation(source) => {
+                CreateEmployeeAssignmentError::InvalidAllocation { source }
+            }
+        })?;
+        let year = assignments[&link.assignment_id].year;
+        links.insert(link.id, link.clone());
+        let months = months_over_capacity(&links, &assignments, link.employee_id, year);
+        if !months.is_empty() {
+            links.remove(&link.id);
+            return Err(CreateEmployeeAssignmentError::Overallocated {
+                employee_id: link.employee_id,
+                year,
+                months,
+            });
+        }
+        Ok(link)
+    }
+    async fn update(
+        &self,
+        _actor: Uuid,
+        ri: &dto::EmployeeAssignment,
+    ) -> Result<dto::EmployeeAssignment, UpdateEmployeeAssignmentError> {
+        let employees = self.employees.read().expect("employees lock poisoned");
+        let initiatives = self.initiatives.read().expect("initiatives lock poisoned");
+        let assignments = self.assignments.read().expect("assignments lock poisoned");
+        let mut links = self
+            .employee_assignments
+            .write()
+            .expect("employee assignments lock poisoned");
+        match links.get(&ri.id) {
+            Some(current) if current.employee_id == ri.employee_id => {
+                if current.version != ri.version {
+                    return Err(UpdateEmployeeAssignmentError::Stale { id: ri.id });
+                }
+            }
+            _ => return Err(UpdateEmployeeAssignmentError::NotFound { id: ri.id }),
+        }
+        let link = dto::EmployeeAssignment {
+            version: ri.version + 1,
+            ..ri.clone()
+        };
+        let before = links.remove(&ri.id).expect("link checked above");
+        if let Err(e) = check_link(&employees, &initiatives, &assignments, &links, &link) {
+            links.insert(before.id, before);
+            return Err(match e {
+                LinkViolation::Duplicate(source) => UpdateEmployeeAssignmentError::Duplicate {
+                    employee_id: ri.employee_id,
+                    initiative_id: ri.initiative_id,
+                    source,
+                },
+                LinkViolation::MissingReference(source) => {
+                    UpdateEmployeeAssignmentError::MissingReference { source }
+                }
+                LinkViolation::InvalidAllocation(source) => {
+                    UpdateEmployeeAssignmentError::InvalidAllocation { source }
+                }
+            });
+        }
+        let year = assignments[&link.assignment_id].year;
+        links.insert(link.id, link.clone());
+        let months = months_over_capacity(&links, &assignments, link.employee_id, year);
+        if !months.is_empty() {
+            links.insert(before.id, before);
+            return Err(UpdateEmployeeAssignmentError::Overallocated {
+                employee_id: link.employee_id,
+                year,
+                months,
+            });
+        }
+        Ok(link)
+    }
+    async fn delete(
+        &self,
+        _actor: Uuid,
+        employee_id: Uuid,
+        id: Uuid,
+        version: i32,
+    ) -> Result<(), DeleteEmployeeAssignmentError> {
+        let mut links = self
+            .employee_assignments
+            .write()
+            .expect("employee assignments lock poisoned");
+        match links.get(&id) {
+            Some(current) if current.employee_id == employee_id => {
+                if current.version != version {
+                    return Err(DeleteEmployeeAssignmentError::Stale { id });
+                }
+            }
+            _ => return Err(DeleteEmployeeAssignmentError::NotFound { id }),
+        }
+        links.remove(&id);
+        self.employee_assignment_tags
+            .write()
+            .expect("employee assignment tags lock poisoned")
+            .retain(|&(link_id, _)| link_id != id);
+        Ok(())
+    }
+    async fn get_for_employee(
+        &self,
+        employee_id: Uuid,
+    ) -> Result<Vec<dto::EmployeeAssignment>, GetEmployeeAssignmentsError> {
+        Ok(self.links_where(|x| x.employee_id == employee_id))
+    }
+    async fn get_for_initiative(
+        &self,
+        initiative_id: Uuid,
+    ) -> Result<Vec<dto::EmployeeAssignment>, GetEmployeeAssignmentsError> {
+        Ok(self.links_where(|x| x.initiative_id == initiative_id))
+    }
+    async fn get_for_assignment(
+        &self,
+        assignment_id: Uuid,
+    ) -> Result<Vec<dto::EmployeeAssignment>, GetEmployeeAssignmentsError> {
+        Ok(self.links_where(|x| x.assignment_id == assignment_id))
+    }
+    async fn get_scheduled(
+        &self,
+        year: i32,
+        employee_id: Option<Uuid>,
+    ) -> Result<Vec<dto::ScheduledAssignment>, GetEmployeeAssignmentsError> {
+        let assignments = self.assignments.read().expect("assignments lock poisoned");
+        let links = self
+            .employee_assignments
+            .read()
+            .expect("employee assignments lock poisoned");
+        let mut ris: Vec<dto::ScheduledAssignment> = links
+            .values()
+            .filter(|x| employee_id.is_none_or(|id| x.employee_id == id))
+            .filter_map(|x| {
+                let assignment = assignments.get(&x.assignment_id)?;
+                (assignment.year == year).then(|| dto::ScheduledAssignment {
+                    employee_assignment: x.clone(),
+                    assignment: assignment.clone(),
+                })
+            })
+            .collect();
+        ris.sort_by_key(|x| (x.employee_assignment.employee_id, x.employee_assignment.id));
+        Ok(ris)
+    }
+    async fn get_scheduled_for(
+        &self,
+        year: i32,
+        employee_ids: &[Uuid],
+    ) -> Result<Vec<dto::ScheduledAssignment>, GetEmployeeAssignmentsError> {
+        let mut ris = self.get_scheduled(year, None).await?;
+        ris.retain(|x| employee_ids.contains(&x.employee_assignment.employee_id));
+        Ok(ris)
+    }
+    async fn get_planned_elsewhere(
+        &self,
+        year: i32,
+    ) -> Result<Vec<(Uuid, Uuid, i32)>, GetEmployeeAssignmentsError> {
+        let assignments = self.assignments.read().expect("assignments lock poisoned");
+        let mut ris: Vec<(Uuid, Uuid, i32)> = self
+            .employee_assignments
+            .read()
+            .expect("employee assignments lock poisoned")
+            .values()
+            .filter_map(|x| {
+                let curve = assignments.get(&x.assignment_id)?;
+                (curve.year != year).then_some((x.employee_id, x.initiative_id, curve.year))
+            })
+            .collect();
+        ris.sort();
+        Ok(ris)
+    }
+    async fn save_plan(
+        &self,
+        _actor: Uuid,
+        planned: &[dto::PlannedLink],
+    ) -> Result<(), SavePlanError> {
+        let mut assignments = self.assignments.write().expect("assignments lock poisoned");
+        let mut links = self
+            .employee_assignments
+            .write()
+            .expect("employee assignments lock poisoned");
+        // Works on copies so a stale row leaves nothing stored, as the rolled back transaction does.
+        let mut new_assignments = assignments.clone();
+        let mut new_links = links.clone();
+        for ri in planned {
+            let assignment_id = if ri.assignment.id.is_nil() {
+                let curve = dto::Assignment {
+                    id: Uuid::now_v7(),
+                    version: 1,
+                    ..ri.assignment.clone()
+                };
+                new_assignments.insert(curve.id, curve.clone());
+                curve.id
+            } else {
+                let link_id = ri.link.as_ref().map(|x| x.id);
+                if new_links
+                    .values()
+                    .any(|x| x.assignment_id == ri.assignment.id && Some(x.id) != link_id)
+                {
+                    return Err(SavePlanError::StaleAssignment {
+                        id: ri.assignment.id,
+                    });
+                }
+                let curve = new_assignments
+                    .get_mut(&ri.assignment.id)
+                    .filter(|x| x.year == ri.assignment.year && x.version == ri.assignment.version)
+                    .ok_or(SavePlanError::StaleAssignment {
+                        id: ri.assignment.id,
+                    })?;
+                *curve = dto::Assignment {
+                    version: curve.version + 1,
+                    ..ri.assignment.clone()
+                };
+                curve.id
+            };
+            match &ri.link {
+                None => {
+                    if new_links.values().any(|x| {
+                        x.employee_id == ri.employee_id && x.initiative_id == ri.initiative_id
+                    }) {
+                        return Err(SavePlanError::Duplicate {
+                            employee_id: ri.employee_id,
+                            initiative_id: ri.initiative_id,
+                            source: unique_violation(
+                                "employee_assignments_employee_id_initiative_id_key",
+                            ),
+                        });
+                    }
+                    let link = dto::EmployeeAssignment {
+                        id: Uuid::now_v7(),
+                        employee_id: ri.employee_id,
+                        assignment_id,
+                        allocation: ri.allocation.clone(),
+                        initiative_id: ri.initiative_id,
+                        version: 1,
+                    };
+                    new_links.insert(link.id, link);
+                }
+                Some(link) => {
+                    let current = new_links
+                        .get_mut(&link.id)
+                        .filter(|x| x.version == link.version)
+                        .ok_or(SavePlanError::StaleLink { id: link.id })?;
+                    current.assignment_id = assignment_id;
+                    current.allocation = ri.allocation.clone();
+                    current.version += 1;
+                }
+            }
+        }
+        let checks: BTreeSet<(Uuid, i32)> = planned
+            .iter()
+            .map(|x| (x.employee_id, x.assignment.year))
+            .collect();
+        for (employee_id, year) in checks {
+            let months = months_over_capacity(&new_links, &new_assignments, employee_id, year);
+            if !months.is_empty() {
+                return Err(SavePlanError::Overallocated {
+                    employee_id,
+                    year,
+                    months,
+                });
+            }
+        }
+        *assignments = new_assignments;
+        *links = new_links;
+        Ok(())
+    }
+}
+
+impl InMemoryDb {
+    /// The links matching `predicate`, in id order.
+    fn links_where(
+        &self,
+        predicate: impl Fn(&dto::EmployeeAssignment) -> bool,
+    ) -> Vec<dto::EmployeeAssignment> {
+        let mut ris: Vec<dto::EmployeeAssignment> = self
+            .employee_assignments
+            .read()
+            .expect("employee assignments lock poisoned")
+            .values()
+            .filter(|x| predicate(x))
+            .cloned()
+            .collect();
+        ris.sort_by_key(|x| x.id);
+        ris
+    }
+}
+
+/// A constraint of the `employee_assignments` table that a link would break.
+enum LinkViolation {
+    Duplicate(sqlx::Error),
+    MissingReference(sqlx::Error),
+    InvalidAllocation(sqlx::Error),
+}
+
+/// Checks `link` against the table constraints, given the `links` it would sit beside.
+fn check_link(
+    employees: &HashMap<Uuid, dto::Employee>,
+    initiatives: &HashMap<Uuid, dto::Initiative>,
+    assignments: &HashMap<Uuid, dto::Assignment>,
+    links: &HashMap<Uuid, dto::EmployeeAssignment>,
+    link: &dto::EmployeeAssignment,
+) -> Result<(), LinkViolation> {
+    if !employees.contains_key(&link.employee_id) {
+        return Err(LinkViolation::MissingReference(foreign_key_violation(
+            "employee_assignments_employee_id_fkey",
+        )));
+    }
+    if !assignments.contains_key(&link.assignment_id) {
+        return Err(LinkViolation::MissingReference(foreign_key_violation(
+            "employee_assignments_assignment_id_fkey",
+        )));
+    }
+    if !initiatives.contains_key(&link.initiative_id) {
+        return Err(LinkViolation::MissingReference(foreign_key_violation(
+            "employee_assignments_initiative_id_fkey",
+        )));
+    }
+    if link.allocation < BigDecimal::zero() || link.allocation > BigDecimal::one() {
+        return Err(LinkViolation::InvalidAllocation(check_violation(
+            "employee_assignments_allocation_check",
+        )));
+    }
+    if links.values().any(|x| {
+        x.id != link.id
+            && x.employee_id == link.employee_id
+            && x.initiative_id == link.initiative_id
+    }) {
+        return Err(LinkViolation::Duplicate(unique_violation(
+            "employee_assignments_employee_id_initiative_id_key",
+        )));
+    }
+    Ok(())
+}
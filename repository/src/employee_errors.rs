@@ -1,3 +1,4 @@
+use crate::in_memory_db::unique_violation;
 use thiserror::Error;
 use uuid::Uuid;
 
@@ -32,22 +33,54 @@ impl CreateEmployeeError {
         CreateEmployeeError::CommitFailed { source: error }
     }
     pub fn from_sqlx_with_email(error: sqlx::Error, email: &str) -> Self {
-        if let Some(db_error) = error.as_database_error() {
-            if let Some(code) = db_error.code() {
-                if code == "23505" {
-                    return CreateEmployeeError::Duplicate {
-                        id: email.to_string(),
-                        source: error,
-                    };
-                }
-            }
+        if let Some(db_error) = error.as_database_error()
+            && let Some(code) = db_error.code()
+            && code == "23505"
+        {
+            return CreateEmployeeError::Duplicate {
+                id: email.to_string(),
+                source: error,
+            };
         }
         CreateEmployeeError::Unknown { source: error }
     }
 }
 
+#[derive(Debug, Error)]
+pub enum CreateEmployeesError {
+    /// Indexes into the batch of the rows whose email is taken, by an existing employee or an
+    /// earlier row.
+    #[error("Rows {rows:?} use an email another employee already has")]
+    Duplicate { rows: Vec<usize> },
+
+    #[error("Failed to commit transactions: {source}")]
+    CommitFailed { source: sqlx::Error },
+
+    #[error("Unknown error for employees: {source}")]
+    Unknown { source: sqlx::Error },
+}
+
+impl From<CreateEmployeesError> for sqlx::Error {
+    fn from(error: CreateEmployeesError) -> Self {
+        match error {
+            CreateEmployeesError::Duplicate { rows: _ } => unique_violation("uk_employees_email"),
+            CreateEmployeesError::CommitFailed { source } => source,
+            CreateEmployeesError::Unknown { source } => source,
+        }
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum UpdateEmployeeError {
+    #[error("Employee with id {id} not found")]
+    NotFound { id: Uuid },
+
+    #[error("Employee with id {id} was changed by someone else")]
+    Stale { id: Uuid },
+
+    #[error("Another employee already uses email {email}: {source}")]
+    Duplicate { email: String, source: sqlx::Error },
+
     #[error("Failed to commit transactions: {source}")]
     CommitFailed { source: sqlx::Error },
 
@@ -58,27 +91,131 @@ pub enum UpdateEmployeeError {
 impl From<UpdateEmployeeError> for sqlx::Error {
     fn from(error: UpdateEmployeeError) -> Self {
         match error {
+            UpdateEmployeeError::NotFound { id: _ } => sqlx::Error::RowNotFound,
+            UpdateEmployeeError::Stale { id: _ } => sqlx::Error::RowNotFound,
+            UpdateEmployeeError::Duplicate { email: _, source } => source,
             UpdateEmployeeError::CommitFailed { source } => source,
             UpdateEmployeeError::Unknown { source } => source,
         }
     }
 }
 
+impl UpdateEmployeeError {
+    pub fn from_sqlx_with_email(error: sqlx::Error, email: &str) -> Self {
+        if let Some(db_error) = error.as_database_error()
+            && let Some(code) = db_error.code()
+            && code == "23505"
+        {
+            return UpdateEmployeeError::Duplicate {
+                email: email.to_string(),
+                source: error,
+            };
+        }
+        UpdateEmployeeError::Unknown { source: error }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum TerminateEmployeeError {
+    #[error("Employee with id {id} not found")]
+    NotFound { id: Uuid },
+
+    #[error("Employee with id {id} was changed by someone else")]
+    Stale { id: Uuid },
+
+    #[error("Failed to commit transactions: {source}")]
+    CommitFailed { source: sqlx::Error },
+
+    #[error("Unknown error for employee: {source}")]
+    Unknown { source: sqlx::Error },
+}
+
+impl From<TerminateEmployeeError> for sqlx::Error {
+    fn from(error: TerminateEmployeeError) -> Self {
+        match error {
+            TerminateEmployeeError::NotFound { id: _ } => sqlx::Error::RowNotFound,
+            TerminateEmployeeError::Stale { id: _ } => sqlx::Error::RowNotFound,
+            TerminateEmployeeError::CommitFailed { source } => source,
+            TerminateEmployeeError::Unknown { source } => source,
+        }
+    }
+}
+
 #[derive(Debug, Error)]
-pub enum DeleteEmployeeError {
+pub enum RestoreEmployeeError {
+    #[error("Employee with id {id} not found")]
+    NotFound { id: Uuid },
+
+    #[error("Employee with id {id} was changed by someone else")]
+    Stale { id: Uuid },
+
+    #[error("Employee with id {id} has not left")]
+    NotTerminated { id: Uuid },
+
+    #[error("Failed to commit transactions: {source}")]
+    CommitFailed { source: sqlx::Error },
+
     #[error("Unknown error for employee: {source}")]
     Unknown { source: sqlx::Error },
 }
-impl From<DeleteEmployeeError> for sqlx::Error {
-    fn from(error: DeleteEmployeeError) -> Self {
+
+impl From<RestoreEmployeeError> for sqlx::Error {
+    fn from(error: RestoreEmployeeError) -> Self {
+        match error {
+            RestoreEmployeeError::NotFound { id: _ } => sqlx::Error::RowNotFound,
+            RestoreEmployeeError::Stale { id: _ } => sqlx::Error::RowNotFound,
+            RestoreEmployeeError::NotTerminated { id: _ } => sqlx::Error::RowNotFound,
+            RestoreEmployeeError::CommitFailed { source } => source,
+            RestoreEmployeeError::Unknown { source } => source,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum AddSalaryError {
+    #[error("Employee with id {id} not found")]
+    NotFound { id: Uuid },
+
+    #[error("Employee with id {id} was changed by someone else")]
+    Stale { id: Uuid },
+
+    #[error("Failed to commit transactions: {source}")]
+    CommitFailed { source: sqlx::Error },
+
+    #[error("Unknown error for salary: {source}")]
+    Unknown { source: sqlx::Error },
+}
+
+impl From<AddSalaryError> for sqlx::Error {
+    fn from(error: AddSalaryError) -> Self {
         match error {
-            DeleteEmployeeError::Unknown { source } => source,
+            AddSalaryError::NotFound { id: _ } => sqlx::Error::RowNotFound,
+            AddSalaryError::Stale { id: _ } => sqlx::Error::RowNotFound,
+            AddSalaryError::CommitFailed { source } => source,
+            AddSalaryError::Unknown { source } => source,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum GetSalariesError {
+    #[error("Unknown error for salaries: {source}")]
+    Unknown { source: sqlx::Error },
+}
+
+impl From<GetSalariesError> for sqlx::Error {
+    fn from(error: GetSalariesError) -> Self {
+        match error {
+            GetSalariesError::Unknown { source } => source,
         }
     }
 }
 
 #[derive(Debug, Error)]
 pub enum GetEmployeesError {
+    #[error("Cursor {id} does not refer to an existing employee")]
+    InvalidCursor { id: Uuid },
+
     #[error("Unknown error for employees: {source}")]
     Unknown { source: sqlx::Error },
 }
@@ -86,6 +223,7 @@ pub enum GetEmployeesError {
 impl From<GetEmployeesError> for sqlx::Error {
     fn from(error: GetEmployeesError) -> Self {
         match error {
+            GetEmployeesError::InvalidCursor { id: _ } => sqlx::Error::RowNotFound,
             GetEmployeesError::Unknown { source } => source,
         }
     }
@@ -1,79 +1,286 @@
+use crate::audit_repo::{record, snapshot};
 use crate::dao;
 use crate::employee_errors::*;
+use crate::in_memory_db::{InMemoryDb, unique_violation};
 use crate::postgres_db::PostgresDb;
 use domain::dto;
-use sqlx::PgPool;
+use sqlx::types::BigDecimal;
+use sqlx::types::chrono::{NaiveDate, Utc};
+use sqlx::{PgConnection, Postgres, QueryBuilder};
+use std::cmp::Ordering;
 use std::future::Future;
 use uuid::Uuid;
 
 pub trait EmployeeRepo: Send + Sync + Clone + 'static {
-    fn new(pool: PgPool) -> Self;
     fn create(
         &self,
+        actor: Uuid,
         ri: &dto::Employee,
     ) -> impl Future<Output = Result<dto::Employee, CreateEmployeeError>> + Send;
+    /// Creates every employee in `ris` in one transaction, or none of them when any email is
+    /// taken. With `dry_run` the rows are checked the same way but never kept.
+    fn create_all(
+        &self,
+        actor: Uuid,
+        ris: &[dto::Employee],
+        dry_run: bool,
+    ) -> impl Future<Output = Result<Vec<dto::Employee>, CreateEmployeesError>> + Send;
     fn get(
         &self,
         id: Uuid,
     ) -> impl Future<Output = Result<Option<dto::Employee>, GetEmployeeError>> + Send;
-    fn delete(&self, pet_id: Uuid) -> impl Future<Output = Result<(), DeleteEmployeeError>> + Send;
+    /// Records that the employee left on `terminated_on` if they are still at `version`. The
+    /// row and its allocations are kept; terminating again moves the date.
+    fn terminate(
+        &self,
+        actor: Uuid,
+        id: Uuid,
+        version: i32,
+        terminated_on: NaiveDate,
+    ) -> impl Future<Output = Result<dto::Employee, TerminateEmployeeError>> + Send;
+    /// Clears the termination date of an employee who left, if they are still at `version`.
+    fn restore(
+        &self,
+        actor: Uuid,
+        id: Uuid,
+        version: i32,
+    ) -> impl Future<Output = Result<dto::Employee, RestoreEmployeeError>> + Send;
+    /// Stores `ri` if the employee is still at `ri.version`, bumping the version. A changed
+    /// salary takes effect today, so earlier months keep the old rate. The termination date
+    /// only changes through `terminate` and `restore`.
     fn update(
         &self,
+        actor: Uuid,
         ri: &dto::Employee,
     ) -> impl Future<Output = Result<dto::Employee, UpdateEmployeeError>> + Send;
+    /// Pays the employee `salary.salary` from `salary.effective_from` if they are still at
+    /// `version`, replacing any rate that starts the same day. The date may be in the past or
+    /// the future; earlier and later rates are kept.
+    fn add_salary(
+        &self,
+        actor: Uuid,
+        version: i32,
+        salary: &dto::Salary,
+    ) -> impl Future<Output = Result<dto::Employee, AddSalaryError>> + Send;
+    /// Returns the rates of one employee, or of everyone, sorted by employee and date.
+    fn get_salaries(
+        &self,
+        employee_id: Option<Uuid>,
+    ) -> impl Future<Output = Result<Vec<dto::Salary>, GetSalariesError>> + Send;
+    /// Returns every employee, including those who have left.
     fn get_all(&self)
     -> impl Future<Output = Result<Vec<dto::Employee>, GetEmployeesError>> + Send;
+    fn get_page(
+        &self,
+        query: &dto::EmployeeQuery,
+    ) -> impl Future<Output = Result<dto::Page<dto::Employee>, GetEmployeesError>> + Send;
 }
 
-impl EmployeeRepo for PostgresDb {
-    fn new(pool: PgPool) -> PostgresDb {
-        PostgresDb { pool }
+/// Escapes the LIKE wildcards in a user supplied prefix so it only matches literally.
+fn like_prefix(prefix: &str) -> String {
+    let mut pattern = String::with_capacity(prefix.len() + 1);
+    for c in prefix.to_lowercase().chars() {
+        if matches!(c, '\\' | '%' | '_') {
+            pattern.push('\\');
+        }
+        pattern.push(c);
     }
-    async fn create(&self, ri: &dto::Employee) -> Result<dto::Employee, CreateEmployeeError> {
+    pattern.push('%');
+    pattern
+}
+
+/// Trims the extra row fetched beyond the limit, reporting where the next page starts.
+pub(crate) fn into_page<T>(mut items: Vec<T>, limit: i64, id: impl Fn(&T) -> Uuid) -> dto::Page<T> {
+    let limit = usize::try_from(limit).unwrap_or(0);
+    let next = if items.len() > limit {
+        items.truncate(limit);
+        items.last().map(id)
+    } else {
+        None
+    };
+    dto::Page { items, next }
+}
+
+/// Reads an employee through `employee_records`, which adds the salary in force today.
+async fn read(conn: &mut PgConnection, id: Uuid) -> Result<dao::Employee, sqlx::Error> {
+    sqlx::query_as!(
+        dao::Employee,
+        r#"SELECT id AS "id!", first_name AS "first_name!", last_name AS "last_name!",
+            email AS "email!", salary AS "salary!", version AS "version!", terminated_on
+           FROM employee_records WHERE id = $1"#,
+        id
+    )
+    .fetch_one(conn)
+    .await
+}
+
+/// Completes a new employee row `id`: pays them `salary` from today and logs the creation.
+async fn finish_create(
+    conn: &mut PgConnection,
+    actor: Uuid,
+    id: Uuid,
+    salary: &BigDecimal,
+) -> Result<dao::Employee, sqlx::Error> {
+    sqlx::query!(
+        r#"INSERT INTO employee_salaries(employee_id, effective_from, salary)
+           VALUES ($1, current_date, $2)"#,
+        id,
+        salary
+    )
+    .execute(&mut *conn)
+    .await?;
+    let employee = read(&mut *conn, id).await?;
+    let after = snapshot(&mut *conn, dto::AuditEntity::Employee, id).await?;
+    record(
+        conn,
+        actor,
+        dto::AuditEntity::Employee,
+        id,
+        dto::AuditAction::Create,
+        None,
+        after,
+    )
+    .await?;
+    Ok(employee)
+}
+
+impl EmployeeRepo for PostgresDb {
+    async fn create(
+        &self,
+        actor: Uuid,
+        ri: &dto::Employee,
+    ) -> Result<dto::Employee, CreateEmployeeError> {
         let mut tx = self
             .pool
             .begin()
             .await
             .map_err(|err| CreateEmployeeError::from_sqlx_with_email(err, &ri.email))?;
-        let employee: dao::Employee = sqlx::query_as!(
-            dao::Employee,
-            "INSERT INTO employees(first_name, last_name, email, salary) VALUES ($1, $2, $3, $4) RETURNING *",
+        let id = sqlx::query_scalar!(
+            "INSERT INTO employees(first_name, last_name, email) VALUES ($1, $2, $3) RETURNING id",
             ri.first_name,
             ri.last_name,
             ri.email,
-            ri.salary,
-            )
-            .fetch_one(&mut *tx)
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| CreateEmployeeError::from_sqlx_with_email(e, &ri.email))?;
+        let employee = finish_create(&mut tx, actor, id, &ri.salary)
             .await
-            .map_err(|e| {
-                CreateEmployeeError::from_sqlx_with_email(e, &ri.email)
-            })?;
+            .map_err(|e| CreateEmployeeError::Unknown { source: e })?;
         tx.commit()
             .await
             .map_err(CreateEmployeeError::from_sqlx_commit_failed)?;
         Ok((&employee).into())
     }
-    async fn update(&self, ri: &dto::Employee) -> Result<dto::Employee, UpdateEmployeeError> {
+    async fn create_all(
+        &self,
+        actor: Uuid,
+        ris: &[dto::Employee],
+        dry_run: bool,
+    ) -> Result<Vec<dto::Employee>, CreateEmployeesError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| CreateEmployeesError::Unknown { source: e })?;
+        let mut created = Vec::with_capacity(ris.len());
+        let mut duplicates = Vec::new();
+        for (row, ri) in ris.iter().enumerate() {
+            // Skipping conflicts keeps the transaction usable, so every duplicate is found.
+            let id = sqlx::query_scalar!(
+                r#"INSERT INTO employees(first_name, last_name, email) VALUES ($1, $2, $3)
+                   ON CONFLICT ON CONSTRAINT uk_employees_email DO NOTHING RETURNING id"#,
+                ri.first_name,
+                ri.last_name,
+                ri.email,
+            )
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| CreateEmployeesError::Unknown { source: e })?;
+            let Some(id) = id else {
+                duplicates.push(row);
+                continue;
+            };
+            let employee = finish_create(&mut tx, actor, id, &ri.salary)
+                .await
+                .map_err(|e| CreateEmployeesError::Unknown { source: e })?;
+            created.push((&employee).into());
+        }
+        if !duplicates.is_empty() {
+            return Err(CreateEmployeesError::Duplicate { rows: duplicates });
+        }
+        if dry_run {
+            tx.rollback()
+                .await
+                .map_err(|e| CreateEmployeesError::Unknown { source: e })?;
+        } else {
+            tx.commit()
+                .await
+                .map_err(|e| CreateEmployeesError::CommitFailed { source: e })?;
+        }
+        Ok(created)
+    }
+    async fn update(
+        &self,
+        actor: Uuid,
+        ri: &dto::Employee,
+    ) -> Result<dto::Employee, UpdateEmployeeError> {
         let mut tx = self
             .pool
             .begin()
             .await
             .map_err(|e| UpdateEmployeeError::Unknown { source: e })?;
-        let ret = sqlx::query_as!(
-            dao::Employee,
-            r#"UPDATE employees SET 
+        let before = snapshot(&mut tx, dto::AuditEntity::Employee, ri.id)
+            .await
+            .map_err(|e| UpdateEmployeeError::Unknown { source: e })?;
+        let updated = sqlx::query_scalar!(
+            r#"UPDATE employees SET
                 first_name = $1,
                 last_name = $2,
                 email = $3,
-                salary = $4 
-               WHERE id = $5 RETURNING *"#,
+                version = version + 1
+               WHERE id = $4 AND version = $5 RETURNING id"#,
             ri.first_name,
             ri.last_name,
             ri.email,
-            ri.salary,
-            ri.id
+            ri.id,
+            ri.version
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| UpdateEmployeeError::from_sqlx_with_email(e, &ri.email))?;
+        if updated.is_none() {
+            return Err(if before.is_some() {
+                UpdateEmployeeError::Stale { id: ri.id }
+            } else {
+                UpdateEmployeeError::NotFound { id: ri.id }
+            });
+        }
+        sqlx::query!(
+            r#"INSERT INTO employee_salaries(employee_id, effective_from, salary)
+               SELECT id, current_date, $2 FROM employee_records WHERE id = $1 AND salary <> $2
+               ON CONFLICT (employee_id, effective_from) DO UPDATE SET salary = excluded.salary"#,
+            ri.id,
+            ri.salary
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| UpdateEmployeeError::Unknown { source: e })?;
+        let ret = read(&mut tx, ri.id)
+            .await
+            .map_err(|e| UpdateEmployeeError::Unknown { source: e })?;
+        let after = snapshot(&mut tx, dto::AuditEntity::Employee, ri.id)
+            .await
+            .map_err(|e| UpdateEmployeeError::Unknown { source: e })?;
+        record(
+            &mut tx,
+            actor,
+            dto::AuditEntity::Employee,
+            ri.id,
+            dto::AuditAction::Update,
+            before,
+            after,
         )
-        .fetch_one(&mut *tx)
         .await
         .map_err(|e| UpdateEmployeeError::Unknown { source: e })?;
         tx.commit()
@@ -81,17 +288,200 @@ impl EmployeeRepo for PostgresDb {
             .map_err(|e| UpdateEmployeeError::CommitFailed { source: e })?;
         Ok((&ret).into())
     }
-    async fn delete(&self, id: Uuid) -> Result<(), DeleteEmployeeError> {
-        sqlx::query_as!(dao::Employee, "DELETE FROM employees WHERE id=$1", id)
-            .execute(&self.pool)
+    async fn terminate(
+        &self,
+        actor: Uuid,
+        id: Uuid,
+        version: i32,
+        terminated_on: NaiveDate,
+    ) -> Result<dto::Employee, TerminateEmployeeError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| TerminateEmployeeError::Unknown { source: e })?;
+        let before = snapshot(&mut tx, dto::AuditEntity::Employee, id)
             .await
-            .map_err(|e| DeleteEmployeeError::Unknown { source: e })?;
-        Ok(())
+            .map_err(|e| TerminateEmployeeError::Unknown { source: e })?;
+        let updated = sqlx::query_scalar!(
+            r#"UPDATE employees SET terminated_on = $1, version = version + 1
+               WHERE id = $2 AND version = $3 RETURNING id"#,
+            terminated_on,
+            id,
+            version
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| TerminateEmployeeError::Unknown { source: e })?;
+        if updated.is_none() {
+            return Err(if before.is_some() {
+                TerminateEmployeeError::Stale { id }
+            } else {
+                TerminateEmployeeError::NotFound { id }
+            });
+        }
+        let ret = read(&mut tx, id)
+            .await
+            .map_err(|e| TerminateEmployeeError::Unknown { source: e })?;
+        let after = snapshot(&mut tx, dto::AuditEntity::Employee, id)
+            .await
+            .map_err(|e| TerminateEmployeeError::Unknown { source: e })?;
+        record(
+            &mut tx,
+            actor,
+            dto::AuditEntity::Employee,
+            id,
+            dto::AuditAction::Terminate,
+            before,
+            after,
+        )
+        .await
+        .map_err(|e| TerminateEmployeeError::Unknown { source: e })?;
+        tx.commit()
+            .await
+            .map_err(|e| TerminateEmployeeError::CommitFailed { source: e })?;
+        Ok((&ret).into())
+    }
+    async fn restore(
+        &self,
+        actor: Uuid,
+        id: Uuid,
+        version: i32,
+    ) -> Result<dto::Employee, RestoreEmployeeError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| RestoreEmployeeError::Unknown { source: e })?;
+        let before = snapshot(&mut tx, dto::AuditEntity::Employee, id)
+            .await
+            .map_err(|e| RestoreEmployeeError::Unknown { source: e })?;
+        let Some(before) = before else {
+            return Err(RestoreEmployeeError::NotFound { id });
+        };
+        let updated = sqlx::query_scalar!(
+            r#"UPDATE employees SET terminated_on = NULL, version = version + 1
+               WHERE id = $1 AND version = $2 AND terminated_on IS NOT NULL RETURNING id"#,
+            id,
+            version
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| RestoreEmployeeError::Unknown { source: e })?;
+        if updated.is_none() {
+            return Err(if before["version"] != version {
+                RestoreEmployeeError::Stale { id }
+            } else {
+                RestoreEmployeeError::NotTerminated { id }
+            });
+        }
+        let ret = read(&mut tx, id)
+            .await
+            .map_err(|e| RestoreEmployeeError::Unknown { source: e })?;
+        let after = snapshot(&mut tx, dto::AuditEntity::Employee, id)
+            .await
+            .map_err(|e| RestoreEmployeeError::Unknown { source: e })?;
+        record(
+            &mut tx,
+            actor,
+            dto::AuditEntity::Employee,
+            id,
+            dto::AuditAction::Restore,
+            Some(before),
+            after,
+        )
+        .await
+        .map_err(|e| RestoreEmployeeError::Unknown { source: e })?;
+        tx.commit()
+            .await
+            .map_err(|e| RestoreEmployeeError::CommitFailed { source: e })?;
+        Ok((&ret).into())
+    }
+    async fn add_salary(
+        &self,
+        actor: Uuid,
+        version: i32,
+        salary: &dto::Salary,
+    ) -> Result<dto::Employee, AddSalaryError> {
+        let id = salary.employee_id;
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| AddSalaryError::Unknown { source: e })?;
+        let before = snapshot(&mut tx, dto::AuditEntity::Employee, id)
+            .await
+            .map_err(|e| AddSalaryError::Unknown { source: e })?;
+        let updated = sqlx::query_scalar!(
+            "UPDATE employees SET version = version + 1 WHERE id = $1 AND version = $2 RETURNING id",
+            id,
+            version
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| AddSalaryError::Unknown { source: e })?;
+        if updated.is_none() {
+            return Err(if before.is_some() {
+                AddSalaryError::Stale { id }
+            } else {
+                AddSalaryError::NotFound { id }
+            });
+        }
+        sqlx::query!(
+            r#"INSERT INTO employee_salaries(employee_id, effective_from, salary)
+               VALUES ($1, $2, $3)
+               ON CONFLICT (employee_id, effective_from) DO UPDATE SET salary = excluded.salary"#,
+            id,
+            salary.effective_from,
+            salary.salary
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AddSalaryError::Unknown { source: e })?;
+        let ret = read(&mut tx, id)
+            .await
+            .map_err(|e| AddSalaryError::Unknown { source: e })?;
+        let after = snapshot(&mut tx, dto::AuditEntity::Employee, id)
+            .await
+            .map_err(|e| AddSalaryError::Unknown { source: e })?;
+        record(
+            &mut tx,
+            actor,
+            dto::AuditEntity::Employee,
+            id,
+            dto::AuditAction::Update,
+            before,
+            after,
+        )
+        .await
+        .map_err(|e| AddSalaryError::Unknown { source: e })?;
+        tx.commit()
+            .await
+            .map_err(|e| AddSalaryError::CommitFailed { source: e })?;
+        Ok((&ret).into())
+    }
+    async fn get_salaries(
+        &self,
+        employee_id: Option<Uuid>,
+    ) -> Result<Vec<dto::Salary>, GetSalariesError> {
+        let ris = sqlx::query_as!(
+            dao::Salary,
+            r#"SELECT employee_id, effective_from, salary FROM employee_salaries
+               WHERE ($1::uuid IS NULL OR employee_id = $1)
+               ORDER BY employee_id, effective_from"#,
+            employee_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| GetSalariesError::Unknown { source: e })?;
+        Ok(ris.iter().map(dto::Salary::from).collect())
     }
     async fn get(&self, id: Uuid) -> Result<Option<dto::Employee>, GetEmployeeError> {
         let ri = sqlx::query_as!(
             dao::Employee,
-            "SELECT id, first_name, last_name, email, salary FROM employees where id=$1",
+            r#"SELECT id AS "id!", first_name AS "first_name!", last_name AS "last_name!",
+                email AS "email!", salary AS "salary!", version AS "version!", terminated_on
+               FROM employee_records WHERE id = $1"#,
             id
         )
         .fetch_optional(&self.pool)
@@ -103,11 +493,372 @@ impl EmployeeRepo for PostgresDb {
     async fn get_all(&self) -> Result<Vec<dto::Employee>, GetEmployeesError> {
         let ris = sqlx::query_as!(
             dao::Employee,
-            "SELECT id, first_name, last_name, email, salary FROM employees",
+            r#"SELECT id AS "id!", first_name AS "first_name!", last_name AS "last_name!",
+                email AS "email!", salary AS "salary!", version AS "version!", terminated_on
+               FROM employee_records ORDER BY id"#,
         )
         .fetch_all(&self.pool)
         .await
         .map_err(|e| GetEmployeesError::Unknown { source: e })?;
         Ok(ris.into_iter().map(|x| (&x).into()).collect())
     }
+    async fn get_page(
+        &self,
+        query: &dto::EmployeeQuery,
+    ) -> Result<dto::Page<dto::Employee>, GetEmployeesError> {
+        if let Some(after) = query.after {
+            let exists = sqlx::query_scalar!(
+                r#"SELECT EXISTS(SELECT 1 FROM employees WHERE id = $1) AS "exists!""#,
+                after
+            )
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| GetEmployeesError::Unknown { source: e })?;
+            if !exists {
+                return Err(GetEmployeesError::InvalidCursor { id: after });
+            }
+        }
+
+        let columns = match query.sort {
+            dto::EmployeeSort::Id => "id",
+            dto::EmployeeSort::Name => "last_name, first_name, id",
+            dto::EmployeeSort::Email => "email, id",
+            dto::EmployeeSort::Salary => "salary, id",
+        };
+        let (comparison, direction) = match query.order {
+            dto::SortOrder::Asc => (">", "ASC"),
+            dto::SortOrder::Desc => ("<", "DESC"),
+        };
+
+        let mut qb = QueryBuilder::<Postgres>::new(
+            "SELECT id, first_name, last_name, email, salary, version, terminated_on FROM employee_records WHERE TRUE",
+        );
+        if !query.include_terminated {
+            qb.push(" AND terminated_on IS NULL");
+        }
+        if let Some(prefix) = &query.name_prefix {
+            let pattern = like_prefix(prefix);
+            qb.push(" AND (lower(first_name) LIKE ")
+                .push_bind(pattern.clone())
+                .push(" OR lower(last_name) LIKE ")
+                .push_bind(pattern)
+                .push(")");
+        }
+        if let Some(min) = &query.min_salary {
+            qb.push(" AND salary >= ").push_bind(min.clone());
+        }
+        if let Some(max) = &query.max_salary {
+            qb.push(" AND salary <= ").push_bind(max.clone());
+        }
+        if let Some(after) = query.after {
+            // Keyset pagination: continue strictly after the cursor row in the requested order.
+            qb.push(format!(
+                " AND ({columns}) {comparison} (SELECT {columns} FROM employee_records WHERE id = "
+            ))
+            .push_bind(after)
+            .push(")");
+        }
+        let order_by = columns
+            .split(", ")
+            .map(|c| format!("{c} {direction}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        qb.push(format!(" ORDER BY {order_by} LIMIT "))
+            .push_bind(query.limit + 1);
+
+        let ris: Vec<dao::Employee> = qb
+            .build_query_as()
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| GetEmployeesError::Unknown { source: e })?;
+        Ok(into_page(
+            ris.into_iter().map(|x| (&x).into()).collect(),
+            query.limit,
+            |x| x.id,
+        ))
+    }
+}
+
+/// Puts `salary` into `history` by date, replacing a rate that starts the same day.
+fn upsert_salary(history: &mut Vec<dto::Salary>, salary: dto::Salary) {
+    match history.binary_search_by_key(&salary.effective_from, |x| x.effective_from) {
+        Ok(i) => history[i] = salary,
+        Err(i) => history.insert(i, salary),
+    }
+}
+
+/// The rate `history` puts in force today, as the `employee_records` view computes it.
+fn salary_today(history: &[dto::Salary]) -> Option<BigDecimal> {
+    dto::Salary::in_force(history, Utc::now().date_naive()).map(|x| x.salary.clone())
+}
+
+impl EmployeeRepo for InMemoryDb {
+    async fn create(
+        &self,
+        _actor: Uuid,
+        ri: &dto::Employee,
+    ) -> Result<dto::Employee, CreateEmployeeError> {
+        let mut employees = self.employees.write().expect("employees lock poisoned");
+        if employees.values().any(|x| x.email == ri.email) {
+            return Err(CreateEmployeeError::Duplicate {
+                id: ri.email.clone(),
+                source: unique_violation("uk_employees_email"),
+            });
+        }
+        let employee = dto::Employee {
+            id: Uuid::now_v7(),
+            version: 1,
+            terminated_on: None,
+            ..ri.clone()
+        };
+        employees.insert(employee.id, employee.clone());
+        self.salaries
+            .write()
+            .expect("salaries lock poisoned")
+            .insert(
+                employee.id,
+                vec![dto::Salary {
+                    employee_id: employee.id,
+                    effective_from: Utc::now().date_naive(),
+                    salary: employee.salary.clone(),
+                }],
+            );
+        Ok(employee)
+    }
+    async fn create_all(
+        &self,
+        _actor: Uuid,
+        ris: &[dto::Employee],
+        dry_run: bool,
+    ) -> Result<Vec<dto::Employee>, CreateEmployeesError> {
+        // One write lock across the check and the inserts, so the batch lands whole or not at all.
+        let mut employees = self.employees.write().expect("employees lock poisoned");
+        let duplicates: Vec<usize> = ris
+            .iter()
+            .enumerate()
+            .filter(|(row, ri)| {
+                employees.values().any(|x| x.email == ri.email)
+                    || ris[..*row].iter().any(|x| x.email == ri.email)
+            })
+            .map(|(row, _)| row)
+            .collect();
+        if !duplicates.is_empty() {
+            return Err(CreateEmployeesError::Duplicate { rows: duplicates });
+        }
+        let created: Vec<dto::Employee> = ris
+            .iter()
+            .map(|ri| dto::Employee {
+                id: Uuid::now_v7(),
+                version: 1,
+                terminated_on: None,
+                ..ri.clone()
+            })
+            .collect();
+        if dry_run {
+            return Ok(created);
+        }
+        let mut salaries = self.salaries.write().expect("salaries lock poisoned");
+        let effective_from = Utc::now().date_naive();
+        for employee in &created {
+            employees.insert(employee.id, employee.clone());
+            salaries.insert(
+                employee.id,
+                vec![dto::Salary {
+                    employee_id: employee.id,
+                    effective_from,
+                    salary: employee.salary.clone(),
+                }],
+            );
+        }
+        Ok(created)
+    }
+    async fn update(
+        &self,
+        _actor: Uuid,
+        ri: &dto::Employee,
+    ) -> Result<dto::Employee, UpdateEmployeeError> {
+        let mut employees = self.employees.write().expect("employees lock poisoned");
+        match employees.get(&ri.id) {
+            None => return Err(UpdateEmployeeError::NotFound { id: ri.id }),
+            Some(current) if current.version != ri.version => {
+                return Err(UpdateEmployeeError::Stale { id: ri.id });
+            }
+            Some(_) => {}
+        }
+        if employees
+            .values()
+            .any(|x| x.id != ri.id && x.email == ri.email)
+        {
+            return Err(UpdateEmployeeError::Duplicate {
+                email: ri.email.clone(),
+                source: unique_violation("uk_employees_email"),
+            });
+        }
+        let mut salaries = self.salaries.write().expect("salaries lock poisoned");
+        let history = salaries.entry(ri.id).or_default();
+        if employees[&ri.id].salary != ri.salary {
+            upsert_salary(
+                history,
+                dto::Salary {
+                    employee_id: ri.id,
+                    effective_from: Utc::now().date_naive(),
+                    salary: ri.salary.clone(),
+                },
+            );
+        }
+        let employee = dto::Employee {
+            version: ri.version + 1,
+            terminated_on: employees[&ri.id].terminated_on,
+            salary: salary_today(history).unwrap_or_else(|| ri.salary.clone()),
+            ..ri.clone()
+        };
+        employees.insert(ri.id, employee.clone());
+        Ok(employee)
+    }
+    async fn terminate(
+        &self,
+        _actor: Uuid,
+        id: Uuid,
+        version: i32,
+        terminated_on: NaiveDate,
+    ) -> Result<dto::Employee, TerminateEmployeeError> {
+        let mut employees = self.employees.write().expect("employees lock poisoned");
+        let current = employees
+            .get_mut(&id)
+            .ok_or(TerminateEmployeeError::NotFound { id })?;
+        if current.version != version {
+            return Err(TerminateEmployeeError::Stale { id });
+        }
+        current.terminated_on = Some(terminated_on);
+        current.version += 1;
+        Ok(current.clone())
+    }
+    async fn restore(
+        &self,
+        _actor: Uuid,
+        id: Uuid,
+        version: i32,
+    ) -> Result<dto::Employee, RestoreEmployeeError> {
+        let mut employees = self.employees.write().expect("employees lock poisoned");
+        let current = employees
+            .get_mut(&id)
+            .ok_or(RestoreEmployeeError::NotFound { id })?;
+        if current.version != version {
+            return Err(RestoreEmployeeError::Stale { id });
+        }
+        if current.terminated_on.is_none() {
+            return Err(RestoreEmployeeError::NotTerminated { id });
+        }
+        current.terminated_on = None;
+        current.version += 1;
+        Ok(current.clone())
+    }
+    async fn add_salary(
+        &self,
+        _actor: Uuid,
+        version: i32,
+        salary: &dto::Salary,
+    ) -> Result<dto::Employee, AddSalaryError> {
+        let id = salary.employee_id;
+        let mut employees = self.employees.write().expect("employees lock poisoned");
+        let current = employees
+            .get_mut(&id)
+            .ok_or(AddSalaryError::NotFound { id })?;
+        if current.version != version {
+            return Err(AddSalaryError::Stale { id });
+        }
+        let mut salaries = self.salaries.write().expect("salaries lock poisoned");
+        let history = salaries.entry(id).or_default();
+        upsert_salary(history, salary.clone());
+        if let Some(today) = salary_today(history) {
+            current.salary = today;
+        }
+        current.version += 1;
+        Ok(current.clone())
+    }
+    async fn get_salaries(
+        &self,
+        employee_id: Option<Uuid>,
+    ) -> Result<Vec<dto::Salary>, GetSalariesError> {
+        let salaries = self.salaries.read().expect("salaries lock poisoned");
+        let mut ris: Vec<dto::Salary> = salaries
+            .iter()
+            .filter(|(id, _)| employee_id.is_none_or(|x| x == **id))
+            .flat_map(|(_, history)| history.iter().cloned())
+            .collect();
+        ris.sort_by_key(|x| (x.employee_id, x.effective_from));
+        Ok(ris)
+    }
+    async fn get(&self, id: Uuid) -> Result<Option<dto::Employee>, GetEmployeeError> {
+        Ok(self
+            .employees
+            .read()
+            .expect("employees lock poisoned")
+            .get(&id)
+            .cloned())
+    }
+    async fn get_all(&self) -> Result<Vec<dto::Employee>, GetEmployeesError> {
+        let mut ris: Vec<dto::Employee> = self
+            .employees
+            .read()
+            .expect("employees lock poisoned")
+            .values()
+            .cloned()
+            .collect();
+        // UUIDv7 ids sort in creation order, matching the insertion order Postgres returns.
+        ris.sort_by_key(|x| x.id);
+        Ok(ris)
+    }
+    async fn get_page(
+        &self,
+        query: &dto::EmployeeQuery,
+    ) -> Result<dto::Page<dto::Employee>, GetEmployeesError> {
+        let employees = self.employees.read().expect("employees lock poisoned");
+        let cursor = match query.after {
+            Some(id) => Some(
+                employees
+                    .get(&id)
+                    .cloned()
+                    .ok_or(GetEmployeesError::InvalidCursor { id })?,
+            ),
+            None => None,
+        };
+        let compare = |a: &dto::Employee, b: &dto::Employee| -> Ordering {
+            let ordering = match query.sort {
+                dto::EmployeeSort::Id => Ordering::Equal,
+                dto::EmployeeSort::Name => {
+                    (&a.last_name, &a.first_name).cmp(&(&b.last_name, &b.first_name))
+                }
+                dto::EmployeeSort::Email => a.email.cmp(&b.email),
+                dto::EmployeeSort::Salary => a.salary.cmp(&b.salary),
+            }
+            .then(a.id.cmp(&b.id));
+            match query.order {
+                dto::SortOrder::Asc => ordering,
+                dto::SortOrder::Desc => ordering.reverse(),
+            }
+        };
+        let prefix = query.name_prefix.as_ref().map(|x| x.to_lowercase());
+        let mut ris: Vec<dto::Employee> = employees
+            .values()
+            .filter(|x| query.include_terminated || x.terminated_on.is_none())
+            .filter(|x| {
+                prefix.as_ref().is_none_or(|p| {
+                    x.first_name.to_lowercase().starts_with(p)
+                        || x.last_name.to_lowercase().starts_with(p)
+                })
+            })
+            .filter(|x| query.min_salary.as_ref().is_none_or(|min| &x.salary >= min))
+            .filter(|x| query.max_salary.as_ref().is_none_or(|max| &x.salary <= max))
+            .filter(|x| {
+                cursor
+                    .as_ref()
+                    .is_none_or(|c| compare(x, c) == Ordering::Greater)
+            })
+            .cloned()
+            .collect();
+        ris.sort_by(compare);
+        ris.truncate(usize::try_from(query.limit + 1).unwrap_or(0));
+        Ok(into_page(ris, query.limit, |x| x.id))
+    }
 }
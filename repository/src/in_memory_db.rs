@@ -0,0 +1,84 @@
+use bigdecimal::{BigDecimal, One, Zero};
+use domain::dto;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, RwLock};
+use uuid::Uuid;
+
+/// A repository that keeps everything in process memory. It enforces the same constraints as
+/// the database schema so handlers and services can be exercised without Postgres. It keeps no
+/// audit trail, so audit listings come back empty.
+///
+/// Writes that touch several tables take their locks in field order, so they never deadlock.
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryDb {
+    pub(crate) employees: Arc<RwLock<HashMap<Uuid, dto::Employee>>>,
+    /// Each employee's rates, sorted by `effective_from`.
+    pub(crate) salaries: Arc<RwLock<HashMap<Uuid, Vec<dto::Salary>>>>,
+    pub(crate) initiatives: Arc<RwLock<HashMap<Uuid, dto::Initiative>>>,
+    pub(crate) tags: Arc<RwLock<HashMap<Uuid, dto::Tag>>>,
+    pub(crate) assignments: Arc<RwLock<HashMap<Uuid, dto::Assignment>>>,
+    /// `(assignment_id, tag_id)` pairs.
+    pub(crate) assignment_tags: Arc<RwLock<HashSet<(Uuid, Uuid)>>>,
+    pub(crate) employee_assignments: Arc<RwLock<HashMap<Uuid, dto::EmployeeAssignment>>>,
+    /// `(employee_assignment_id, tag_id)` pairs.
+    pub(crate) employee_assignment_tags: Arc<RwLock<HashSet<(Uuid, Uuid)>>>,
+    pub(crate) roles: Arc<RwLock<HashMap<Uuid, dto::Role>>>,
+    pub(crate) users: Arc<RwLock<HashMap<Uuid, dto::User>>>,
+}
+
+impl InMemoryDb {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// Builds the error Postgres would report for a violated unique constraint.
+pub(crate) fn unique_violation(constraint: &str) -> sqlx::Error {
+    sqlx::Error::Protocol(format!(
+        "duplicate key value violates unique constraint \"{}\"",
+        constraint
+    ))
+}
+
+/// Builds the error Postgres would report for a violated foreign key.
+pub(crate) fn foreign_key_violation(constraint: &str) -> sqlx::Error {
+    sqlx::Error::Protocol(format!(
+        "insert or update violates foreign key constraint \"{}\"",
+        constraint
+    ))
+}
+
+/// Builds the error Postgres would report for a violated check constraint.
+pub(crate) fn check_violation(constraint: &str) -> sqlx::Error {
+    sqlx::Error::Protocol(format!(
+        "new row violates check constraint \"{}\"",
+        constraint
+    ))
+}
+
+/// Indexes (January is 0) of the months of `year` in which the `links` of `employee_id` add up
+/// to more than one whole person.
+pub(crate) fn months_over_capacity(
+    links: &HashMap<Uuid, dto::EmployeeAssignment>,
+    assignments: &HashMap<Uuid, dto::Assignment>,
+    employee_id: Uuid,
+    year: i32,
+) -> Vec<usize> {
+    let mut totals: [BigDecimal; 12] = std::array::from_fn(|_| BigDecimal::zero());
+    for link in links.values().filter(|x| x.employee_id == employee_id) {
+        let Some(curve) = assignments
+            .get(&link.assignment_id)
+            .filter(|x| x.year == year)
+        else {
+            continue;
+        };
+        for (total, fraction) in totals.iter_mut().zip(curve.months()) {
+            if let Some(fraction) = fraction {
+                *total += &link.allocation * fraction;
+            }
+        }
+    }
+    (0..12)
+        .filter(|&month| totals[month] > BigDecimal::one())
+        .collect()
+}
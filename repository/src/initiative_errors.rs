@@ -0,0 +1,100 @@
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum CreateInitiativeError {
+    #[error("Failed to commit transactions: {source}")]
+    CommitFailed { source: sqlx::Error },
+
+    #[error("Unknown error for initiative: {source}")]
+    Unknown { source: sqlx::Error },
+}
+
+impl From<CreateInitiativeError> for sqlx::Error {
+    fn from(error: CreateInitiativeError) -> Self {
+        match error {
+            CreateInitiativeError::CommitFailed { source } => source,
+            CreateInitiativeError::Unknown { source } => source,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum UpdateInitiativeError {
+    #[error("Initiative with id {id} not found")]
+    NotFound { id: Uuid },
+
+    #[error("Initiative with id {id} was changed by someone else")]
+    Stale { id: Uuid },
+
+    #[error("Failed to commit transactions: {source}")]
+    CommitFailed { source: sqlx::Error },
+
+    #[error("Unknown error for initiative: {source}")]
+    Unknown { source: sqlx::Error },
+}
+
+impl From<UpdateInitiativeError> for sqlx::Error {
+    fn from(error: UpdateInitiativeError) -> Self {
+        match error {
+            UpdateInitiativeError::NotFound { id: _ } => sqlx::Error::RowNotFound,
+            UpdateInitiativeError::Stale { id: _ } => sqlx::Error::RowNotFound,
+            UpdateInitiativeError::CommitFailed { source } => source,
+            UpdateInitiativeError::Unknown { source } => source,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum DeleteInitiativeError {
+    #[error("Initiative with id {id} not found")]
+    NotFound { id: Uuid },
+
+    #[error("Initiative with id {id} was changed by someone else")]
+    Stale { id: Uuid },
+
+    #[error("Failed to commit transactions: {source}")]
+    CommitFailed { source: sqlx::Error },
+
+    #[error("Unknown error for initiative: {source}")]
+    Unknown { source: sqlx::Error },
+}
+
+impl From<DeleteInitiativeError> for sqlx::Error {
+    fn from(error: DeleteInitiativeError) -> Self {
+        match error {
+            DeleteInitiativeError::NotFound { id: _ } => sqlx::Error::RowNotFound,
+            DeleteInitiativeError::Stale { id: _ } => sqlx::Error::RowNotFound,
+            DeleteInitiativeError::CommitFailed { source } => source,
+            DeleteInitiativeError::Unknown { source } => source,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum GetInitiativesError {
+    #[error("Unknown error for initiatives: {source}")]
+    Unknown { source: sqlx::Error },
+}
+
+impl From<GetInitiativesError> for sqlx::Error {
+    fn from(error: GetInitiativesError) -> Self {
+        match error {
+            GetInitiativesError::Unknown { source } => source,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum GetInitiativeError {
+    #[error("Unknown error for initiative: {source}")]
+    Unknown { source: sqlx::Error },
+}
+
+impl From<GetInitiativeError> for sqlx::Error {
+    fn from(error: GetInitiativeError) -> Self {
+        match error {
+            GetInitiativeError::Unknown { source } => source,
+        }
+    }
+}
@@ -0,0 +1,270 @@
+use crate::audit_repo::{record, snapshot};
+use crate::dao;
+use crate::in_memory_db::InMemoryDb;
+use crate::initiative_errors::*;
+use crate::postgres_db::PostgresDb;
+use domain::dto;
+use std::future::Future;
+use uuid::Uuid;
+
+pub trait InitiativeRepo: Send + Sync + Clone + 'static {
+    fn create(
+        &self,
+        actor: Uuid,
+        ri: &dto::Initiative,
+    ) -> impl Future<Output = Result<dto::Initiative, CreateInitiativeError>> + Send;
+    fn get(
+        &self,
+        id: Uuid,
+    ) -> impl Future<Output = Result<Option<dto::Initiative>, GetInitiativeError>> + Send;
+    /// Deletes the initiative if it is still at `version`.
+    fn delete(
+        &self,
+        actor: Uuid,
+        id: Uuid,
+        version: i32,
+    ) -> impl Future<Output = Result<(), DeleteInitiativeError>> + Send;
+    /// Stores `ri` if the initiative is still at `ri.version`, bumping the version.
+    fn update(
+        &self,
+        actor: Uuid,
+        ri: &dto::Initiative,
+    ) -> impl Future<Output = Result<dto::Initiative, UpdateInitiativeError>> + Send;
+    fn get_all(
+        &self,
+    ) -> impl Future<Output = Result<Vec<dto::Initiative>, GetInitiativesError>> + Send;
+}
+
+impl InitiativeRepo for PostgresDb {
+    async fn create(
+        &self,
+        actor: Uuid,
+        ri: &dto::Initiative,
+    ) -> Result<dto::Initiative, CreateInitiativeError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| CreateInitiativeError::Unknown { source: e })?;
+        let initiative = sqlx::query_as!(
+            dao::Initiative,
+            "INSERT INTO initiatives(initiative) VALUES ($1) RETURNING *",
+            ri.initiative,
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| CreateInitiativeError::Unknown { source: e })?;
+        let after = snapshot(&mut tx, dto::AuditEntity::Initiative, initiative.id)
+            .await
+            .map_err(|e| CreateInitiativeError::Unknown { source: e })?;
+        record(
+            &mut tx,
+            actor,
+            dto::AuditEntity::Initiative,
+            initiative.id,
+            dto::AuditAction::Create,
+            None,
+            after,
+        )
+        .await
+        .map_err(|e| CreateInitiativeError::Unknown { source: e })?;
+        tx.commit()
+            .await
+            .map_err(|e| CreateInitiativeError::CommitFailed { source: e })?;
+        Ok((&initiative).into())
+    }
+    async fn update(
+        &self,
+        actor: Uuid,
+        ri: &dto::Initiative,
+    ) -> Result<dto::Initiative, UpdateInitiativeError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| UpdateInitiativeError::Unknown { source: e })?;
+        let before = snapshot(&mut tx, dto::AuditEntity::Initiative, ri.id)
+            .await
+            .map_err(|e| UpdateInitiativeError::Unknown { source: e })?;
+        let ret = sqlx::query_as!(
+            dao::Initiative,
+            "UPDATE initiatives SET initiative = $1, version = version + 1
+             WHERE id = $2 AND version = $3 RETURNING *",
+            ri.initiative,
+            ri.id,
+            ri.version
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| UpdateInitiativeError::Unknown { source: e })?;
+        let Some(ret) = ret else {
+            return Err(if before.is_some() {
+                UpdateInitiativeError::Stale { id: ri.id }
+            } else {
+                UpdateInitiativeError::NotFound { id: ri.id }
+            });
+        };
+        let after = snapshot(&mut tx, dto::AuditEntity::Initiative, ri.id)
+            .await
+            .map_err(|e| UpdateInitiativeError::Unknown { source: e })?;
+        record(
+            &mut tx,
+            actor,
+            dto::AuditEntity::Initiative,
+            ri.id,
+            dto::AuditAction::Update,
+            before,
+            after,
+        )
+        .await
+        .map_err(|e| UpdateInitiativeError::Unknown { source: e })?;
+        tx.commit()
+            .await
+            .map_err(|e| UpdateInitiativeError::CommitFailed { source: e })?;
+        Ok((&ret).into())
+    }
+    async fn delete(
+        &self,
+        actor: Uuid,
+        id: Uuid,
+        version: i32,
+    ) -> Result<(), DeleteInitiativeError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| DeleteInitiativeError::Unknown { source: e })?;
+        let before = snapshot(&mut tx, dto::AuditEntity::Initiative, id)
+            .await
+            .map_err(|e| DeleteInitiativeError::Unknown { source: e })?;
+        let deleted = sqlx::query!(
+            "DELETE FROM initiatives WHERE id = $1 AND version = $2",
+            id,
+            version
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| DeleteInitiativeError::Unknown { source: e })?;
+        if deleted.rows_affected() == 0 {
+            return Err(if before.is_some() {
+                DeleteInitiativeError::Stale { id }
+            } else {
+                DeleteInitiativeError::NotFound { id }
+            });
+        }
+        record(
+            &mut tx,
+            actor,
+            dto::AuditEntity::Initiative,
+            id,
+            dto::AuditAction::Delete,
+            before,
+            None,
+        )
+        .await
+        .map_err(|e| DeleteInitiativeError::Unknown { source: e })?;
+        tx.commit()
+            .await
+            .map_err(|e| DeleteInitiativeError::CommitFailed { source: e })?;
+        Ok(())
+    }
+    async fn get(&self, id: Uuid) -> Result<Option<dto::Initiative>, GetInitiativeError> {
+        let ri = sqlx::query_as!(
+            dao::Initiative,
+            "SELECT id, initiative, version FROM initiatives where id=$1",
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| GetInitiativeError::Unknown { source: e })?;
+        Ok(ri.map(|x: dao::Initiative| (&x).into()))
+    }
+    async fn get_all(&self) -> Result<Vec<dto::Initiative>, GetInitiativesError> {
+        let ris = sqlx::query_as!(
+            dao::Initiative,
+            "SELECT id, initiative, version FROM initiatives"
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| GetInitiativesError::Unknown { source: e })?;
+        Ok(ris.into_iter().map(|x| (&x).into()).collect())
+    }
+}
+
+impl InitiativeRepo for InMemoryDb {
+    async fn create(
+        &self,
+        _actor: Uuid,
+        ri: &dto::Initiative,
+    ) -> Result<dto::Initiative, CreateInitiativeError> {
+        let initiative = dto::Initiative {
+            id: Uuid::now_v7(),
+            version: 1,
+            ..ri.clone()
+        };
+        self.initiatives
+            .write()
+            .expect("initiatives lock poisoned")
+            .insert(initiative.id, initiative.clone());
+        Ok(initiative)
+    }
+    async fn get(&self, id: Uuid) -> Result<Option<dto::Initiative>, GetInitiativeError> {
+        Ok(self
+            .initiatives
+            .read()
+            .expect("initiatives lock poisoned")
+            .get(&id)
+            .cloned())
+    }
+    async fn delete(
+        &self,
+        _actor: Uuid,
+        id: Uuid,
+        version: i32,
+    ) -> Result<(), DeleteInitiativeError> {
+        let mut initiatives = self.initiatives.write().expect("initiatives lock poisoned");
+        match initiatives.get(&id) {
+            None => return Err(DeleteInitiativeError::NotFound { id }),
+            Some(current) if current.version != version => {
+                return Err(DeleteInitiativeError::Stale { id });
+            }
+            Some(_) => {}
+        }
+        initiatives.remove(&id);
+        // Links to the initiative go with it, as `ON DELETE CASCADE` does.
+        self.employee_assignments
+            .write()
+            .expect("employee assignments lock poisoned")
+            .retain(|_, x| x.initiative_id != id);
+        Ok(())
+    }
+    async fn update(
+        &self,
+        _actor: Uuid,
+        ri: &dto::Initiative,
+    ) -> Result<dto::Initiative, UpdateInitiativeError> {
+        let mut initiatives = self.initiatives.write().expect("initiatives lock poisoned");
+        let current = initiatives
+            .get_mut(&ri.id)
+            .ok_or(UpdateInitiativeError::NotFound { id: ri.id })?;
+        if current.version != ri.version {
+            return Err(UpdateInitiativeError::Stale { id: ri.id });
+        }
+        *current = dto::Initiative {
+            version: ri.version + 1,
+            ..ri.clone()
+        };
+        Ok(current.clone())
+    }
+    async fn get_all(&self) -> Result<Vec<dto::Initiative>, GetInitiativesError> {
+        let mut ris: Vec<dto::Initiative> = self
+            .initiatives
+            .read()
+            .expect("initiatives lock poisoned")
+            .values()
+            .cloned()
+            .collect();
+        ris.sort_by_key(|x| x.id);
+        Ok(ris)
+    }
+}
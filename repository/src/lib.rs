@@ -1,5 +1,20 @@
+pub mod assignment_errors;
+pub mod assignment_repo;
+pub mod audit_errors;
+pub mod audit_repo;
 pub mod converters;
 pub mod dao;
+pub mod employee_assignment_errors;
+pub mod employee_assignment_repo;
 pub mod employee_errors;
 pub mod employee_repo;
+pub mod in_memory_db;
+pub mod initiative_errors;
+pub mod initiative_repo;
 pub mod postgres_db;
+pub mod role_errors;
+pub mod role_repo;
+pub mod tag_errors;
+pub mod tag_repo;
+pub mod user_errors;
+pub mod user_repo;
@@ -7,6 +7,10 @@ pub struct PostgresDb {
 }
 
 impl PostgresDb {
+    pub fn new(pool: PgPool) -> Self {
+        PostgresDb { pool }
+    }
+
     pub async fn create_db_pool(url: &str) -> Result<PgPool, Error> {
         PgPool::connect(url).await
     }
@@ -0,0 +1,47 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum CreateRoleError {
+    #[error("Failed to commit transactions: {source}")]
+    CommitFailed { source: sqlx::Error },
+
+    #[error("Unknown error for role: {source}")]
+    Unknown { source: sqlx::Error },
+}
+
+impl From<CreateRoleError> for sqlx::Error {
+    fn from(error: CreateRoleError) -> Self {
+        match error {
+            CreateRoleError::CommitFailed { source } => source,
+            CreateRoleError::Unknown { source } => source,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum GetRoleError {
+    #[error("Unknown error for role: {source}")]
+    Unknown { source: sqlx::Error },
+}
+
+impl From<GetRoleError> for sqlx::Error {
+    fn from(error: GetRoleError) -> Self {
+        match error {
+            GetRoleError::Unknown { source } => source,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum GetRolesError {
+    #[error("Unknown error for roles: {source}")]
+    Unknown { source: sqlx::Error },
+}
+
+impl From<GetRolesError> for sqlx::Error {
+    fn from(error: GetRolesError) -> Self {
+        match error {
+            GetRolesError::Unknown { source } => source,
+        }
+    }
+}
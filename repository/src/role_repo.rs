@@ -0,0 +1,113 @@
+use crate::audit_repo::{record, snapshot};
+use crate::dao;
+use crate::in_memory_db::InMemoryDb;
+use crate::postgres_db::PostgresDb;
+use crate::role_errors::*;
+use domain::dto;
+use sqlx::types::Json;
+use std::future::Future;
+use uuid::Uuid;
+
+pub trait RoleRepo: Send + Sync + Clone + 'static {
+    fn create(
+        &self,
+        actor: Uuid,
+        ri: &dto::Role,
+    ) -> impl Future<Output = Result<dto::Role, CreateRoleError>> + Send;
+    fn get(&self, id: Uuid)
+    -> impl Future<Output = Result<Option<dto::Role>, GetRoleError>> + Send;
+    fn get_all(&self) -> impl Future<Output = Result<Vec<dto::Role>, GetRolesError>> + Send;
+}
+
+impl RoleRepo for PostgresDb {
+    async fn create(&self, actor: Uuid, ri: &dto::Role) -> Result<dto::Role, CreateRoleError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| CreateRoleError::Unknown { source: e })?;
+        let scopes: Vec<String> = ri.scopes.iter().map(|x| x.to_string()).collect();
+        let ret = sqlx::query_as!(
+            dao::Role,
+            r#"INSERT INTO roles(name, scopes) VALUES ($1, $2)
+               RETURNING id, name, scopes AS "scopes: Json<Vec<String>>""#,
+            ri.name,
+            Json(scopes) as _
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| CreateRoleError::Unknown { source: e })?;
+        let after = snapshot(&mut tx, dto::AuditEntity::Role, ret.id)
+            .await
+            .map_err(|e| CreateRoleError::Unknown { source: e })?;
+        record(
+            &mut tx,
+            actor,
+            dto::AuditEntity::Role,
+            ret.id,
+            dto::AuditAction::Create,
+            None,
+            after,
+        )
+        .await
+        .map_err(|e| CreateRoleError::Unknown { source: e })?;
+        tx.commit()
+            .await
+            .map_err(|e| CreateRoleError::CommitFailed { source: e })?;
+        Ok((&ret).into())
+    }
+    async fn get(&self, id: Uuid) -> Result<Option<dto::Role>, GetRoleError> {
+        let ri = sqlx::query_as!(
+            dao::Role,
+            r#"SELECT id, name, scopes AS "scopes: Json<Vec<String>>" FROM roles WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| GetRoleError::Unknown { source: e })?;
+        Ok(ri.map(|x| (&x).into()))
+    }
+    async fn get_all(&self) -> Result<Vec<dto::Role>, GetRolesError> {
+        let ris = sqlx::query_as!(
+            dao::Role,
+            r#"SELECT id, name, scopes AS "scopes: Json<Vec<String>>" FROM roles ORDER BY name"#,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| GetRolesError::Unknown { source: e })?;
+        Ok(ris.into_iter().map(|x| (&x).into()).collect())
+    }
+}
+
+impl RoleRepo for InMemoryDb {
+    async fn create(&self, _actor: Uuid, ri: &dto::Role) -> Result<dto::Role, CreateRoleError> {
+        let role = dto::Role {
+            id: Uuid::now_v7(),
+            ..ri.clone()
+        };
+        self.roles
+            .write()
+            .expect("roles lock poisoned")
+            .insert(role.id, role.clone());
+        Ok(role)
+    }
+    async fn get(&self, id: Uuid) -> Result<Option<dto::Role>, GetRoleError> {
+        Ok(self
+            .roles
+            .read()
+            .expect("roles lock poisoned")
+            .get(&id)
+            .cloned())
+    }
+    async fn get_all(&self) -> Result<Vec<dto::Role>, GetRolesError> {
+        let mut ris: Vec<dto::Role> = self
+            .roles
+            .read()
+            .expect("roles lock poisoned")
+            .values()
+            .cloned()
+            .collect();
+        ris.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(ris)
+    }
+}
@@ -0,0 +1,132 @@
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum CreateTagError {
+    #[error("Failed to commit transactions: {source}")]
+    CommitFailed { source: sqlx::Error },
+
+    #[error("Unknown error for tag: {source}")]
+    Unknown { source: sqlx::Error },
+}
+
+impl From<CreateTagError> for sqlx::Error {
+    fn from(error: CreateTagError) -> Self {
+        match error {
+            CreateTagError::CommitFailed { source } => source,
+            CreateTagError::Unknown { source } => source,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum RenameTagError {
+    #[error("Tag with id {id} not found")]
+    NotFound { id: Uuid },
+
+    #[error("Tag with id {id} was changed by someone else")]
+    Stale { id: Uuid },
+
+    #[error("Failed to commit transactions: {source}")]
+    CommitFailed { source: sqlx::Error },
+
+    #[error("Unknown error for tag: {source}")]
+    Unknown { source: sqlx::Error },
+}
+
+impl From<RenameTagError> for sqlx::Error {
+    fn from(error: RenameTagError) -> Self {
+        match error {
+            RenameTagError::NotFound { id: _ } => sqlx::Error::RowNotFound,
+            RenameTagError::Stale { id: _ } => sqlx::Error::RowNotFound,
+            RenameTagError::CommitFailed { source } => source,
+            RenameTagError::Unknown { source } => source,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum MergeTagsError {
+    #[error("Tag with id {id} not found")]
+    NotFound { id: Uuid },
+
+    #[error("Tag with id {id} was changed by someone else")]
+    Stale { id: Uuid },
+
+    #[error("Cannot merge tag {id} into itself")]
+    SameTag { id: Uuid },
+
+    #[error("Failed to commit transactions: {source}")]
+    CommitFailed { source: sqlx::Error },
+
+    #[error("Unknown error for tag: {source}")]
+    Unknown { source: sqlx::Error },
+}
+
+impl From<MergeTagsError> for sqlx::Error {
+    fn from(error: MergeTagsError) -> Self {
+        match error {
+            MergeTagsError::NotFound { id: _ } => sqlx::Error::RowNotFound,
+            MergeTagsError::Stale { id: _ } => sqlx::Error::RowNotFound,
+            MergeTagsError::SameTag { id } => {
+                sqlx::Error::Protocol(format!("Cannot merge tag {} into itself", id))
+            }
+            MergeTagsError::CommitFailed { source } => source,
+            MergeTagsError::Unknown { source } => source,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum DeleteTagError {
+    #[error("Tag with id {id} not found")]
+    NotFound { id: Uuid },
+
+    #[error("Tag with id {id} was changed by someone else")]
+    Stale { id: Uuid },
+
+    #[error("Failed to commit transactions: {source}")]
+    CommitFailed { source: sqlx::Error },
+
+    #[error("Unknown error for tag: {source}")]
+    Unknown { source: sqlx::Error },
+}
+
+impl From<DeleteTagError> for sqlx::Error {
+    fn from(error: DeleteTagError) -> Self {
+        match error {
+            DeleteTagError::NotFound { id: _ } => sqlx::Error::RowNotFound,
+            DeleteTagError::Stale { id: _ } => sqlx::Error::RowNotFound,
+            DeleteTagError::CommitFailed { source } => source,
+            DeleteTagError::Unknown { source } => source,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum GetTagsError {
+    #[error("Unknown error for tags: {source}")]
+    Unknown { source: sqlx::Error },
+}
+
+impl From<GetTagsError> for sqlx::Error {
+    fn from(error: GetTagsError) -> Self {
+        match error {
+            GetTagsError::Unknown { source } => source,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum GetTagError {
+    #[error("Unknown error for tag: {source}")]
+    Unknown { source: sqlx::Error },
+}
+
+impl From<GetTagError> for sqlx::Error {
+    fn from(error: GetTagError) -> Self {
+        match error {
+            GetTagError::Unknown { source } => source,
+        }
+    }
+}
@@ -0,0 +1,478 @@
+use crate::audit_repo::{record, snapshot};
+use crate::dao;
+use crate::in_memory_db::InMemoryDb;
+use crate::postgres_db::PostgresDb;
+use crate::tag_errors::*;
+use domain::dto;
+use std::collections::HashSet;
+use std::future::Future;
+use uuid::Uuid;
+
+pub trait TagRepo: Send + Sync + Clone + 'static {
+    fn create(
+        &self,
+        actor: Uuid,
+        ri: &dto::Tag,
+    ) -> impl Future<Output = Result<dto::Tag, CreateTagError>> + Send;
+    fn get(
+        &self,
+        id: Uuid,
+    ) -> impl Future<Output = Result<Option<dto::TagUsage>, GetTagError>> + Send;
+    fn get_all(&self) -> impl Future<Output = Result<Vec<dto::TagUsage>, GetTagsError>> + Send;
+    /// Renames the tag if it is still at `ri.version`, bumping the version.
+    fn rename(
+        &self,
+        actor: Uuid,
+        ri: &dto::Tag,
+    ) -> impl Future<Output = Result<dto::Tag, RenameTagError>> + Send;
+    /// Moves every reference to `source_id` over to `target_id` and removes `source_id` if it
+    /// is still at `source_version`. Rows that would end up duplicated on the target are dropped.
+    /// The target's usage changes, so its version is bumped.
+    fn merge(
+        &self,
+        actor: Uuid,
+        source_id: Uuid,
+        source_version: i32,
+        target_id: Uuid,
+    ) -> impl Future<Output = Result<dto::TagUsage, MergeTagsError>> + Send;
+    /// Deletes the tag if it is still at `version`.
+    fn delete(
+        &self,
+        actor: Uuid,
+        id: Uuid,
+        version: i32,
+    ) -> impl Future<Output = Result<(), DeleteTagError>> + Send;
+}
+
+impl TagRepo for PostgresDb {
+    async fn create(&self, actor: Uuid, ri: &dto::Tag) -> Result<dto::Tag, CreateTagError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| CreateTagError::Unknown { source: e })?;
+        let tag = sqlx::query_as!(
+            dao::Tag,
+            "INSERT INTO tags(tag) VALUES ($1) RETURNING *",
+            ri.tag,
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| CreateTagError::Unknown { source: e })?;
+        let after = snapshot(&mut tx, dto::AuditEntity::Tag, tag.id)
+            .await
+            .map_err(|e| CreateTagError::Unknown { source: e })?;
+        record(
+            &mut tx,
+            actor,
+            dto::AuditEntity::Tag,
+            tag.id,
+            dto::AuditAction::Create,
+            None,
+            after,
+        )
+        .await
+        .map_err(|e| CreateTagError::Unknown { source: e })?;
+        tx.commit()
+            .await
+            .map_err(|e| CreateTagError::CommitFailed { source: e })?;
+        Ok((&tag).into())
+    }
+    async fn get(&self, id: Uuid) -> Result<Option<dto::TagUsage>, GetTagError> {
+        let ri = sqlx::query_as!(
+            dao::TagUsage,
+            r#"SELECT t.id, t.tag, t.version,
+                (SELECT count(*) FROM assignment_tags a WHERE a.tag_id = t.id) AS "assignment_count!",
+                (SELECT count(*) FROM employee_assignment_tags e WHERE e.tag_id = t.id) AS "employee_assignment_count!"
+               FROM tags t WHERE t.id = $1"#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| GetTagError::Unknown { source: e })?;
+        Ok(ri.map(|x| (&x).into()))
+    }
+    async fn get_all(&self) -> Result<Vec<dto::TagUsage>, GetTagsError> {
+        let ris = sqlx::query_as!(
+            dao::TagUsage,
+            r#"SELECT t.id, t.tag, t.version,
+                (SELECT count(*) FROM assignment_tags a WHERE a.tag_id = t.id) AS "assignment_count!",
+                (SELECT count(*) FROM employee_assignment_tags e WHERE e.tag_id = t.id) AS "employee_assignment_count!"
+               FROM tags t ORDER BY t.tag"#,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| GetTagsError::Unknown { source: e })?;
+        Ok(ris.into_iter().map(|x| (&x).into()).collect())
+    }
+    async fn rename(&self, actor: Uuid, ri: &dto::Tag) -> Result<dto::Tag, RenameTagError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| RenameTagError::Unknown { source: e })?;
+        let before = snapshot(&mut tx, dto::AuditEntity::Tag, ri.id)
+            .await
+            .map_err(|e| RenameTagError::Unknown { source: e })?;
+        let ret = sqlx::query_as!(
+            dao::Tag,
+            "UPDATE tags SET tag = $1, version = version + 1
+             WHERE id = $2 AND version = $3 RETURNING *",
+            ri.tag,
+            ri.id,
+            ri.version
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| RenameTagError::Unknown { source: e })?;
+        let Some(ret) = ret else {
+            return Err(if before.is_some() {
+                RenameTagError::Stale { id: ri.id }
+            } else {
+                RenameTagError::NotFound { id: ri.id }
+            });
+        };
+        let after = snapshot(&mut tx, dto::AuditEntity::Tag, ri.id)
+            .await
+            .map_err(|e| RenameTagError::Unknown { source: e })?;
+        record(
+            &mut tx,
+            actor,
+            dto::AuditEntity::Tag,
+            ri.id,
+            dto::AuditAction::Update,
+            before,
+            after,
+        )
+        .await
+        .map_err(|e| RenameTagError::Unknown { source: e })?;
+        tx.commit()
+            .await
+            .map_err(|e| RenameTagError::CommitFailed { source: e })?;
+        Ok((&ret).into())
+    }
+    async fn merge(
+        &self,
+        actor: Uuid,
+        source_id: Uuid,
+        source_version: i32,
+        target_id: Uuid,
+    ) -> Result<dto::TagUsage, MergeTagsError> {
+        if source_id == target_id {
+            return Err(MergeTagsError::SameTag { id: source_id });
+        }
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| MergeTagsError::Unknown { source: e })?;
+        let before = snapshot(&mut tx, dto::AuditEntity::Tag, source_id)
+            .await
+            .map_err(|e| MergeTagsError::Unknown { source: e })?
+            .ok_or(MergeTagsError::NotFound { id: source_id })?;
+        let target_before = snapshot(&mut tx, dto::AuditEntity::Tag, target_id)
+            .await
+            .map_err(|e| MergeTagsError::Unknown { source: e })?
+            .ok_or(MergeTagsError::NotFound { id: target_id })?;
+        sqlx::query!(
+            r#"DELETE FROM assignment_tags s WHERE s.tag_id = $1 AND EXISTS
+                (SELECT 1 FROM assignment_tags t WHERE t.assignment_id = s.assignment_id AND t.tag_id = $2)"#,
+            source_id,
+            target_id
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| MergeTagsError::Unknown { source: e })?;
+        sqlx::query!(
+            "UPDATE assignment_tags SET tag_id = $2 WHERE tag_id = $1",
+            source_id,
+            target_id
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| MergeTagsError::Unknown { source: e })?;
+        sqlx::query!(
+            r#"DELETE FROM employee_assignment_tags s WHERE s.tag_id = $1 AND EXISTS
+                (SELECT 1 FROM employee_assignment_tags t
+                 WHERE t.employee_assignment_id = s.employee_assignment_id AND t.tag_id = $2)"#,
+            source_id,
+            target_id
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| MergeTagsError::Unknown { source: e })?;
+        sqlx::query!(
+            "UPDATE employee_assignment_tags SET tag_id = $2 WHERE tag_id = $1",
+            source_id,
+            target_id
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| MergeTagsError::Unknown { source: e })?;
+        let deleted = sqlx::query!(
+            "DELETE FROM tags WHERE id = $1 AND version = $2",
+            source_id,
+            source_version
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| MergeTagsError::Unknown { source: e })?;
+        if deleted.rows_affected() == 0 {
+            return Err(MergeTagsError::Stale { id: source_id });
+        }
+        sqlx::query!(
+            "UPDATE tags SET version = version + 1 WHERE id = $1",
+            target_id
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| MergeTagsError::Unknown { source: e })?;
+        let after = snapshot(&mut tx, dto::AuditEntity::Tag, target_id)
+            .await
+            .map_err(|e| MergeTagsError::Unknown { source: e })?;
+        let ret = sqlx::query_as!(
+            dao::TagUsage,
+            r#"SELECT t.id, t.tag, t.version,
+                (SELECT count(*) FROM assignment_tags a WHERE a.tag_id = t.id) AS "assignment_count!",
+                (SELECT count(*) FROM employee_assignment_tags e WHERE e.tag_id = t.id) AS "employee_assignment_count!"
+               FROM tags t WHERE t.id = $1"#,
+            target_id
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| MergeTagsError::Unknown { source: e })?;
+        // The source is recorded as merged into the target, so its audit entry ends with the target.
+        record(
+            &mut tx,
+            actor,
+            dto::AuditEntity::Tag,
+            source_id,
+            dto::AuditAction::Merge,
+            Some(before),
+            after.clone(),
+        )
+        .await
+        .map_err(|e| MergeTagsError::Unknown { source: e })?;
+        record(
+            &mut tx,
+            actor,
+            dto::AuditEntity::Tag,
+            target_id,
+            dto::AuditAction::Update,
+            Some(target_before),
+            after,
+        )
+        .await
+        .map_err(|e| MergeTagsError::Unknown { source: e })?;
+        tx.commit()
+            .await
+            .map_err(|e| MergeTagsError::CommitFailed { source: e })?;
+        Ok((&ret).into())
+    }
+    async fn delete(&self, actor: Uuid, id: Uuid, version: i32) -> Result<(), DeleteTagError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| DeleteTagError::Unknown { source: e })?;
+        let before = snapshot(&mut tx, dto::AuditEntity::Tag, id)
+            .await
+            .map_err(|e| DeleteTagError::Unknown { source: e })?;
+        let deleted = sqlx::query!(
+            "DELETE FROM tags WHERE id = $1 AND version = $2",
+            id,
+            version
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| DeleteTagError::Unknown { source: e })?;
+        if deleted.rows_affected() == 0 {
+            return Err(if before.is_some() {
+                DeleteTagError::Stale { id }
+            } else {
+                DeleteTagError::NotFound { id }
+            });
+        }
+        record(
+            &mut tx,
+            actor,
+            dto::AuditEntity::Tag,
+            id,
+            dto::AuditAction::Delete,
+            before,
+            None,
+        )
+        .await
+        .map_err(|e| DeleteTagError::Unknown { source: e })?;
+        tx.commit()
+            .await
+            .map_err(|e| DeleteTagError::CommitFailed { source: e })?;
+        Ok(())
+    }
+}
+
+impl InMemoryDb {
+    /// Tags an allocation curve. Nothing in the API tags rows yet, so fixtures use this.
+    pub fn tag_assignment(&self, assignment_id: Uuid, tag_id: Uuid) {
+        self.assignment_tags
+            .write()
+            .expect("assignment tags lock poisoned")
+            .insert((assignment_id, tag_id));
+    }
+
+    /// Tags an employee assignment. Nothing in the API tags rows yet, so fixtures use this.
+    pub fn tag_employee_assignment(&self, employee_assignment_id: Uuid, tag_id: Uuid) {
+        self.employee_assignment_tags
+            .write()
+            .expect("employee assignment tags lock poisoned")
+            .insert((employee_assignment_id, tag_id));
+    }
+
+    /// `tag` with the number of rows carrying it, as the Postgres queries count them.
+    fn usage(&self, tag: &dto::Tag) -> dto::TagUsage {
+        let count = |pairs: &HashSet<(Uuid, Uuid)>| {
+            pairs
+                .iter()
+                .filter(|&&(_, tag_id)| tag_id == tag.id)
+                .count() as i64
+        };
+        dto::TagUsage {
+            tag: tag.clone(),
+            assignment_count: count(
+                &self
+                    .assignment_tags
+                    .read()
+                    .expect("assignment tags lock poisoned"),
+            ),
+            employee_assignment_count: count(
+                &self
+                    .employee_assignment_tags
+                    .read()
+                    .expect("employee assignment tags lock poisoned"),
+            ),
+        }
+    }
+}
+
+/// Points the pairs tagged `source_id` at `target_id`, dropping those the target already has.
+fn move_tags(pairs: &mut HashSet<(Uuid, Uuid)>, source_id: Uuid, target_id: Uuid) {
+    let moved: Vec<Uuid> = pairs
+        .iter()
+        .filter(|&&(_, tag_id)| tag_id == source_id)
+        .map(|&(row_id, _)| row_id)
+        .collect();
+    for row_id in moved {
+        pairs.remove(&(row_id, source_id));
+        pairs.insert((row_id, target_id));
+    }
+}
+
+impl TagRepo for InMemoryDb {
+    async fn create(&self, _actor: Uuid, ri: &dto::Tag) -> Result<dto::Tag, CreateTagError> {
+        let tag = dto::Tag {
+            id: Uuid::now_v7(),
+            version: 1,
+            ..ri.clone()
+        };
+        self.tags
+            .write()
+            .expect("tags lock poisoned")
+            .insert(tag.id, tag.clone());
+        Ok(tag)
+    }
+    async fn get(&self, id: Uuid) -> Result<Option<dto::TagUsage>, GetTagError> {
+        Ok(self
+            .tags
+            .read()
+            .expect("tags lock poisoned")
+            .get(&id)
+            .map(|x| self.usage(x)))
+    }
+    async fn get_all(&self) -> Result<Vec<dto::TagUsage>, GetTagsError> {
+        let mut ris: Vec<dto::TagUsage> = self
+            .tags
+            .read()
+            .expect("tags lock poisoned")
+            .values()
+            .map(|x| self.usage(x))
+            .collect();
+        ris.sort_by(|a, b| a.tag.tag.cmp(&b.tag.tag));
+        Ok(ris)
+    }
+    async fn rename(&self, _actor: Uuid, ri: &dto::Tag) -> Result<dto::Tag, RenameTagError> {
+        let mut tags = self.tags.write().expect("tags lock poisoned");
+        let current = tags
+            .get_mut(&ri.id)
+            .ok_or(RenameTagError::NotFound { id: ri.id })?;
+        if current.version != ri.version {
+            return Err(RenameTagError::Stale { id: ri.id });
+        }
+        *current = dto::Tag {
+            version: ri.version + 1,
+            ..ri.clone()
+        };
+        Ok(current.clone())
+    }
+    async fn merge(
+        &self,
+        _actor: Uuid,
+        source_id: Uuid,
+        source_version: i32,
+        target_id: Uuid,
+    ) -> Result<dto::TagUsage, MergeTagsError> {
+        if source_id == target_id {
+            return Err(MergeTagsError::SameTag { id: source_id });
+        }
+        let mut tags = self.tags.write().expect("tags lock poisoned");
+        match tags.get(&source_id) {
+            None => return Err(MergeTagsError::NotFound { id: source_id }),
+            Some(current) if current.version != source_version => {
+                return Err(MergeTagsError::Stale { id: source_id });
+            }
+            Some(_) => {}
+        }
+        let target = tags
+            .get_mut(&target_id)
+            .ok_or(MergeTagsError::NotFound { id: target_id })?;
+        target.version += 1;
+        let target = target.clone();
+        tags.remove(&source_id);
+        move_tags(
+            &mut self
+                .assignment_tags
+                .write()
+                .expect("assignment tags lock poisoned"),
+            source_id,
+            target_id,
+        );
+        move_tags(
+            &mut self
+                .employee_assignment_tags
+                .write()
+                .expect("employee assignment tags lock poisoned"),
+            source_id,
+            target_id,
+        );
+        Ok(self.usage(&target))
+    }
+    async fn delete(&self, _actor: Uuid, id: Uuid, version: i32) -> Result<(), DeleteTagError> {
+        let mut tags = self.tags.write().expect("tags lock poisoned");
+        match tags.get(&id) {
+            None => return Err(DeleteTagError::NotFound { id }),
+            Some(current) if current.version != version => {
+                return Err(DeleteTagError::Stale { id });
+            }
+            Some(_) => {}
+        }
+        tags.remove(&id);
+        self.assignment_tags
+            .write()
+            .expect("assignment tags lock poisoned")
+            .retain(|&(_, tag_id)| tag_id != id);
+        self.employee_assignment_tags
+            .write()
+            .expect("employee assignment tags lock poisoned")
+            .retain(|&(_, tag_id)| tag_id != id);
+        Ok(())
+    }
+}
@@ -0,0 +1,123 @@
+use thiserror::Error;
+use uuid::Uuid;
+
+/// Postgres error code for `unique_violation`.
+const UNIQUE_VIOLATION: &str = "23505";
+/// Postgres error code for `foreign_key_violation`.
+const FOREIGN_KEY_VIOLATION: &str = "23503";
+
+#[derive(Debug, Error)]
+pub enum CreateUserError {
+    #[error("User with email {email} already exists: {source}")]
+    Duplicate { email: String, source: sqlx::Error },
+
+    #[error("Role {role_id} does not exist: {source}")]
+    MissingRole { role_id: Uuid, source: sqlx::Error },
+
+    #[error("Failed to commit transactions: {source}")]
+    CommitFailed { source: sqlx::Error },
+
+    #[error("Unknown error for user: {source}")]
+    Unknown { source: sqlx::Error },
+}
+
+impl From<CreateUserError> for sqlx::Error {
+    fn from(error: CreateUserError) -> Self {
+        match error {
+            CreateUserError::Duplicate { source, .. } => source,
+            CreateUserError::MissingRole { source, .. } => source,
+            CreateUserError::CommitFailed { source } => source,
+            CreateUserError::Unknown { source } => source,
+        }
+    }
+}
+
+impl CreateUserError {
+    pub fn from_sqlx(error: sqlx::Error, email: &str, role_id: Option<Uuid>) -> Self {
+        let code = error
+            .as_database_error()
+            .and_then(|e| e.code())
+            .map(|c| c.into_owned());
+        match (code.as_deref(), role_id) {
+            (Some(UNIQUE_VIOLATION), _) => CreateUserError::Duplicate {
+                email: email.to_string(),
+                source: error,
+            },
+            (Some(FOREIGN_KEY_VIOLATION), Some(role_id)) => CreateUserError::MissingRole {
+                role_id,
+                source: error,
+            },
+            _ => CreateUserError::Unknown { source: error },
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum UpdateUserError {
+    #[error("User with id {id} not found")]
+    NotFound { id: Uuid },
+
+    #[error("Role {role_id} does not exist: {source}")]
+    MissingRole { role_id: Uuid, source: sqlx::Error },
+
+    #[error("Failed to commit transactions: {source}")]
+    CommitFailed { source: sqlx::Error },
+
+    #[error("Unknown error for user: {source}")]
+    Unknown { source: sqlx::Error },
+}
+
+impl From<UpdateUserError> for sqlx::Error {
+    fn from(error: UpdateUserError) -> Self {
+        match error {
+            UpdateUserError::NotFound { id: _ } => sqlx::Error::RowNotFound,
+            UpdateUserError::MissingRole { source, .. } => source,
+            UpdateUserError::CommitFailed { source } => source,
+            UpdateUserError::Unknown { source } => source,
+        }
+    }
+}
+
+impl UpdateUserError {
+    pub fn from_sqlx(error: sqlx::Error, role_id: Option<Uuid>) -> Self {
+        let code = error
+            .as_database_error()
+            .and_then(|e| e.code())
+            .map(|c| c.into_owned());
+        match (code.as_deref(), role_id) {
+            (Some(FOREIGN_KEY_VIOLATION), Some(role_id)) => UpdateUserError::MissingRole {
+                role_id,
+                source: error,
+            },
+            _ => UpdateUserError::Unknown { source: error },
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum GetUserError {
+    #[error("Unknown error for user: {source}")]
+    Unknown { source: sqlx::Error },
+}
+
+impl From<GetUserError> for sqlx::Error {
+    fn from(error: GetUserError) -> Self {
+        match error {
+            GetUserError::Unknown { source } => source,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum GetUsersError {
+    #[error("Unknown error for users: {source}")]
+    Unknown { source: sqlx::Error },
+}
+
+impl From<GetUsersError> for sqlx::Error {
+    fn from(error: GetUsersError) -> Self {
+        match error {
+            GetUsersError::Unknown { source } => source,
+        }
+    }
+}
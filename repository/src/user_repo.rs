@@ -0,0 +1,288 @@
+use crate::audit_repo::{record, snapshot};
+use crate::dao;
+use crate::in_memory_db::{InMemoryDb, foreign_key_violation, unique_violation};
+use crate::postgres_db::PostgresDb;
+use crate::user_errors::*;
+use domain::dto;
+use std::future::Future;
+use uuid::Uuid;
+
+pub trait UserRepo: Send + Sync + Clone + 'static {
+    fn create(
+        &self,
+        actor: Uuid,
+        ri: &dto::User,
+    ) -> impl Future<Output = Result<dto::User, CreateUserError>> + Send;
+    fn get(&self, id: Uuid)
+    -> impl Future<Output = Result<Option<dto::User>, GetUserError>> + Send;
+    /// Finds the user a token was issued for, by its subject and issuer.
+    fn get_by_external_id(
+        &self,
+        external_id: &str,
+        external_id_source: &str,
+    ) -> impl Future<Output = Result<Option<dto::User>, GetUserError>> + Send;
+    fn get_all(&self) -> impl Future<Output = Result<Vec<dto::User>, GetUsersError>> + Send;
+    /// Assigns the user a role, or removes it with `None`.
+    fn set_role(
+        &self,
+        actor: Uuid,
+        id: Uuid,
+        role_id: Option<Uuid>,
+    ) -> impl Future<Output = Result<dto::User, UpdateUserError>> + Send;
+    fn deactivate(
+        &self,
+        actor: Uuid,
+        id: Uuid,
+    ) -> impl Future<Output = Result<dto::User, UpdateUserError>> + Send;
+}
+
+impl UserRepo for PostgresDb {
+    async fn create(&self, actor: Uuid, ri: &dto::User) -> Result<dto::User, CreateUserError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| CreateUserError::Unknown { source: e })?;
+        let ret = sqlx::query_as!(
+            dao::User,
+            r#"INSERT INTO users(first_name, last_name, email, external_id, external_id_source, role_id)
+               VALUES ($1, $2, $3, $4, $5, $6)
+               RETURNING id, first_name, last_name, email, external_id, external_id_source, role_id, active"#,
+            ri.first_name,
+            ri.last_name,
+            ri.email,
+            ri.external_id,
+            ri.external_id_source,
+            ri.role_id
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| CreateUserError::from_sqlx(e, &ri.email, ri.role_id))?;
+        let after = snapshot(&mut tx, dto::AuditEntity::User, ret.id)
+            .await
+            .map_err(|e| CreateUserError::Unknown { source: e })?;
+        record(
+            &mut tx,
+            actor,
+            dto::AuditEntity::User,
+            ret.id,
+            dto::AuditAction::Create,
+            None,
+            after,
+        )
+        .await
+        .map_err(|e| CreateUserError::Unknown { source: e })?;
+        tx.commit()
+            .await
+            .map_err(|e| CreateUserError::CommitFailed { source: e })?;
+        Ok((&ret).into())
+    }
+    async fn get(&self, id: Uuid) -> Result<Option<dto::User>, GetUserError> {
+        let ri = sqlx::query_as!(
+            dao::User,
+            r#"SELECT id, first_name, last_name, email, external_id, external_id_source, role_id, active
+               FROM users WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| GetUserError::Unknown { source: e })?;
+        Ok(ri.map(|x| (&x).into()))
+    }
+    async fn get_by_external_id(
+        &self,
+        external_id: &str,
+        external_id_source: &str,
+    ) -> Result<Option<dto::User>, GetUserError> {
+        let ri = sqlx::query_as!(
+            dao::User,
+            r#"SELECT id, first_name, last_name, email, external_id, external_id_source, role_id, active
+               FROM users WHERE external_id = $1 AND external_id_source = $2"#,
+            external_id,
+            external_id_source
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| GetUserError::Unknown { source: e })?;
+        Ok(ri.map(|x| (&x).into()))
+    }
+    async fn get_all(&self) -> Result<Vec<dto::User>, GetUsersError> {
+        let ris = sqlx::query_as!(
+            dao::User,
+            r#"SELECT id, first_name, last_name, email, external_id, external_id_source, role_id, active
+               FROM users ORDER BY email"#,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| GetUsersError::Unknown { source: e })?;
+        Ok(ris.into_iter().map(|x| (&x).into()).collect())
+    }
+    async fn set_role(
+        &self,
+        actor: Uuid,
+        id: Uuid,
+        role_id: Option<Uuid>,
+    ) -> Result<dto::User, UpdateUserError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| UpdateUserError::Unknown { source: e })?;
+        let before = snapshot(&mut tx, dto::AuditEntity::User, id)
+            .await
+            .map_err(|e| UpdateUserError::Unknown { source: e })?;
+        let ret = sqlx::query_as!(
+            dao::User,
+            r#"UPDATE users SET role_id = $1, updated_at = CURRENT_TIMESTAMP WHERE id = $2
+               RETURNING id, first_name, last_name, email, external_id, external_id_source, role_id, active"#,
+            role_id,
+            id
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| UpdateUserError::from_sqlx(e, role_id))?
+        .ok_or(UpdateUserError::NotFound { id })?;
+        let after = snapshot(&mut tx, dto::AuditEntity::User, id)
+            .await
+            .map_err(|e| UpdateUserError::Unknown { source: e })?;
+        record(
+            &mut tx,
+            actor,
+            dto::AuditEntity::User,
+            id,
+            dto::AuditAction::Update,
+            before,
+            after,
+        )
+        .await
+        .map_err(|e| UpdateUserError::Unknown { source: e })?;
+        tx.commit()
+            .await
+            .map_err(|e| UpdateUserError::CommitFailed { source: e })?;
+        Ok((&ret).into())
+    }
+    async fn deactivate(&self, actor: Uuid, id: Uuid) -> Result<dto::User, UpdateUserError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| UpdateUserError::Unknown { source: e })?;
+        let before = snapshot(&mut tx, dto::AuditEntity::User, id)
+            .await
+            .map_err(|e| UpdateUserError::Unknown { source: e })?;
+        let ret = sqlx::query_as!(
+            dao::User,
+            r#"UPDATE users SET active = false, updated_at = CURRENT_TIMESTAMP WHERE id = $1
+               RETURNING id, first_name, last_name, email, external_id, external_id_source, role_id, active"#,
+            id
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| UpdateUserError::from_sqlx(e, None))?
+        .ok_or(UpdateUserError::NotFound { id })?;
+        let after = snapshot(&mut tx, dto::AuditEntity::User, id)
+            .await
+            .map_err(|e| UpdateUserError::Unknown { source: e })?;
+        record(
+            &mut tx,
+            actor,
+            dto::AuditEntity::User,
+            id,
+            dto::AuditAction::Update,
+            before,
+            after,
+        )
+        .await
+        .map_err(|e| UpdateUserError::Unknown { source: e })?;
+        tx.commit()
+            .await
+            .map_err(|e| UpdateUserError::CommitFailed { source: e })?;
+        Ok((&ret).into())
+    }
+}
+
+impl UserRepo for InMemoryDb {
+    async fn create(&self, _actor: Uuid, ri: &dto::User) -> Result<dto::User, CreateUserError> {
+        let roles = self.roles.read().expect("roles lock poisoned");
+        let mut users = self.users.write().expect("users lock poisoned");
+        if users.values().any(|x| x.email == ri.email) {
+            return Err(CreateUserError::Duplicate {
+                email: ri.email.clone(),
+                source: unique_violation("uk_users_email"),
+            });
+        }
+        if let Some(role_id) = ri.role_id
+            && !roles.contains_key(&role_id)
+        {
+            return Err(CreateUserError::MissingRole {
+                role_id,
+                source: foreign_key_violation("users_role_id_fkey"),
+            });
+        }
+        let user = dto::User {
+            id: Uuid::now_v7(),
+            active: true,
+            ..ri.clone()
+        };
+        users.insert(user.id, user.clone());
+        Ok(user)
+    }
+    async fn get(&self, id: Uuid) -> Result<Option<dto::User>, GetUserError> {
+        Ok(self
+            .users
+            .read()
+            .expect("users lock poisoned")
+            .get(&id)
+            .cloned())
+    }
+    async fn get_by_external_id(
+        &self,
+        external_id: &str,
+        external_id_source: &str,
+    ) -> Result<Option<dto::User>, GetUserError> {
+        Ok(self
+            .users
+            .read()
+            .expect("users lock poisoned")
+            .values()
+            .find(|x| x.external_id == external_id && x.external_id_source == external_id_source)
+            .cloned())
+    }
+    async fn get_all(&self) -> Result<Vec<dto::User>, GetUsersError> {
+        let mut ris: Vec<dto::User> = self
+            .users
+            .read()
+            .expect("users lock poisoned")
+            .values()
+            .cloned()
+            .collect();
+        ris.sort_by(|a, b| a.email.cmp(&b.email));
+        Ok(ris)
+    }
+    async fn set_role(
+        &self,
+        _actor: Uuid,
+        id: Uuid,
+        role_id: Option<Uuid>,
+    ) -> Result<dto::User, UpdateUserError> {
+        let roles = self.roles.read().expect("roles lock poisoned");
+        let mut users = self.users.write().expect("users lock poisoned");
+        let user = users.get_mut(&id).ok_or(UpdateUserError::NotFound { id })?;
+        if let Some(role_id) = role_id
+            && !roles.contains_key(&role_id)
+        {
+            return Err(UpdateUserError::MissingRole {
+                role_id,
+                source: foreign_key_violation("users_role_id_fkey"),
+            });
+        }
+        user.role_id = role_id;
+        Ok(user.clone())
+    }
+    async fn deactivate(&self, _actor: Uuid, id: Uuid) -> Result<dto::User, UpdateUserError> {
+        let mut users = self.users.write().expect("users lock poisoned");
+        let user = users.get_mut(&id).ok_or(UpdateUserError::NotFound { id })?;
+        user.active = false;
+        Ok(user.clone())
+    }
+}
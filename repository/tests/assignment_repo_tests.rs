@@ -0,0 +1,76 @@
+use bigdecimal::BigDecimal;
+use domain::dto;
+use repository::assignment_repo::AssignmentRepo;
+use repository::postgres_db::PostgresDb;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Recorded as the author of every write these tests make.
+const ACTOR: Uuid = Uuid::nil();
+
+fn ramp_up(year: i32) -> dto::Assignment {
+    dto::Assignment {
+        id: Uuid::nil(),
+        year,
+        jan: Some("0.25".parse::<BigDecimal>().unwrap()),
+        feb: Some("0.50".parse::<BigDecimal>().unwrap()),
+        mar: Some("1.00".parse::<BigDecimal>().unwrap()),
+        apr: None,
+        may: None,
+        jun: None,
+        jul: None,
+        aug: None,
+        sep: None,
+        oct: None,
+        nov: None,
+        dec: None,
+        version: 0,
+    }
+}
+
+#[sqlx::test(migrations = "../migrations")]
+#[ignore = "integration"]
+async fn test_crud_assignment(pool: PgPool) -> sqlx::Result<()> {
+    let repo = PostgresDb::new(pool);
+    let ret_ri = repo.create(ACTOR, &ramp_up(2025)).await?;
+    assert_ne!(ret_ri.id, Uuid::nil());
+    assert_eq!(ret_ri.year, 2025);
+    assert_eq!(ret_ri.jan, Some("0.25".parse::<BigDecimal>().unwrap()));
+    assert_eq!(ret_ri.dec, None);
+
+    let ramp_down = dto::Assignment {
+        id: ret_ri.id,
+        jan: Some("1.00".parse::<BigDecimal>().unwrap()),
+        dec: Some("0.10".parse::<BigDecimal>().unwrap()),
+        ..ret_ri.clone()
+    };
+    let ret2_ri = repo.update(ACTOR, &ramp_down).await?;
+    assert_eq!(
+        ret2_ri,
+        dto::Assignment {
+            version: ret_ri.version + 1,
+            ..ramp_down
+        }
+    );
+
+    let ret3_ri = repo.get(ret_ri.id).await?.unwrap();
+    assert_eq!(ret3_ri, ret2_ri);
+
+    repo.delete(ACTOR, ret_ri.id, ret2_ri.version).await?;
+    assert!(repo.get(ret_ri.id).await?.is_none());
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../migrations")]
+#[ignore = "integration"]
+async fn test_get_assignments_by_year(pool: PgPool) -> sqlx::Result<()> {
+    let repo = PostgresDb::new(pool);
+    repo.create(ACTOR, &ramp_up(2025)).await?;
+    repo.create(ACTOR, &ramp_up(2025)).await?;
+    repo.create(ACTOR, &ramp_up(2026)).await?;
+
+    assert_eq!(repo.get_all(None).await?.len(), 3);
+    assert_eq!(repo.get_all(Some(2025)).await?.len(), 2);
+    assert_eq!(repo.get_all(Some(2024)).await?.len(), 0);
+    Ok(())
+}
@@ -0,0 +1,246 @@
+use bigdecimal::BigDecimal;
+use chrono::NaiveDate;
+use domain::dto;
+use repository::audit_errors::GetAuditEntriesError;
+use repository::audit_repo::AuditRepo;
+use repository::employee_errors::UpdateEmployeeError;
+use repository::employee_repo::EmployeeRepo;
+use repository::postgres_db::PostgresDb;
+use repository::tag_repo::TagRepo;
+use repository::user_repo::UserRepo;
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Recorded as the author of the writes that set up each test.
+const ACTOR: Uuid = Uuid::nil();
+
+fn employee(email: &str) -> dto::Employee {
+    dto::Employee {
+        id: Uuid::nil(),
+        first_name: "run".to_string(),
+        last_name: "away".to_string(),
+        email: email.to_string(),
+        salary: "1000.00".parse::<BigDecimal>().unwrap(),
+        version: 0,
+        terminated_on: None,
+    }
+}
+
+fn query(entity: dto::AuditEntity, entity_id: Option<Uuid>) -> dto::AuditQuery {
+    dto::AuditQuery {
+        entity,
+        entity_id,
+        after: None,
+        limit: 50,
+    }
+}
+
+#[sqlx::test(migrations = "../migrations")]
+#[ignore = "integration"]
+async fn test_employee_writes_are_audited(pool: PgPool) -> sqlx::Result<()> {
+    let repo = PostgresDb::new(pool);
+    let actor = UserRepo::create(
+        &repo,
+        ACTOR,
+        &dto::User {
+            id: Uuid::nil(),
+            first_name: "audit".to_string(),
+            last_name: "or".to_string(),
+            email: "auditor@away.com".to_string(),
+            external_id: "sub-1".to_string(),
+            external_id_source: "https://issuer".to_string(),
+            role_id: None,
+            active: true,
+        },
+    )
+    .await?;
+
+    let created = EmployeeRepo::create(&repo, actor.id, &employee("run@away.com")).await?;
+    let other = EmployeeRepo::create(&repo, actor.id, &employee("this@away.com")).await?;
+    let updated = EmployeeRepo::update(
+        &repo,
+        actor.id,
+        &dto::Employee {
+            first_name: "walk".to_string(),
+            ..created.clone()
+        },
+    )
+    .await?;
+    let last_day = NaiveDate::from_ymd_opt(2025, 6, 30).unwrap();
+    EmployeeRepo::terminate(&repo, actor.id, created.id, updated.version, last_day).await?;
+
+    let entries = repo
+        .get_entries(&query(dto::AuditEntity::Employee, Some(created.id)))
+        .await?
+        .items;
+    let actions: Vec<dto::AuditAction> = entries.iter().map(|x| x.action).collect();
+    assert_eq!(
+        actions,
+        vec![
+            dto::AuditAction::Terminate,
+            dto::AuditAction::Update,
+            dto::AuditAction::Create
+        ]
+    );
+    assert!(entries.iter().all(|x| x.actor_id == actor.id
+        && x.actor_email.as_deref() == Some("auditor@away.com")
+        && x.entity_id == created.id));
+
+    let [terminated, changed, inserted] = &entries[..] else {
+        panic!("expected three entries, got {entries:?}");
+    };
+    assert_eq!(inserted.before, None);
+    assert_eq!(inserted.after.as_ref().unwrap()["first_name"], json!("run"));
+    assert_eq!(changed.before, inserted.after);
+    assert_eq!(changed.after.as_ref().unwrap()["first_name"], json!("walk"));
+    assert_eq!(changed.after.as_ref().unwrap()["version"], json!(2));
+    assert_eq!(terminated.before, changed.after);
+    assert_eq!(
+        terminated.after.as_ref().unwrap()["terminated_on"],
+        json!("2025-06-30")
+    );
+
+    let all = repo
+        .get_entries(&query(dto::AuditEntity::Employee, None))
+        .await?
+        .items;
+    assert_eq!(all.len(), 4);
+    assert!(all.iter().any(|x| x.entity_id == other.id));
+    assert!(
+        repo.get_entries(&query(dto::AuditEntity::Initiative, None))
+            .await?
+            .items
+            .is_empty()
+    );
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../migrations")]
+#[ignore = "integration"]
+async fn test_failed_writes_are_not_audited(pool: PgPool) -> sqlx::Result<()> {
+    let repo = PostgresDb::new(pool);
+    let created = EmployeeRepo::create(&repo, ACTOR, &employee("run@away.com")).await?;
+
+    let stale = EmployeeRepo::update(
+        &repo,
+        ACTOR,
+        &dto::Employee {
+            version: created.version + 1,
+            ..created.clone()
+        },
+    )
+    .await;
+    assert!(matches!(stale, Err(UpdateEmployeeError::Stale { .. })));
+
+    let entries = repo
+        .get_entries(&query(dto::AuditEntity::Employee, Some(created.id)))
+        .await?
+        .items;
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].action, dto::AuditAction::Create);
+    assert_eq!(entries[0].actor_email, None);
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../migrations")]
+#[ignore = "integration"]
+async fn test_tag_merge_is_audited(pool: PgPool) -> sqlx::Result<()> {
+    let repo = PostgresDb::new(pool);
+    let tag = |name: &str| dto::Tag {
+        id: Uuid::nil(),
+        tag: name.to_string(),
+        version: 0,
+    };
+    let source = TagRepo::create(&repo, ACTOR, &tag("front-end")).await?;
+    let target = TagRepo::create(&repo, ACTOR, &tag("frontend")).await?;
+    repo.merge(ACTOR, source.id, source.version, target.id)
+        .await?;
+
+    let entries = repo
+        .get_entries(&query(dto::AuditEntity::Tag, Some(source.id)))
+        .await?
+        .items;
+    assert_eq!(entries[0].action, dto::AuditAction::Merge);
+    assert_eq!(
+        entries[0].before.as_ref().unwrap()["tag"],
+        json!("front-end")
+    );
+    assert_eq!(entries[0].after.as_ref().unwrap()["id"], json!(target.id));
+
+    let entries = repo
+        .get_entries(&query(dto::AuditEntity::Tag, Some(target.id)))
+        .await?
+        .items;
+    assert_eq!(entries[0].action, dto::AuditAction::Update);
+    assert_eq!(entries[0].before.as_ref().unwrap()["version"], json!(1));
+    assert_eq!(entries[0].after.as_ref().unwrap()["version"], json!(2));
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../migrations")]
+#[ignore = "integration"]
+async fn test_audit_log_is_append_only(pool: PgPool) -> sqlx::Result<()> {
+    let repo = PostgresDb::new(pool.clone());
+    EmployeeRepo::create(&repo, ACTOR, &employee("run@away.com")).await?;
+
+    let update = sqlx::query("UPDATE audit_log SET actor_id = $1")
+        .bind(Uuid::now_v7())
+        .execute(&pool)
+        .await;
+    assert!(update.is_err());
+    let delete = sqlx::query("DELETE FROM audit_log").execute(&pool).await;
+    assert!(delete.is_err());
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../migrations")]
+#[ignore = "integration"]
+async fn test_entries_are_paged_newest_first(pool: PgPool) -> sqlx::Result<()> {
+    let repo = PostgresDb::new(pool);
+    let mut created = vec![];
+    for n in 0..5 {
+        created
+            .push(EmployeeRepo::create(&repo, ACTOR, &employee(&format!("{}@away.com", n))).await?);
+    }
+
+    let first = repo
+        .get_entries(&dto::AuditQuery {
+            limit: 2,
+            ..query(dto::AuditEntity::Employee, None)
+        })
+        .await?;
+    let second = repo
+        .get_entries(&dto::AuditQuery {
+            after: first.next,
+            limit: 2,
+            ..query(dto::AuditEntity::Employee, None)
+        })
+        .await?;
+    let last = repo
+        .get_entries(&dto::AuditQuery {
+            after: second.next,
+            limit: 2,
+            ..query(dto::AuditEntity::Employee, None)
+        })
+        .await?;
+    assert_eq!(first.next, first.items.last().map(|x| x.id));
+    assert_eq!(last.next, None);
+    let listed: Vec<Uuid> = [first.items, second.items, last.items]
+        .concat()
+        .iter()
+        .map(|x| x.entity_id)
+        .collect();
+    let newest_first: Vec<Uuid> = created.iter().rev().map(|x| x.id).collect();
+    assert_eq!(listed, newest_first);
+
+    assert!(matches!(
+        repo.get_entries(&dto::AuditQuery {
+            after: Some(Uuid::now_v7()),
+            ..query(dto::AuditEntity::Employee, None)
+        })
+        .await,
+        Err(GetAuditEntriesError::InvalidCursor { .. })
+    ));
+    Ok(())
+}
@@ -0,0 +1,467 @@
+use bigdecimal::BigDecimal;
+use domain::dto;
+use repository::assignment_errors::UpdateAssignmentError;
+use repository::assignment_repo::AssignmentRepo;
+use repository::employee_assignment_errors::{
+    CreateEmployeeAssignmentError, DeleteEmployeeAssignmentError, SavePlanError,
+    UpdateEmployeeAssignmentError,
+};
+use repository::employee_assignment_repo::EmployeeAssignmentRepo;
+use repository::employee_repo::EmployeeRepo;
+use repository::initiative_repo::InitiativeRepo;
+use repository::postgres_db::PostgresDb;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Recorded as the author of every write these tests make.
+const ACTOR: Uuid = Uuid::nil();
+
+struct Fixture {
+    employee: dto::Employee,
+    initiative: dto::Initiative,
+    assignment: dto::Assignment,
+}
+
+async fn fixture(repo: &PostgresDb, email: &str, initiative: &str) -> Fixture {
+    let employee = EmployeeRepo::create(
+        repo,
+        ACTOR,
+        &dto::Employee {
+            id: Uuid::nil(),
+            first_name: "run".to_string(),
+            last_name: "away".to_string(),
+            email: email.to_string(),
+            salary: "32_000.00".parse::<BigDecimal>().unwrap(),
+            version: 0,
+            terminated_on: None,
+        },
+    )
+    .await
+    .unwrap();
+    let initiative = InitiativeRepo::create(
+        repo,
+        ACTOR,
+        &dto::Initiative {
+            id: Uuid::nil(),
+            initiative: initiative.to_string(),
+            version: 0,
+        },
+    )
+    .await
+    .unwrap();
+    let assignment = AssignmentRepo::create(
+        repo,
+        ACTOR,
+        &dto::Assignment {
+            id: Uuid::nil(),
+            year: 2025,
+            jan: Some("1.00".parse::<BigDecimal>().unwrap()),
+            feb: None,
+            mar: None,
+            apr: None,
+            may: None,
+            jun: None,
+            jul: None,
+            aug: None,
+            sep: None,
+            oct: None,
+            nov: None,
+            dec: None,
+            version: 0,
+        },
+    )
+    .await
+    .unwrap();
+    Fixture {
+        employee,
+        initiative,
+        assignment,
+    }
+}
+
+fn link(f: &Fixture, allocation: &str) -> dto::EmployeeAssignment {
+    dto::EmployeeAssignment {
+        id: Uuid::nil(),
+        employee_id: f.employee.id,
+        assignment_id: f.assignment.id,
+        allocation: allocation.parse::<BigDecimal>().unwrap(),
+        initiative_id: f.initiative.id,
+        version: 0,
+    }
+}
+
+#[sqlx::test(migrations = "../migrations")]
+#[ignore = "integration"]
+async fn test_crud_employee_assignment(pool: PgPool) -> sqlx::Result<()> {
+    let repo = PostgresDb::new(pool);
+    let f = fixture(&repo, "run@away.com", "Platform").await;
+
+    let ret_ri = EmployeeAssignmentRepo::create(&repo, ACTOR, &link(&f, "0.50")).await?;
+    assert_ne!(ret_ri.id, Uuid::nil());
+    assert_eq!(ret_ri.allocation, "0.50".parse::<BigDecimal>().unwrap());
+
+    let changed = dto::EmployeeAssignment {
+        allocation: "0.75".parse::<BigDecimal>().unwrap(),
+        ..ret_ri.clone()
+    };
+    let ret2_ri = EmployeeAssignmentRepo::update(&repo, ACTOR, &changed).await?;
+    assert_eq!(
+        ret2_ri,
+        dto::EmployeeAssignment {
+            version: ret_ri.version + 1,
+            ..changed
+        }
+    );
+
+    assert_eq!(
+        repo.get_for_employee(f.employee.id).await?,
+        vec![ret2_ri.clone()]
+    );
+    assert_eq!(
+        repo.get_for_initiative(f.initiative.id).await?,
+        vec![ret2_ri.clone()]
+    );
+
+    EmployeeAssignmentRepo::delete(&repo, ACTOR, f.employee.id, ret_ri.id, ret2_ri.version).await?;
+    assert!(repo.get_for_employee(f.employee.id).await?.is_empty());
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../migrations")]
+#[ignore = "integration"]
+async fn test_employee_assignment_errors(pool: PgPool) -> sqlx::Result<()> {
+    let repo = PostgresDb::new(pool);
+    let f = fixture(&repo, "run@away.com", "Platform").await;
+    let created = EmployeeAssignmentRepo::create(&repo, ACTOR, &link(&f, "0.50")).await?;
+
+    let duplicate = EmployeeAssignmentRepo::create(&repo, ACTOR, &link(&f, "0.25")).await;
+    assert!(matches!(
+        duplicate,
+        Err(CreateEmployeeAssignmentError::Duplicate { .. })
+    ));
+
+    let other = fixture(&repo, "other@away.com", "Mobile").await;
+    let too_much = EmployeeAssignmentRepo::create(&repo, ACTOR, &link(&other, "1.50")).await;
+    assert!(matches!(
+        too_much,
+        Err(CreateEmployeeAssignmentError::InvalidAllocation { .. })
+    ));
+
+    let missing = dto::EmployeeAssignment {
+        initiative_id: Uuid::now_v7(),
+        ..link(&other, "0.25")
+    };
+    let missing = EmployeeAssignmentRepo::create(&repo, ACTOR, &missing).await;
+    assert!(matches!(
+        missing,
+        Err(CreateEmployeeAssignmentError::MissingReference { .. })
+    ));
+
+    let not_found = dto::EmployeeAssignment {
+        id: Uuid::now_v7(),
+        ..link(&f, "0.25")
+    };
+    let not_found = EmployeeAssignmentRepo::update(&repo, ACTOR, &not_found).await;
+    assert!(matches!(
+        not_found,
+        Err(UpdateEmployeeAssignmentError::NotFound { .. })
+    ));
+
+    let stale = dto::EmployeeAssignment {
+        version: created.version - 1,
+        ..created.clone()
+    };
+    let stale = EmployeeAssignmentRepo::update(&repo, ACTOR, &stale).await;
+    assert!(matches!(
+        stale,
+        Err(UpdateEmployeeAssignmentError::Stale { id }) if id == created.id
+    ));
+
+    // Another employee's link is reported missing rather than stale.
+    let not_theirs = EmployeeAssignmentRepo::delete(
+        &repo,
+        ACTOR,
+        other.employee.id,
+        created.id,
+        created.version,
+    )
+    .await;
+    assert!(matches!(
+        not_theirs,
+        Err(DeleteEmployeeAssignmentError::NotFound { .. })
+    ));
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../migrations")]
+#[ignore = "integration"]
+async fn test_get_scheduled(pool: PgPool) -> sqlx::Result<()> {
+    let repo = PostgresDb::new(pool);
+    let f = fixture(&repo, "run@away.com", "Platform").await;
+    let other = fixture(&repo, "other@away.com", "Mobile").await;
+    let ret_ri = EmployeeAssignmentRepo::create(&repo, ACTOR, &link(&f, "0.50")).await?;
+    EmployeeAssignmentRepo::create(&repo, ACTOR, &link(&other, "0.25")).await?;
+
+    assert_eq!(repo.get_scheduled(2025, None).await?.len(), 2);
+    assert!(repo.get_scheduled(2026, None).await?.is_empty());
+
+    let scheduled = repo.get_scheduled(2025, Some(f.employee.id)).await?;
+    assert_eq!(
+        scheduled,
+        vec![dto::ScheduledAssignment {
+            employee_assignment: ret_ri.clone(),
+            assignment: f.assignment.clone(),
+        }]
+    );
+    assert_eq!(
+        repo.get_scheduled_for(2025, &[f.employee.id]).await?,
+        scheduled
+    );
+    assert_eq!(
+        repo.get_scheduled_for(2025, &[f.employee.id, other.employee.id])
+            .await?
+            .len(),
+        2
+    );
+    assert!(repo.get_scheduled_for(2025, &[]).await?.is_empty());
+    assert_eq!(
+        repo.get_for_assignment(f.assignment.id).await?,
+        vec![ret_ri]
+    );
+    assert!(repo.get_planned_elsewhere(2025).await?.is_empty());
+    let mut elsewhere = repo.get_planned_elsewhere(2026).await?;
+    elsewhere.sort_by_key(|x| x.0 != f.employee.id);
+    assert_eq!(
+        elsewhere,
+        vec![
+            (f.employee.id, f.initiative.id, 2025),
+            (other.employee.id, other.initiative.id, 2025),
+        ]
+    );
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../migrations")]
+#[ignore = "integration"]
+async fn test_concurrent_writes_cannot_overallocate(pool: PgPool) -> sqlx::Result<()> {
+    let repo = PostgresDb::new(pool);
+    let f = fixture(&repo, "run@away.com", "Platform").await;
+    let mobile = InitiativeRepo::create(
+        &repo,
+        ACTOR,
+        &dto::Initiative {
+            id: Uuid::nil(),
+            initiative: "Mobile".to_string(),
+            version: 0,
+        },
+    )
+    .await
+    .unwrap();
+    let on_platform = link(&f, "0.60");
+    let on_mobile = dto::EmployeeAssignment {
+        initiative_id: mobile.id,
+        ..link(&f, "0.60")
+    };
+
+    // Each write alone fits; together they would put January at 120%.
+    let (platform, mobile) = tokio::join!(
+        EmployeeAssignmentRepo::create(&repo, ACTOR, &on_platform),
+        EmployeeAssignmentRepo::create(&repo, ACTOR, &on_mobile),
+    );
+    let (created, refused) = match (platform, mobile) {
+        (Ok(created), Err(refused)) | (Err(refused), Ok(created)) => (created, refused),
+        other => panic!("expected exactly one write to pass, got {:?}", other),
+    };
+    assert!(matches!(
+        refused,
+        CreateEmployeeAssignmentError::Overallocated { year: 2025, ref months, .. }
+            if *months == vec![0]
+    ));
+    assert_eq!(
+        repo.get_for_employee(f.employee.id).await?,
+        vec![created.clone()]
+    );
+    // Either write may have won; the other initiative is still free to link.
+    let free = if created.initiative_id == on_platform.initiative_id {
+        on_mobile
+    } else {
+        on_platform
+    };
+
+    let raised = dto::EmployeeAssignment {
+        allocation: "1.00".parse::<BigDecimal>().unwrap(),
+        ..created.clone()
+    };
+    EmployeeAssignmentRepo::update(&repo, ACTOR, &raised).await?;
+    let half = AssignmentRepo::create(&repo, ACTOR, &curve(2025, "0.50")).await?;
+    let on_half = dto::EmployeeAssignment {
+        assignment_id: half.id,
+        ..free
+    };
+    assert!(matches!(
+        EmployeeAssignmentRepo::create(&repo, ACTOR, &on_half).await,
+        Err(CreateEmployeeAssignmentError::Overallocated { .. })
+    ));
+
+    // Raising a curve is checked for everyone following it.
+    let lowered = dto::EmployeeAssignment {
+        allocation: "0.50".parse::<BigDecimal>().unwrap(),
+        version: created.version + 1,
+        ..created
+    };
+    EmployeeAssignmentRepo::update(&repo, ACTOR, &lowered).await?;
+    EmployeeAssignmentRepo::create(&repo, ACTOR, &on_half).await?;
+    let steeper = dto::Assignment {
+        jan: Some("1.00".parse::<BigDecimal>().unwrap()),
+        ..half
+    };
+    assert!(matches!(
+        AssignmentRepo::update(&repo, ACTOR, &steeper).await,
+        Err(UpdateAssignmentError::Overallocated { employee_id, .. }) if employee_id == f.employee.id
+    ));
+    Ok(())
+}
+
+fn curve(year: i32, jan: &str) -> dto::Assignment {
+    let mut months: [Option<BigDecimal>; 12] = Default::default();
+    months[0] = Some(jan.parse::<BigDecimal>().unwrap());
+    dto::Assignment::from_months(year, months)
+}
+
+#[sqlx::test(migrations = "../migrations")]
+#[ignore = "integration"]
+async fn test_save_plan(pool: PgPool) -> sqlx::Result<()> {
+    let repo = PostgresDb::new(pool);
+    let f = fixture(&repo, "run@away.com", "Platform").await;
+    let other = fixture(&repo, "other@away.com", "Mobile").await;
+    let created = EmployeeAssignmentRepo::create(&repo, ACTOR, &link(&f, "0.50")).await?;
+    let rewrite = dto::PlannedLink {
+        link: Some(created.clone()),
+        employee_id: f.employee.id,
+        initiative_id: f.initiative.id,
+        allocation: "1.00".parse::<BigDecimal>().unwrap(),
+        assignment: dto::Assignment {
+            id: f.assignment.id,
+            version: f.assignment.version,
+            ..curve(2025, "0.40")
+        },
+    };
+    let add = dto::PlannedLink {
+        link: None,
+        employee_id: other.employee.id,
+        initiative_id: other.initiative.id,
+        allocation: "1.00".parse::<BigDecimal>().unwrap(),
+        assignment: curve(2025, "0.30"),
+    };
+    repo.save_plan(ACTOR, &[rewrite.clone(), add]).await?;
+
+    let mut scheduled = repo.get_scheduled(2025, None).await?;
+    scheduled.sort_by_key(|x| x.employee_assignment.employee_id != f.employee.id);
+    let [own, added] = &scheduled[..] else {
+        panic!("expected two links, got {:?}", scheduled);
+    };
+    assert_eq!(own.employee_assignment.id, created.id);
+    assert_eq!(own.employee_assignment.version, created.version + 1);
+    assert_eq!(
+        own.employee_assignment.allocation,
+        "1.00".parse::<BigDecimal>().unwrap()
+    );
+    assert_eq!(own.assignment.id, f.assignment.id);
+    assert_eq!(own.assignment.version, f.assignment.version + 1);
+    assert_eq!(
+        own.assignment.jan,
+        Some("0.40".parse::<BigDecimal>().unwrap())
+    );
+    assert_eq!(added.employee_assignment.employee_id, other.employee.id);
+    assert_ne!(added.assignment.id, other.assignment.id);
+    assert_eq!(
+        added.assignment.jan,
+        Some("0.30".parse::<BigDecimal>().unwrap())
+    );
+
+    // Replaying the plan finds the curve changed and stores nothing, not even the new link.
+    let third = fixture(&repo, "third@away.com", "Web").await;
+    let late = dto::PlannedLink {
+        link: None,
+        employee_id: third.employee.id,
+        initiative_id: third.initiative.id,
+        allocation: "1.00".parse::<BigDecimal>().unwrap(),
+        assignment: curve(2025, "0.20"),
+    };
+    let stale = repo.save_plan(ACTOR, &[late, rewrite]).await;
+    assert!(matches!(
+        stale,
+        Err(SavePlanError::StaleAssignment { id }) if id == f.assignment.id
+    ));
+    assert!(repo.get_for_employee(third.employee.id).await?.is_empty());
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../migrations")]
+#[ignore = "integration"]
+async fn test_save_plan_checks_links_written_since_the_plan_was_read(
+    pool: PgPool,
+) -> sqlx::Result<()> {
+    let repo = PostgresDb::new(pool);
+    let f = fixture(&repo, "run@away.com", "Platform").await;
+    let mobile = InitiativeRepo::create(
+        &repo,
+        ACTOR,
+        &dto::Initiative {
+            id: Uuid::nil(),
+            initiative: "Mobile".to_string(),
+            version: 0,
+        },
+    )
+    .await
+    .unwrap();
+    let existing = EmployeeAssignmentRepo::create(&repo, ACTOR, &link(&f, "0.50")).await?;
+    // Planned while `existing` was the only link following the curve.
+    let rewrite = dto::PlannedLink {
+        link: Some(existing.clone()),
+        employee_id: f.employee.id,
+        initiative_id: f.initiative.id,
+        allocation: "1.00".parse::<BigDecimal>().unwrap(),
+        assignment: dto::Assignment {
+            id: f.assignment.id,
+            version: f.assignment.version,
+            ..curve(2025, "0.40")
+        },
+    };
+    let added = dto::PlannedLink {
+        link: None,
+        employee_id: f.employee.id,
+        initiative_id: mobile.id,
+        allocation: "1.00".parse::<BigDecimal>().unwrap(),
+        assignment: curve(2025, "0.80"),
+    };
+
+    // The existing link already takes half of January, so the new one does not fit.
+    assert!(matches!(
+        repo.save_plan(ACTOR, std::slice::from_ref(&added)).await,
+        Err(SavePlanError::Overallocated { year: 2025, ref months, .. }) if *months == vec![0]
+    ));
+    assert_eq!(
+        repo.get_for_employee(f.employee.id).await?,
+        vec![existing.clone()]
+    );
+
+    // A link that started following the curve keeps it from being rewritten under it.
+    let follower = EmployeeAssignmentRepo::create(
+        &repo,
+        ACTOR,
+        &dto::EmployeeAssignment {
+            initiative_id: mobile.id,
+            ..link(&f, "0.50")
+        },
+    )
+    .await?;
+    assert!(matches!(
+        repo.save_plan(ACTOR, &[rewrite]).await,
+        Err(SavePlanError::StaleAssignment { id }) if id == f.assignment.id
+    ));
+    let mut expected = vec![existing, follower];
+    expected.sort_by_key(|x| x.id);
+    assert_eq!(repo.get_for_employee(f.employee.id).await?, expected);
+    Ok(())
+}
@@ -1,22 +1,32 @@
 use bigdecimal::BigDecimal;
+use chrono::NaiveDate;
 use domain::dto;
+use repository::employee_errors::{
+    AddSalaryError, CreateEmployeesError, GetEmployeesError, RestoreEmployeeError,
+    TerminateEmployeeError, UpdateEmployeeError,
+};
 use repository::employee_repo::EmployeeRepo;
 use repository::postgres_db::PostgresDb;
 use sqlx::PgPool;
 use uuid::Uuid;
 
+/// Recorded as the author of every write these tests make.
+const ACTOR: Uuid = Uuid::nil();
+
 #[sqlx::test(migrations = "../migrations")]
 #[ignore = "integration"]
 async fn test_create_employee_repo(pool: PgPool) -> sqlx::Result<()> {
-    let repo: PostgresDb = EmployeeRepo::new(pool);
+    let repo = PostgresDb::new(pool);
     let employee = dto::Employee {
         id: uuid::Uuid::nil(),
         first_name: "foo".to_string(),
         last_name: "bar".to_string(),
         email: "foo@email.com".to_string(),
         salary: "1000.00".parse::<BigDecimal>().unwrap(),
+        version: 0,
+        terminated_on: None,
     };
-    let ret_employee = repo.create(&employee).await?;
+    let ret_employee = repo.create(ACTOR, &employee).await?;
 
     assert_ne!(ret_employee.id, uuid::Uuid::nil());
     assert_eq!(ret_employee.first_name, "foo");
@@ -32,23 +42,27 @@ async fn test_create_employee_repo(pool: PgPool) -> sqlx::Result<()> {
 #[sqlx::test(migrations = "../migrations")]
 #[ignore = "integration"]
 async fn test_get_employees(pool: PgPool) -> sqlx::Result<()> {
-    let repo: PostgresDb = EmployeeRepo::new(pool);
+    let repo = PostgresDb::new(pool);
     let ri = dto::Employee {
         id: Uuid::nil(),
         first_name: "run".to_string(),
         last_name: "away".to_string(),
         email: "run@away.com".to_string(),
         salary: "32_000.00".parse::<BigDecimal>().unwrap(),
+        version: 0,
+        terminated_on: None,
     };
-    let ret_ri = repo.create(&ri).await.unwrap();
+    let ret_ri = repo.create(ACTOR, &ri).await.unwrap();
     let ri2 = dto::Employee {
         id: Uuid::nil(),
         first_name: "foo".to_string(),
         last_name: "bar".to_string(),
         email: "this@away.com".to_string(),
         salary: "30_000.00".parse::<BigDecimal>().unwrap(),
+        version: 0,
+        terminated_on: None,
     };
-    let ret_ri2 = repo.create(&ri2).await.unwrap();
+    let ret_ri2 = repo.create(ACTOR, &ri2).await.unwrap();
 
     let employees: Vec<dto::Employee> = repo.get_all().await?;
     assert_eq!(employees.len(), 2);
@@ -60,15 +74,17 @@ async fn test_get_employees(pool: PgPool) -> sqlx::Result<()> {
 #[sqlx::test(migrations = "../migrations")]
 #[ignore = "integration"]
 async fn test_crud_employee(pool: PgPool) -> sqlx::Result<()> {
-    let repo: PostgresDb = EmployeeRepo::new(pool);
+    let repo = PostgresDb::new(pool);
     let ri = dto::Employee {
         id: Uuid::nil(),
         first_name: "run".to_string(),
         last_name: "away".to_string(),
         email: "run@away.com".to_string(),
         salary: "32_000.00".parse::<BigDecimal>().unwrap(),
+        version: 0,
+        terminated_on: None,
     };
-    let ret_ri = repo.create(&ri).await.unwrap();
+    let ret_ri = repo.create(ACTOR, &ri).await.unwrap();
 
     assert_eq!(ret_ri.first_name, "run");
     assert_eq!(ret_ri.last_name, "away");
@@ -82,8 +98,10 @@ async fn test_crud_employee(pool: PgPool) -> sqlx::Result<()> {
         email: "bar@another.com".to_string(),
         salary: "45_000.00".parse::<BigDecimal>().unwrap(),
         id: ret_ri.id,
+        version: ret_ri.version,
+        terminated_on: None,
     };
-    let ret2_ri = repo.update(&updated_pet).await.unwrap();
+    let ret2_ri = repo.update(ACTOR, &updated_pet).await.unwrap();
     assert_eq!(ret2_ri.first_name, "bar");
     assert_eq!(ret2_ri.last_name, "another");
     assert_eq!(ret2_ri.email, "bar@another.com");
@@ -97,9 +115,310 @@ async fn test_crud_employee(pool: PgPool) -> sqlx::Result<()> {
     assert_eq!(ret3_ri.salary, "45_000.00".parse::<BigDecimal>().unwrap());
     assert_eq!(ret3_ri.id, ret_ri.id.clone());
 
-    repo.delete(ret_ri.id).await?;
-    let ret3_ri = repo.get(ret_ri.id).await.unwrap();
-    assert!(ret3_ri.is_none());
+    let last_day = NaiveDate::from_ymd_opt(2025, 6, 30).unwrap();
+    let left = repo
+        .terminate(ACTOR, ret_ri.id, ret2_ri.version, last_day)
+        .await?;
+    assert_eq!(left.terminated_on, Some(last_day));
+    assert_eq!(repo.get(ret_ri.id).await?, Some(left));
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../migrations")]
+#[ignore = "integration"]
+async fn test_get_employee_pages(pool: PgPool) -> sqlx::Result<()> {
+    let repo = PostgresDb::new(pool);
+    let mut created = Vec::new();
+    for (first_name, salary) in [("ann", "10.00"), ("bob", "30.00"), ("cal", "20.00")] {
+        let ri = dto::Employee {
+            id: Uuid::nil(),
+            first_name: first_name.to_string(),
+            last_name: "away".to_string(),
+            email: format!("{first_name}@away.com"),
+            salary: salary.parse::<BigDecimal>().unwrap(),
+            version: 0,
+            terminated_on: None,
+        };
+        created.push(repo.create(ACTOR, &ri).await.unwrap());
+    }
+
+    let query = dto::EmployeeQuery {
+        sort: dto::EmployeeSort::Salary,
+        order: dto::SortOrder::Desc,
+        limit: 2,
+        ..Default::default()
+    };
+    let first = repo.get_page(&query).await?;
+    assert_eq!(first.items, vec![created[1].clone(), created[2].clone()]);
+    assert_eq!(first.next, Some(created[2].id));
+    let second = repo
+        .get_page(&dto::EmployeeQuery {
+            after: first.next,
+            ..query.clone()
+        })
+        .await?;
+    assert_eq!(second.items, vec![created[0].clone()]);
+    assert_eq!(second.next, None);
+
+    let filtered = repo
+        .get_page(&dto::EmployeeQuery {
+            name_prefix: Some("B".to_string()),
+            max_salary: Some("30".parse::<BigDecimal>().unwrap()),
+            limit: 10,
+            ..Default::default()
+        })
+        .await?;
+    assert_eq!(filtered.items, vec![created[1].clone()]);
+
+    let missing = repo
+        .get_page(&dto::EmployeeQuery {
+            after: Some(Uuid::now_v7()),
+            ..query
+        })
+        .await;
+    assert!(matches!(
+        missing,
+        Err(GetEmployeesError::InvalidCursor { id: _ })
+    ));
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../migrations")]
+#[ignore = "integration"]
+async fn test_update_employee_errors(pool: PgPool) -> sqlx::Result<()> {
+    let repo = PostgresDb::new(pool);
+    let mut created = Vec::new();
+    for email in ["run@away.com", "this@away.com"] {
+        let ri = dto::Employee {
+            id: Uuid::nil(),
+            first_name: "run".to_string(),
+            last_name: "away".to_string(),
+            email: email.to_string(),
+            salary: "32_000.00".parse::<BigDecimal>().unwrap(),
+            version: 0,
+            terminated_on: None,
+        };
+        created.push(repo.create(ACTOR, &ri).await.unwrap());
+    }
+
+    let taken = dto::Employee {
+        email: "run@away.com".to_string(),
+        ..created[1].clone()
+    };
+    assert!(matches!(
+        repo.update(ACTOR, &taken).await,
+        Err(UpdateEmployeeError::Duplicate { email, source: _ }) if email == "run@away.com"
+    ));
+
+    let missing = dto::Employee {
+        id: Uuid::now_v7(),
+        ..created[1].clone()
+    };
+    assert!(matches!(
+        repo.update(ACTOR, &missing).await,
+        Err(UpdateEmployeeError::NotFound { id }) if id == missing.id
+    ));
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../migrations")]
+#[ignore = "integration"]
+async fn test_stale_employee_writes(pool: PgPool) -> sqlx::Result<()> {
+    let repo = PostgresDb::new(pool);
+    let ri = dto::Employee {
+        id: Uuid::nil(),
+        first_name: "run".to_string(),
+        last_name: "away".to_string(),
+        email: "run@away.com".to_string(),
+        salary: "32_000.00".parse::<BigDecimal>().unwrap(),
+        version: 0,
+        terminated_on: None,
+    };
+    let created = repo.create(ACTOR, &ri).await?;
+    assert_eq!(created.version, 1);
+
+    // Two planners read version 1; the first write wins and the second is refused.
+    let first = dto::Employee {
+        first_name: "first".to_string(),
+        ..created.clone()
+    };
+    let second = dto::Employee {
+        first_name: "second".to_string(),
+        ..created.clone()
+    };
+    assert_eq!(repo.update(ACTOR, &first).await?.version, 2);
+    assert!(matches!(
+        repo.update(ACTOR, &second).await,
+        Err(UpdateEmployeeError::Stale { id }) if id == created.id
+    ));
+    assert_eq!(repo.get(created.id).await?.unwrap().first_name, "first");
+
+    let last_day = NaiveDate::from_ymd_opt(2025, 6, 30).unwrap();
+    assert!(matches!(
+        repo.terminate(ACTOR, created.id, created.version, last_day).await,
+        Err(TerminateEmployeeError::Stale { id }) if id == created.id
+    ));
+    assert!(matches!(
+        repo.terminate(ACTOR, Uuid::now_v7(), 2, last_day).await,
+        Err(TerminateEmployeeError::NotFound { id: _ })
+    ));
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../migrations")]
+#[ignore = "integration"]
+async fn test_terminate_and_restore_employee(pool: PgPool) -> sqlx::Result<()> {
+    let repo = PostgresDb::new(pool);
+    let mut created = Vec::new();
+    for email in ["ann@away.com", "bob@away.com"] {
+        let ri = dto::Employee {
+            id: Uuid::nil(),
+            first_name: "run".to_string(),
+            last_name: "away".to_string(),
+            email: email.to_string(),
+            salary: "32_000.00".parse::<BigDecimal>().unwrap(),
+            version: 0,
+            terminated_on: None,
+        };
+        created.push(repo.create(ACTOR, &ri).await?);
+    }
+    assert!(matches!(
+        repo.restore(ACTOR, created[0].id, created[0].version).await,
+        Err(RestoreEmployeeError::NotTerminated { id }) if id == created[0].id
+    ));
+
+    let last_day = NaiveDate::from_ymd_opt(2025, 6, 30).unwrap();
+    let left = repo
+        .terminate(ACTOR, created[0].id, created[0].version, last_day)
+        .await?;
+    let query = dto::EmployeeQuery {
+        sort: dto::EmployeeSort::Email,
+        limit: 10,
+        ..Default::default()
+    };
+    assert_eq!(repo.get_page(&query).await?.items, vec![created[1].clone()]);
+    let everyone = repo
+        .get_page(&dto::EmployeeQuery {
+            include_terminated: true,
+            ..query.clone()
+        })
+        .await?;
+    assert_eq!(everyone.items, vec![left.clone(), created[1].clone()]);
+    assert_eq!(repo.get_all().await?.len(), 2);
+
+    assert!(matches!(
+        repo.restore(ACTOR, left.id, created[0].version).await,
+        Err(RestoreEmployeeError::Stale { id }) if id == left.id
+    ));
+    let back = repo.restore(ACTOR, left.id, left.version).await?;
+    assert_eq!(back.terminated_on, None);
+    assert_eq!(back.version, left.version + 1);
+    assert_eq!(repo.get_page(&query).await?.items.len(), 2);
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../migrations")]
+#[ignore = "integration"]
+async fn test_salary_history(pool: PgPool) -> sqlx::Result<()> {
+    let repo = PostgresDb::new(pool);
+    let ri = dto::Employee {
+        id: Uuid::nil(),
+        first_name: "run".to_string(),
+        last_name: "away".to_string(),
+        email: "run@away.com".to_string(),
+        salary: "100_000.00".parse::<BigDecimal>().unwrap(),
+        version: 0,
+        terminated_on: None,
+    };
+    let created = repo.create(ACTOR, &ri).await?;
+    let history = repo.get_salaries(Some(created.id)).await?;
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].salary, created.salary);
+    let today = history[0].effective_from;
+
+    // Neither a corrected past rate nor a scheduled raise changes what is paid today.
+    let rate = |effective_from: NaiveDate, salary: &str| dto::Salary {
+        employee_id: created.id,
+        effective_from,
+        salary: salary.parse::<BigDecimal>().unwrap(),
+    };
+    let past = rate(NaiveDate::from_ymd_opt(2020, 1, 1).unwrap(), "90_000.00");
+    let future = rate(today + chrono::Days::new(365), "150_000.00");
+    let corrected = repo.add_salary(ACTOR, created.version, &past).await?;
+    let scheduled = repo.add_salary(ACTOR, corrected.version, &future).await?;
+    assert_eq!(scheduled.salary, created.salary);
+    assert_eq!(scheduled.version, created.version + 2);
+
+    // An update that changes the salary replaces today's rate.
+    let raised = repo
+        .update(
+            ACTOR,
+            &dto::Employee {
+                salary: "110_000.00".parse::<BigDecimal>().unwrap(),
+                ..scheduled.clone()
+            },
+        )
+        .await?;
+    assert_eq!(raised.salary, "110_000.00".parse::<BigDecimal>().unwrap());
+    assert_eq!(
+        repo.get_salaries(Some(created.id)).await?,
+        vec![past, rate(today, "110_000.00"), future.clone()]
+    );
+
+    assert!(matches!(
+        repo.add_salary(ACTOR, scheduled.version, &future).await,
+        Err(AddSalaryError::Stale { id }) if id == created.id
+    ));
+    assert!(matches!(
+        repo.add_salary(
+            ACTOR,
+            1,
+            &dto::Salary {
+                employee_id: Uuid::now_v7(),
+                ..future
+            }
+        )
+        .await,
+        Err(AddSalaryError::NotFound { id: _ })
+    ));
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../migrations")]
+#[ignore = "integration"]
+async fn test_create_all_employees(pool: PgPool) -> sqlx::Result<()> {
+    let repo = PostgresDb::new(pool);
+    let employee = |email: &str| dto::Employee {
+        id: Uuid::nil(),
+        first_name: "run".to_string(),
+        last_name: "away".to_string(),
+        email: email.to_string(),
+        salary: "32_000.00".parse::<BigDecimal>().unwrap(),
+        version: 0,
+        terminated_on: None,
+    };
+    repo.create(ACTOR, &employee("taken@away.com")).await?;
+
+    let clashing = [
+        employee("taken@away.com"),
+        employee("run@away.com"),
+        employee("run@away.com"),
+    ];
+    assert!(matches!(
+        repo.create_all(ACTOR, &clashing, false).await,
+        Err(CreateEmployeesError::Duplicate { rows }) if rows == vec![0, 2]
+    ));
+    assert_eq!(repo.get_all().await?.len(), 1);
+
+    let batch = [employee("run@away.com"), employee("this@away.com")];
+    let rehearsed = repo.create_all(ACTOR, &batch, true).await?;
+    assert_eq!(rehearsed.len(), 2);
+    assert_eq!(repo.get_all().await?.len(), 1);
 
+    let created = repo.create_all(ACTOR, &batch, false).await?;
+    assert_eq!(created.len(), 2);
+    assert_eq!(repo.get(created[1].id).await?, Some(created[1].clone()));
+    assert_eq!(repo.get_salaries(Some(created[0].id)).await?.len(), 1);
     Ok(())
 }
@@ -0,0 +1,308 @@
+use bigdecimal::BigDecimal;
+use domain::dto;
+use repository::assignment_errors::UpdateAssignmentError;
+use repository::assignment_repo::AssignmentRepo;
+use repository::employee_assignment_errors::{
+    CreateEmployeeAssignmentError, SavePlanError, UpdateEmployeeAssignmentError,
+};
+use repository::employee_assignment_repo::EmployeeAssignmentRepo;
+use repository::employee_repo::EmployeeRepo;
+use repository::in_memory_db::InMemoryDb;
+use repository::initiative_repo::InitiativeRepo;
+use uuid::Uuid;
+
+/// Recorded as the author of every write these tests make.
+const ACTOR: Uuid = Uuid::nil();
+
+fn dec(value: &str) -> BigDecimal {
+    value.parse::<BigDecimal>().unwrap()
+}
+
+/// An employee, two initiatives and a 2025 curve planned fully in January.
+async fn fixture(repo: &InMemoryDb) -> (Uuid, Uuid, Uuid, dto::Assignment) {
+    let employee = EmployeeRepo::create(
+        repo,
+        ACTOR,
+        &dto::Employee {
+            id: Uuid::nil(),
+            first_name: "run".to_string(),
+            last_name: "away".to_string(),
+            email: "run@away.com".to_string(),
+            salary: dec("32000.00"),
+            version: 0,
+            terminated_on: None,
+        },
+    )
+    .await
+    .unwrap();
+    let initiative = |name: &str| dto::Initiative {
+        id: Uuid::nil(),
+        initiative: name.to_string(),
+        version: 0,
+    };
+    let platform = InitiativeRepo::create(repo, ACTOR, &initiative("platform"))
+        .await
+        .unwrap();
+    let mobile = InitiativeRepo::create(repo, ACTOR, &initiative("mobile"))
+        .await
+        .unwrap();
+    let mut months: [Option<BigDecimal>; 12] = Default::default();
+    months[0] = Some(dec("1.00"));
+    let curve = AssignmentRepo::create(repo, ACTOR, &dto::Assignment::from_months(2025, months))
+        .await
+        .unwrap();
+    (employee.id, platform.id, mobile.id, curve)
+}
+
+fn link(
+    employee_id: Uuid,
+    initiative_id: Uuid,
+    curve: &dto::Assignment,
+    allocation: &str,
+) -> dto::EmployeeAssignment {
+    dto::EmployeeAssignment {
+        id: Uuid::nil(),
+        employee_id,
+        assignment_id: curve.id,
+        allocation: dec(allocation),
+        initiative_id,
+        version: 0,
+    }
+}
+
+#[tokio::test]
+async fn test_links_keep_table_constraints_in_memory() {
+    let repo = InMemoryDb::new();
+    let (employee_id, platform, mobile, curve) = fixture(&repo).await;
+
+    let created =
+        EmployeeAssignmentRepo::create(&repo, ACTOR, &link(employee_id, platform, &curve, "0.50"))
+            .await
+            .unwrap();
+    assert_eq!(created.version, 1);
+    assert!(matches!(
+        EmployeeAssignmentRepo::create(&repo, ACTOR, &link(employee_id, platform, &curve, "0.10"))
+            .await,
+        Err(CreateEmployeeAssignmentError::Duplicate { .. })
+    ));
+    assert!(matches!(
+        EmployeeAssignmentRepo::create(&repo, ACTOR, &link(Uuid::now_v7(), mobile, &curve, "0.10"))
+            .await,
+        Err(CreateEmployeeAssignmentError::MissingReference { .. })
+    ));
+    assert!(matches!(
+        EmployeeAssignmentRepo::create(&repo, ACTOR, &link(employee_id, mobile, &curve, "1.10"))
+            .await,
+        Err(CreateEmployeeAssignmentError::InvalidAllocation { .. })
+    ));
+    assert!(matches!(
+        EmployeeAssignmentRepo::create(&repo, ACTOR, &link(employee_id, mobile, &curve, "0.60")).await,
+        Err(CreateEmployeeAssignmentError::Overallocated { months, .. }) if months == [0]
+    ));
+
+    let raised = dto::EmployeeAssignment {
+        allocation: dec("1.00"),
+        ..created.clone()
+    };
+    let updated = EmployeeAssignmentRepo::update(&repo, ACTOR, &raised)
+        .await
+        .unwrap();
+    assert_eq!(updated.version, 2);
+    assert!(matches!(
+        EmployeeAssignmentRepo::update(&repo, ACTOR, &raised).await,
+        Err(UpdateEmployeeAssignmentError::Stale { .. })
+    ));
+    assert_eq!(
+        repo.get_for_employee(employee_id).await.unwrap(),
+        vec![updated.clone()]
+    );
+
+    let scheduled = repo.get_scheduled(2025, Some(employee_id)).await.unwrap();
+    assert_eq!(scheduled.len(), 1);
+    assert_eq!(scheduled[0].assignment, curve);
+    assert_eq!(
+        repo.get_scheduled_for(2025, &[employee_id]).await.unwrap(),
+        scheduled
+    );
+    assert!(
+        repo.get_scheduled_for(2025, &[Uuid::now_v7()])
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    assert!(repo.get_scheduled(2026, None).await.unwrap().is_empty());
+
+    EmployeeAssignmentRepo::delete(&repo, ACTOR, employee_id, updated.id, updated.version)
+        .await
+        .unwrap();
+    assert_eq!(repo.get_for_initiative(platform).await.unwrap(), vec![]);
+}
+
+#[tokio::test]
+async fn test_curve_updates_check_capacity_in_memory() {
+    let repo = InMemoryDb::new();
+    let (employee_id, platform, mobile, curve) = fixture(&repo).await;
+    let mut months: [Option<BigDecimal>; 12] = Default::default();
+    months[0] = Some(dec("0.50"));
+    let half = AssignmentRepo::create(&repo, ACTOR, &dto::Assignment::from_months(2025, months))
+        .await
+        .unwrap();
+    EmployeeAssignmentRepo::create(&repo, ACTOR, &link(employee_id, platform, &curve, "0.50"))
+        .await
+        .unwrap();
+    EmployeeAssignmentRepo::create(&repo, ACTOR, &link(employee_id, mobile, &half, "1.00"))
+        .await
+        .unwrap();
+
+    let mut months: [Option<BigDecimal>; 12] = Default::default();
+    months[0] = Some(dec("0.60"));
+    let raised = dto::Assignment {
+        id: half.id,
+        version: half.version,
+        ..dto::Assignment::from_months(2025, months)
+    };
+    assert!(matches!(
+        AssignmentRepo::update(&repo, ACTOR, &raised).await,
+        Err(UpdateAssignmentError::Overallocated { employee_id: id, .. }) if id == employee_id
+    ));
+    assert_eq!(
+        AssignmentRepo::get(&repo, half.id).await.unwrap(),
+        Some(half)
+    );
+}
+
+#[tokio::test]
+async fn test_save_plan_is_all_or_nothing_in_memory() {
+    let repo = InMemoryDb::new();
+    let (employee_id, platform, mobile, curve) = fixture(&repo).await;
+    let existing =
+        EmployeeAssignmentRepo::create(&repo, ACTOR, &link(employee_id, platform, &curve, "0.50"))
+            .await
+            .unwrap();
+
+    let new_curve = dto::Assignment::from_months(2025, Default::default());
+    let stale = dto::PlannedLink {
+        link: Some(dto::EmployeeAssignment {
+            version: existing.version + 1,
+            ..existing.clone()
+        }),
+        employee_id,
+        initiative_id: platform,
+        allocation: dec("0.20"),
+        assignment: curve.clone(),
+    };
+    let added = dto::PlannedLink {
+        link: None,
+        employee_id,
+        initiative_id: mobile,
+        allocation: dec("0.30"),
+        assignment: new_curve,
+    };
+    assert!(matches!(
+        repo.save_plan(ACTOR, &[added.clone(), stale.clone()]).await,
+        Err(SavePlanError::StaleLink { .. })
+    ));
+    assert_eq!(
+        repo.get_for_employee(employee_id).await.unwrap(),
+        vec![existing.clone()]
+    );
+    assert_eq!(
+        AssignmentRepo::get_all(&repo, None).await.unwrap(),
+        vec![curve.clone()]
+    );
+
+    let current = dto::PlannedLink {
+        link: Some(existing.clone()),
+        ..stale
+    };
+    repo.save_plan(ACTOR, &[added, current]).await.unwrap();
+    let links = repo.get_for_employee(employee_id).await.unwrap();
+    assert_eq!(links.len(), 2);
+    assert_eq!(links[0].allocation, dec("0.20"));
+    assert_eq!(links[0].version, existing.version + 1);
+    assert_eq!(links[1].initiative_id, mobile);
+}
+
+#[tokio::test]
+async fn test_save_plan_checks_links_written_since_the_plan_was_read_in_memory() {
+    let repo = InMemoryDb::new();
+    let (employee_id, platform, mobile, curve) = fixture(&repo).await;
+    let existing =
+        EmployeeAssignmentRepo::create(&repo, ACTOR, &link(employee_id, platform, &curve, "0.50"))
+            .await
+            .unwrap();
+    // Planned while `existing` was the only link following the curve.
+    let rewrite = dto::PlannedLink {
+        link: Some(existing.clone()),
+        employee_id,
+        initiative_id: platform,
+        allocation: dec("1.00"),
+        assignment: curve.clone(),
+    };
+    let mut months: [Option<BigDecimal>; 12] = Default::default();
+    months[0] = Some(dec("0.80"));
+    let added = dto::PlannedLink {
+        link: None,
+        employee_id,
+        initiative_id: mobile,
+        allocation: dec("1.00"),
+        assignment: dto::Assignment::from_months(2025, months),
+    };
+
+    // The existing link already takes half of January, so the new one does not fit.
+    assert!(matches!(
+        repo.save_plan(ACTOR, std::slice::from_ref(&added)).await,
+        Err(SavePlanError::Overallocated { year: 2025, ref months, .. }) if *months == vec![0]
+    ));
+    assert_eq!(
+        repo.get_for_employee(employee_id).await.unwrap(),
+        vec![existing.clone()]
+    );
+
+    // A link that started following the curve keeps it from being rewritten under it.
+    let follower =
+        EmployeeAssignmentRepo::create(&repo, ACTOR, &link(employee_id, mobile, &curve, "0.50"))
+            .await
+            .unwrap();
+    assert!(matches!(
+        repo.save_plan(ACTOR, &[rewrite]).await,
+        Err(SavePlanError::StaleAssignment { id }) if id == curve.id
+    ));
+    assert_eq!(
+        repo.get_for_employee(employee_id).await.unwrap(),
+        vec![existing, follower]
+    );
+    assert_eq!(
+        AssignmentRepo::get_all(&repo, None).await.unwrap(),
+        vec![curve]
+    );
+}
+
+#[tokio::test]
+async fn test_get_planned_elsewhere_in_memory() {
+    let repo = InMemoryDb::new();
+    let (employee_id, platform, mobile, curve) = fixture(&repo).await;
+    let later = AssignmentRepo::create(
+        &repo,
+        ACTOR,
+        &dto::Assignment::from_months(2026, Default::default()),
+    )
+    .await
+    .unwrap();
+    EmployeeAssignmentRepo::create(&repo, ACTOR, &link(employee_id, platform, &curve, "0.50"))
+        .await
+        .unwrap();
+    EmployeeAssignmentRepo::create(&repo, ACTOR, &link(employee_id, mobile, &later, "0.50"))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        repo.get_planned_elsewhere(2025).await.unwrap(),
+        vec![(employee_id, mobile, 2026)]
+    );
+    assert_eq!(
+        repo.get_planned_elsewhere(2026).await.unwrap(),
+        vec![(employee_id, platform, 2025)]
+    );
+    assert_eq!(repo.get_planned_elsewhere(2024).await.unwrap().len(), 2);
+}
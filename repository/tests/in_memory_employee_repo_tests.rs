@@ -0,0 +1,209 @@
+use bigdecimal::BigDecimal;
+use chrono::NaiveDate;
+use domain::dto;
+use repository::employee_errors::{
+    AddSalaryError, CreateEmployeeError, GetEmployeesError, RestoreEmployeeError,
+    TerminateEmployeeError, UpdateEmployeeError,
+};
+use repository::employee_repo::EmployeeRepo;
+use repository::in_memory_db::InMemoryDb;
+use uuid::Uuid;
+
+/// Recorded as the author of every write these tests make.
+const ACTOR: Uuid = Uuid::nil();
+
+fn employee(email: &str) -> dto::Employee {
+    dto::Employee {
+        id: Uuid::nil(),
+        first_name: "run".to_string(),
+        last_name: "away".to_string(),
+        email: email.to_string(),
+        salary: "32_000.00".parse::<BigDecimal>().unwrap(),
+        version: 0,
+        terminated_on: None,
+    }
+}
+
+#[tokio::test]
+async fn test_crud_employee_in_memory() {
+    let repo = InMemoryDb::new();
+    let ret_ri = repo.create(ACTOR, &employee("run@away.com")).await.unwrap();
+    assert_ne!(ret_ri.id, Uuid::nil());
+    assert_eq!(ret_ri.email, "run@away.com");
+
+    let updated = dto::Employee {
+        email: "bar@another.com".to_string(),
+        ..ret_ri.clone()
+    };
+    let ret2_ri = repo.update(ACTOR, &updated).await.unwrap();
+    assert_eq!(
+        ret2_ri,
+        dto::Employee {
+            version: ret_ri.version + 1,
+            ..updated
+        }
+    );
+    assert_eq!(repo.get(ret_ri.id).await.unwrap(), Some(ret2_ri.clone()));
+
+    let last_day = NaiveDate::from_ymd_opt(2025, 6, 30).unwrap();
+    let left = repo
+        .terminate(ACTOR, ret_ri.id, ret2_ri.version, last_day)
+        .await
+        .unwrap();
+    assert_eq!(left.terminated_on, Some(last_day));
+    assert_eq!(repo.get(ret_ri.id).await.unwrap(), Some(left.clone()));
+
+    let back = repo.restore(ACTOR, left.id, left.version).await.unwrap();
+    assert_eq!(back.terminated_on, None);
+    assert!(matches!(
+        repo.restore(ACTOR, back.id, back.version).await,
+        Err(RestoreEmployeeError::NotTerminated { .. })
+    ));
+}
+
+#[tokio::test]
+async fn test_get_employees_in_memory() {
+    let repo = InMemoryDb::new();
+    let ret_ri = repo.create(ACTOR, &employee("run@away.com")).await.unwrap();
+    let ret_ri2 = repo
+        .create(ACTOR, &employee("this@away.com"))
+        .await
+        .unwrap();
+
+    assert_eq!(repo.get_all().await.unwrap(), vec![ret_ri, ret_ri2]);
+}
+
+#[tokio::test]
+async fn test_unique_email_in_memory() {
+    let repo = InMemoryDb::new();
+    repo.create(ACTOR, &employee("run@away.com")).await.unwrap();
+    let other = repo
+        .create(ACTOR, &employee("this@away.com"))
+        .await
+        .unwrap();
+
+    let duplicate = repo.create(ACTOR, &employee("run@away.com")).await;
+    assert!(matches!(
+        duplicate,
+        Err(CreateEmployeeError::Duplicate { .. })
+    ));
+
+    let taken = dto::Employee {
+        email: "run@away.com".to_string(),
+        ..other
+    };
+    assert!(matches!(
+        repo.update(ACTOR, &taken).await,
+        Err(UpdateEmployeeError::Duplicate { .. })
+    ));
+}
+
+#[tokio::test]
+async fn test_stale_employee_in_memory() {
+    let repo = InMemoryDb::new();
+    let created = repo.create(ACTOR, &employee("run@away.com")).await.unwrap();
+    repo.update(ACTOR, &created).await.unwrap();
+
+    assert!(matches!(
+        repo.update(ACTOR, &created).await,
+        Err(UpdateEmployeeError::Stale { .. })
+    ));
+    let last_day = NaiveDate::from_ymd_opt(2025, 6, 30).unwrap();
+    assert!(matches!(
+        repo.terminate(ACTOR, created.id, created.version, last_day)
+            .await,
+        Err(TerminateEmployeeError::Stale { .. })
+    ));
+    assert!(matches!(
+        repo.terminate(ACTOR, Uuid::now_v7(), 1, last_day).await,
+        Err(TerminateEmployeeError::NotFound { .. })
+    ));
+}
+
+#[tokio::test]
+async fn test_get_employee_pages_in_memory() {
+    let repo = InMemoryDb::new();
+    let mut created = Vec::new();
+    for email in ["cal@away.com", "ann@away.com", "bob@away.com"] {
+        created.push(repo.create(ACTOR, &employee(email)).await.unwrap());
+    }
+
+    let query = dto::EmployeeQuery {
+        sort: dto::EmployeeSort::Email,
+        limit: 2,
+        ..Default::default()
+    };
+    let first = repo.get_page(&query).await.unwrap();
+    assert_eq!(first.items, vec![created[1].clone(), created[2].clone()]);
+    assert_eq!(first.next, Some(created[2].id));
+    let second = repo
+        .get_page(&dto::EmployeeQuery {
+            after: first.next,
+            ..query.clone()
+        })
+        .await
+        .unwrap();
+    assert_eq!(second.items, vec![created[0].clone()]);
+    assert_eq!(second.next, None);
+
+    let filtered = repo
+        .get_page(&dto::EmployeeQuery {
+            name_prefix: Some("nobody".to_string()),
+            limit: 10,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert!(filtered.items.is_empty());
+
+    let last_day = NaiveDate::from_ymd_opt(2025, 6, 30).unwrap();
+    repo.terminate(ACTOR, created[1].id, created[1].version, last_day)
+        .await
+        .unwrap();
+    let current = repo
+        .get_page(&dto::EmployeeQuery {
+            limit: 10,
+            ..query.clone()
+        })
+        .await
+        .unwrap();
+    assert_eq!(current.items, vec![created[2].clone(), created[0].clone()]);
+
+    let missing = repo
+        .get_page(&dto::EmployeeQuery {
+            after: Some(Uuid::now_v7()),
+            ..query
+        })
+        .await;
+    assert!(matches!(
+        missing,
+        Err(GetEmployeesError::InvalidCursor { id: _ })
+    ));
+}
+
+#[tokio::test]
+async fn test_salary_history_in_memory() {
+    let repo = InMemoryDb::new();
+    let created = repo.create(ACTOR, &employee("run@away.com")).await.unwrap();
+    let past = dto::Salary {
+        employee_id: created.id,
+        effective_from: NaiveDate::from_ymd_opt(2020, 1, 1).unwrap(),
+        salary: "10_000.00".parse::<BigDecimal>().unwrap(),
+    };
+    let corrected = repo
+        .add_salary(ACTOR, created.version, &past)
+        .await
+        .unwrap();
+    assert_eq!(corrected.salary, created.salary);
+    assert_eq!(corrected.version, created.version + 1);
+
+    let history = repo.get_salaries(Some(created.id)).await.unwrap();
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[0], past);
+    assert_eq!(history[1].salary, created.salary);
+
+    assert!(matches!(
+        repo.add_salary(ACTOR, created.version, &past).await,
+        Err(AddSalaryError::Stale { .. })
+    ));
+}
@@ -0,0 +1,79 @@
+use domain::dto;
+use repository::initiative_repo::InitiativeRepo;
+use repository::postgres_db::PostgresDb;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Recorded as the author of every write these tests make.
+const ACTOR: Uuid = Uuid::nil();
+
+#[sqlx::test(migrations = "../migrations")]
+#[ignore = "integration"]
+async fn test_get_initiatives(pool: PgPool) -> sqlx::Result<()> {
+    let repo = PostgresDb::new(pool);
+    let ri = dto::Initiative {
+        id: Uuid::nil(),
+        initiative: "Platform".to_string(),
+        version: 0,
+    };
+    let ret_ri = repo.create(ACTOR, &ri).await?;
+    let ri2 = dto::Initiative {
+        id: Uuid::nil(),
+        initiative: "Mobile".to_string(),
+        version: 0,
+    };
+    let ret_ri2 = repo.create(ACTOR, &ri2).await?;
+
+    let initiatives: Vec<dto::Initiative> = repo.get_all().await?;
+    assert_eq!(initiatives.len(), 2);
+    assert!(initiatives.iter().any(|x| x.id == ret_ri.id));
+    assert!(initiatives.iter().any(|x| x.id == ret_ri2.id));
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../migrations")]
+#[ignore = "integration"]
+async fn test_crud_initiative(pool: PgPool) -> sqlx::Result<()> {
+    let repo = PostgresDb::new(pool);
+    let ri = dto::Initiative {
+        id: Uuid::nil(),
+        initiative: "Platform".to_string(),
+        version: 0,
+    };
+    let ret_ri = repo.create(ACTOR, &ri).await?;
+    assert_ne!(ret_ri.id, Uuid::nil());
+    assert_eq!(ret_ri.initiative, "Platform");
+
+    let updated = dto::Initiative {
+        id: ret_ri.id,
+        initiative: "Platform v2".to_string(),
+        version: ret_ri.version,
+    };
+    let ret2_ri = repo.update(ACTOR, &updated).await?;
+    assert_eq!(ret2_ri.initiative, "Platform v2");
+    assert_eq!(ret2_ri.id, ret_ri.id);
+
+    let ret3_ri: dto::Initiative = repo.get(ret_ri.id).await?.unwrap();
+    assert_eq!(ret3_ri.initiative, "Platform v2");
+
+    repo.delete(ACTOR, ret_ri.id, ret2_ri.version).await?;
+    assert!(repo.get(ret_ri.id).await?.is_none());
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../migrations")]
+#[ignore = "integration"]
+async fn test_update_missing_initiative(pool: PgPool) -> sqlx::Result<()> {
+    let repo = PostgresDb::new(pool);
+    let missing = dto::Initiative {
+        id: Uuid::now_v7(),
+        initiative: "Nope".to_string(),
+        version: 0,
+    };
+    let ret = repo.update(ACTOR, &missing).await;
+    assert!(matches!(
+        ret,
+        Err(repository::initiative_errors::UpdateInitiativeError::NotFound { .. })
+    ));
+    Ok(())
+}
@@ -0,0 +1,26 @@
+use domain::dto::Scope;
+use repository::postgres_db::PostgresDb;
+use repository::role_repo::RoleRepo;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+#[sqlx::test(migrations = "../migrations")]
+#[ignore = "integration"]
+async fn test_get_role(pool: PgPool) -> sqlx::Result<()> {
+    let id: Uuid = sqlx::query_scalar(
+        r#"INSERT INTO roles(name, scopes)
+           VALUES ('finance', '["employees:read", "salary:write", "rockets:launch"]')
+           RETURNING id"#,
+    )
+    .fetch_one(&pool)
+    .await?;
+    let repo = PostgresDb::new(pool);
+
+    let role = repo.get(id).await?.unwrap();
+    assert_eq!(role.name, "finance");
+    // Unknown scopes are dropped rather than failing the lookup.
+    assert_eq!(role.scopes, vec![Scope::EmployeesRead, Scope::SalaryWrite]);
+
+    assert!(repo.get(Uuid::now_v7()).await?.is_none());
+    Ok(())
+}
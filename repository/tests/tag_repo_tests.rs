@@ -0,0 +1,127 @@
+use domain::dto;
+use repository::postgres_db::PostgresDb;
+use repository::tag_errors::MergeTagsError;
+use repository::tag_repo::TagRepo;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Recorded as the author of every write these tests make.
+const ACTOR: Uuid = Uuid::nil();
+
+fn new_tag(tag: &str) -> dto::Tag {
+    dto::Tag {
+        id: Uuid::nil(),
+        tag: tag.to_string(),
+        version: 0,
+    }
+}
+
+async fn insert_assignment(pool: &PgPool) -> Uuid {
+    sqlx::query_scalar("INSERT INTO assignments(year) VALUES (2025) RETURNING id")
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+async fn tag_assignment(pool: &PgPool, assignment_id: Uuid, tag_id: Uuid) {
+    sqlx::query("INSERT INTO assignment_tags(assignment_id, tag_id) VALUES ($1, $2)")
+        .bind(assignment_id)
+        .bind(tag_id)
+        .execute(pool)
+        .await
+        .unwrap();
+}
+
+#[sqlx::test(migrations = "../migrations")]
+#[ignore = "integration"]
+async fn test_crud_tag(pool: PgPool) -> sqlx::Result<()> {
+    let repo = PostgresDb::new(pool);
+    let ret_ri = repo.create(ACTOR, &new_tag("backend")).await?;
+    assert_ne!(ret_ri.id, Uuid::nil());
+    assert_eq!(ret_ri.tag, "backend");
+
+    let renamed = repo
+        .rename(
+            ACTOR,
+            &dto::Tag {
+                id: ret_ri.id,
+                tag: "back-end".to_string(),
+                version: ret_ri.version,
+            },
+        )
+        .await?;
+    assert_eq!(renamed.tag, "back-end");
+
+    let usage = repo.get(ret_ri.id).await?.unwrap();
+    assert_eq!(usage.tag, renamed);
+    assert_eq!(usage.assignment_count, 0);
+    assert_eq!(usage.employee_assignment_count, 0);
+
+    repo.delete(ACTOR, ret_ri.id, renamed.version).await?;
+    assert!(repo.get(ret_ri.id).await?.is_none());
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../migrations")]
+#[ignore = "integration"]
+async fn test_tag_usage_counts(pool: PgPool) -> sqlx::Result<()> {
+    let repo = PostgresDb::new(pool.clone());
+    let used = repo.create(ACTOR, &new_tag("used")).await?;
+    let unused = repo.create(ACTOR, &new_tag("unused")).await?;
+    let a1 = insert_assignment(&pool).await;
+    let a2 = insert_assignment(&pool).await;
+    tag_assignment(&pool, a1, used.id).await;
+    tag_assignment(&pool, a2, used.id).await;
+
+    let tags = repo.get_all().await?;
+    assert_eq!(tags.len(), 2);
+    let used_usage = tags.iter().find(|x| x.tag.id == used.id).unwrap();
+    assert_eq!(used_usage.assignment_count, 2);
+    let unused_usage = tags.iter().find(|x| x.tag.id == unused.id).unwrap();
+    assert_eq!(unused_usage.assignment_count, 0);
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../migrations")]
+#[ignore = "integration"]
+async fn test_merge_tags(pool: PgPool) -> sqlx::Result<()> {
+    let repo = PostgresDb::new(pool.clone());
+    let source = repo.create(ACTOR, &new_tag("front-end")).await?;
+    let target = repo.create(ACTOR, &new_tag("frontend")).await?;
+    let a1 = insert_assignment(&pool).await;
+    let a2 = insert_assignment(&pool).await;
+    // a1 carries both tags, so merging must not violate the unique constraint.
+    tag_assignment(&pool, a1, source.id).await;
+    tag_assignment(&pool, a1, target.id).await;
+    tag_assignment(&pool, a2, source.id).await;
+
+    let stale = repo
+        .merge(ACTOR, source.id, source.version + 1, target.id)
+        .await;
+    assert!(matches!(stale, Err(MergeTagsError::Stale { .. })));
+    assert_eq!(repo.get(source.id).await?.unwrap().assignment_count, 2);
+
+    let merged = repo
+        .merge(ACTOR, source.id, source.version, target.id)
+        .await?;
+    // The target's usage changed, so its version moves on.
+    assert_eq!(
+        merged.tag,
+        dto::Tag {
+            version: target.version + 1,
+            ..target.clone()
+        }
+    );
+    assert_eq!(merged.assignment_count, 2);
+    assert!(repo.get(source.id).await?.is_none());
+
+    let same = repo
+        .merge(ACTOR, target.id, merged.tag.version, target.id)
+        .await;
+    assert!(matches!(same, Err(MergeTagsError::SameTag { .. })));
+    let missing = repo
+        .merge(ACTOR, source.id, source.version, target.id)
+        .await;
+    assert!(matches!(missing, Err(MergeTagsError::NotFound { .. })));
+    Ok(())
+}
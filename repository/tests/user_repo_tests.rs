@@ -0,0 +1,100 @@
+use domain::dto;
+use repository::postgres_db::PostgresDb;
+use repository::role_repo::RoleRepo;
+use repository::user_errors::{CreateUserError, UpdateUserError};
+use repository::user_repo::UserRepo;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Recorded as the author of every write these tests make.
+const ACTOR: Uuid = Uuid::nil();
+
+fn user(email: &str, external_id: &str) -> dto::User {
+    dto::User {
+        id: Uuid::nil(),
+        first_name: "run".to_string(),
+        last_name: "away".to_string(),
+        email: email.to_string(),
+        external_id: external_id.to_string(),
+        external_id_source: "https://idp.example.com".to_string(),
+        role_id: None,
+        active: true,
+    }
+}
+
+#[sqlx::test(migrations = "../migrations")]
+#[ignore = "integration"]
+async fn test_get_user_by_external_id(pool: PgPool) -> sqlx::Result<()> {
+    let repo = PostgresDb::new(pool);
+    let created = UserRepo::create(&repo, ACTOR, &user("run@away.com", "sub-1")).await?;
+    assert_ne!(created.id, Uuid::nil());
+
+    let found = repo
+        .get_by_external_id("sub-1", "https://idp.example.com")
+        .await?;
+    assert_eq!(found, Some(created));
+
+    // The same subject from another issuer is a different identity.
+    assert!(
+        repo.get_by_external_id("sub-1", "https://other.example.com")
+            .await?
+            .is_none()
+    );
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../migrations")]
+#[ignore = "integration"]
+async fn test_create_duplicate_user(pool: PgPool) -> sqlx::Result<()> {
+    let repo = PostgresDb::new(pool);
+    UserRepo::create(&repo, ACTOR, &user("run@away.com", "sub-1")).await?;
+    let ret = UserRepo::create(&repo, ACTOR, &user("run@away.com", "sub-2")).await;
+    assert!(matches!(ret, Err(CreateUserError::Duplicate { .. })));
+
+    let ret = UserRepo::create(
+        &repo,
+        ACTOR,
+        &dto::User {
+            role_id: Some(Uuid::now_v7()),
+            ..user("other@away.com", "sub-3")
+        },
+    )
+    .await;
+    assert!(matches!(ret, Err(CreateUserError::MissingRole { .. })));
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../migrations")]
+#[ignore = "integration"]
+async fn test_set_role_and_deactivate_user(pool: PgPool) -> sqlx::Result<()> {
+    let repo = PostgresDb::new(pool);
+    let role = RoleRepo::create(
+        &repo,
+        ACTOR,
+        &dto::Role {
+            id: Uuid::nil(),
+            name: "finance".to_string(),
+            scopes: vec![dto::Scope::EmployeesRead, dto::Scope::SalaryWrite],
+        },
+    )
+    .await?;
+    assert_eq!(RoleRepo::get_all(&repo).await?, vec![role.clone()]);
+
+    let created = UserRepo::create(&repo, ACTOR, &user("run@away.com", "sub-1")).await?;
+    let updated = repo.set_role(ACTOR, created.id, Some(role.id)).await?;
+    assert_eq!(updated.role_id, Some(role.id));
+    assert_eq!(repo.set_role(ACTOR, created.id, None).await?.role_id, None);
+    assert!(matches!(
+        repo.set_role(ACTOR, created.id, Some(Uuid::now_v7())).await,
+        Err(UpdateUserError::MissingRole { .. })
+    ));
+
+    let deactivated = repo.deactivate(ACTOR, created.id).await?;
+    assert!(!deactivated.active);
+    assert_eq!(UserRepo::get(&repo, created.id).await?, Some(deactivated));
+    assert!(matches!(
+        repo.deactivate(ACTOR, Uuid::now_v7()).await,
+        Err(UpdateUserError::NotFound { .. })
+    ));
+    Ok(())
+}
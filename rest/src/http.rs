@@ -4,31 +4,90 @@
 */
 
 mod api;
+mod assignment_handlers;
+mod audit_handlers;
+mod auth;
+mod employee_assignment_handlers;
 mod employee_handlers;
+mod export_handlers;
+mod initiative_handlers;
+mod openapi;
+mod plan_handlers;
+mod report_handlers;
+mod role_handlers;
+mod tag_handlers;
+mod user_handlers;
 use std::sync::Arc;
 
+pub use crate::http::auth::{AuthConfig, TokenKeySource};
+pub use crate::http::openapi::openapi_json;
+
+use crate::http::api::ApiError;
+use crate::http::assignment_handlers::{
+    create_assignment, delete_assignment, get_assignment, get_assignments, update_assignment,
+};
+use crate::http::audit_handlers::get_audit_entries;
+use crate::http::auth::{TokenVerifier, authenticate, authorize};
+use crate::http::employee_assignment_handlers::{
+    create_employee_assignment, delete_employee_assignment, get_employee_assignments,
+    get_initiative_staff, update_employee_assignment,
+};
 use crate::http::employee_handlers::{
-    create_employee, delete_employee, get_employee, get_employees, update_employee,
+    create_employee, create_salary, delete_employee, get_employee, get_employees, get_salaries,
+    import_employees, patch_employee, restore_employee, update_employee,
+};
+use crate::http::export_handlers::{export_allocations, export_employees};
+use crate::http::initiative_handlers::{
+    create_initiative, delete_initiative, get_initiative, get_initiatives, update_initiative,
+};
+use crate::http::plan_handlers::import_allocations;
+use crate::http::report_handlers::{get_initiative_costs, get_overallocated, get_utilization};
+use crate::http::role_handlers::{create_role, get_roles};
+use crate::http::tag_handlers::{create_tag, delete_tag, get_tag, get_tags, merge_tag, rename_tag};
+use crate::http::user_handlers::{
+    create_user, deactivate_user, get_current_user, get_user, get_users, set_user_role,
 };
 use anyhow::Context;
 use axum::Router;
-use axum::routing::{delete, get, post, put};
+use axum::middleware;
+use axum::routing::{delete, get, patch, post, put};
+use domain::dto::Scope;
+use repository::audit_repo::AuditRepo;
 use repository::employee_repo::EmployeeRepo;
+use repository::initiative_repo::InitiativeRepo;
 use repository::postgres_db::PostgresDb;
+use repository::role_repo::RoleRepo;
+use repository::tag_repo::TagRepo;
+use repository::user_repo::UserRepo;
 use serde_json::json;
+use service::allocation_service::{AllocationRepo, AllocationService};
 use sqlx::PgPool;
 use tokio::net;
 
 #[derive(Debug, Clone)]
 /// The global application state shared between all request handlers.
-pub struct AppState<Repo: EmployeeRepo> {
-    employee_repo: Arc<Repo>,
+pub struct AppState<Repo> {
+    allocation_service: AllocationService<Repo>,
+    token_verifier: Arc<TokenVerifier>,
+}
+
+impl<Repo> AppState<Repo> {
+    /// Builds the state handlers share over `repo`, loading the token keys named by `auth`.
+    pub fn new(repo: Repo, auth: &AuthConfig) -> anyhow::Result<Self> {
+        Ok(AppState {
+            allocation_service: AllocationService::new(Arc::new(repo)),
+            token_verifier: Arc::new(
+                TokenVerifier::load(auth).context("failed to load token keys")?,
+            ),
+        })
+    }
 }
 
 /// Configuration for the HTTP server.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct HttpServerConfig<'a> {
     pub port: &'a str,
+    pub auth: AuthConfig,
 }
 
 /// The application's HTTP server. The underlying HTTP package is opaque to module consumers.
@@ -45,6 +104,49 @@ async fn health_check() -> axum::Json<serde_json::Value> {
     }))
 }
 
+/// Unknown routes answer with the same problem body as missing rows.
+async fn route_not_found() -> ApiError {
+    ApiError::NotFound("No route matches the request".to_string())
+}
+
+/// Served without authentication so clients can fetch the contract before they have a token.
+async fn openapi_spec() -> impl axum::response::IntoResponse {
+    (
+        [(axum::http::header::CONTENT_TYPE, "application/json")],
+        openapi_json(),
+    )
+}
+
+/// Every route of the application over `state`. Everything under /api requires a bearer token;
+/// the health check and the API description stay open.
+pub fn router<R>(state: AppState<R>) -> Router
+where
+    R: AllocationRepo + InitiativeRepo + TagRepo + AuditRepo + UserRepo + RoleRepo,
+{
+    let api = axum::Router::new()
+        .route("/api/me", get(get_current_user))
+        .nest("/api/employees", employee_routes())
+        .nest("/api/initiatives", initiative_routes())
+        .nest("/api/tags", tag_routes())
+        .nest("/api/assignments", assignment_routes())
+        .nest("/api", employee_assignment_routes())
+        .nest("/api/allocations", allocation_routes())
+        .nest("/api/reports", report_routes())
+        .nest("/api/admin", admin_routes())
+        .nest("/api/audit", audit_routes())
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            authenticate::<R>,
+        ));
+
+    axum::Router::new()
+        .route("/health", get(health_check))
+        .route("/api/openapi.json", get(openapi_spec))
+        .merge(api)
+        .fallback(route_not_found)
+        .with_state(state)
+}
+
 impl HttpServer {
     /// Returns a new HTTP server bound to the port specified in `config`.
     pub async fn new(pool: PgPool, config: HttpServerConfig<'_>) -> anyhow::Result<Self> {
@@ -55,17 +157,8 @@ impl HttpServer {
             },
         );
 
-        let employee_repo = <PostgresDb as EmployeeRepo>::new(pool);
-        // Construct dependencies to inject into handlers.
-        let state = AppState {
-            employee_repo: Arc::new(employee_repo),
-        };
-
-        let router = axum::Router::new()
-            .route("/health", get(health_check))
-            .nest("/api/employees", employee_routes())
-            .layer(trace_layer)
-            .with_state(state);
+        let state = AppState::new(PostgresDb::new(pool), &config.auth)?;
+        let router = router(state).layer(trace_layer);
 
         let listener = net::TcpListener::bind(format!("0.0.0.0:{}", config.port))
             .await
@@ -85,10 +178,166 @@ impl HttpServer {
 }
 
 fn employee_routes<RR: EmployeeRepo>() -> Router<AppState<RR>> {
-    Router::new()
-        .route("/", post(create_employee::<RR>))
+    // Listing salaries additionally requires salary:read; without it exports leave them out.
+    let read = Router::new()
         .route("/{id}", get(get_employee::<RR>))
+        .route("/", get(get_employees::<RR>))
+        .route("/export", get(export_employees::<RR>))
+        .route("/{id}/salaries", get(get_salaries::<RR>))
+        .route_layer(middleware::from_fn_with_state(
+            Scope::EmployeesRead,
+            authorize,
+        ));
+    // Handlers additionally require salary:write before setting a salary.
+    let write = Router::new()
+        .route("/", post(create_employee::<RR>))
+        .route("/import", post(import_employees::<RR>))
         .route("/{id}", put(update_employee::<RR>))
+        .route("/{id}", patch(patch_employee::<RR>))
         .route("/{id}", delete(delete_employee::<RR>))
-        .route("/", get(get_employees::<RR>))
+        .route("/{id}/restore", post(restore_employee::<RR>))
+        .route("/{id}/salaries", post(create_salary::<RR>))
+        .route_layer(middleware::from_fn_with_state(
+            Scope::EmployeesWrite,
+            authorize,
+        ));
+    read.merge(write)
+}
+
+fn initiative_routes<IR: InitiativeRepo>() -> Router<AppState<IR>> {
+    let read = Router::new()
+        .route("/{id}", get(get_initiative::<IR>))
+        .route("/", get(get_initiatives::<IR>))
+        .route_layer(middleware::from_fn_with_state(
+            Scope::AllocationsRead,
+            authorize,
+        ));
+    let write = Router::new()
+        .route("/", post(create_initiative::<IR>))
+        .route("/{id}", put(update_initiative::<IR>))
+        .route("/{id}", delete(delete_initiative::<IR>))
+        .route_layer(middleware::from_fn_with_state(
+            Scope::AllocationsWrite,
+            authorize,
+        ));
+    read.merge(write)
+}
+
+fn tag_routes<TR: TagRepo>() -> Router<AppState<TR>> {
+    let read = Router::new()
+        .route("/{id}", get(get_tag::<TR>))
+        .route("/", get(get_tags::<TR>))
+        .route_layer(middleware::from_fn_with_state(
+            Scope::AllocationsRead,
+            authorize,
+        ));
+    let write = Router::new()
+        .route("/", post(create_tag::<TR>))
+        .route("/{id}", put(rename_tag::<TR>))
+        .route("/{id}", delete(delete_tag::<TR>))
+        .route("/{id}/merge", post(merge_tag::<TR>))
+        .route_layer(middleware::from_fn_with_state(
+            Scope::AllocationsWrite,
+            authorize,
+        ));
+    read.merge(write)
+}
+
+fn assignment_routes<R: AllocationRepo>() -> Router<AppState<R>> {
+    let read = Router::new()
+        .route("/{id}", get(get_assignment::<R>))
+        .route("/", get(get_assignments::<R>))
+        .route_layer(middleware::from_fn_with_state(
+            Scope::AllocationsRead,
+            authorize,
+        ));
+    let write = Router::new()
+        .route("/", post(create_assignment::<R>))
+        .route("/{id}", put(update_assignment::<R>))
+        .route("/{id}", delete(delete_assignment::<R>))
+        .route_layer(middleware::from_fn_with_state(
+            Scope::AllocationsWrite,
+            authorize,
+        ));
+    read.merge(write)
+}
+
+fn employee_assignment_routes<R: AllocationRepo>() -> Router<AppState<R>> {
+    let read = Router::new()
+        .route(
+            "/employees/{employee_id}/assignments",
+            get(get_employee_assignments::<R>),
+        )
+        .route(
+            "/initiatives/{initiative_id}/staff",
+            get(get_initiative_staff::<R>),
+        )
+        .route_layer(middleware::from_fn_with_state(
+            Scope::AllocationsRead,
+            authorize,
+        ));
+    let write = Router::new()
+        .route(
+            "/employees/{employee_id}/assignments",
+            post(create_employee_assignment::<R>),
+        )
+        .route(
+            "/employees/{employee_id}/assignments/{id}",
+            put(update_employee_assignment::<R>),
+        )
+        .route(
+            "/employees/{employee_id}/assignments/{id}",
+            delete(delete_employee_assignment::<R>),
+        )
+        .route_layer(middleware::from_fn_with_state(
+            Scope::AllocationsWrite,
+            authorize,
+        ));
+    read.merge(write)
+}
+
+fn allocation_routes<R: AllocationRepo + InitiativeRepo>() -> Router<AppState<R>> {
+    let read = Router::new()
+        .route("/export", get(export_allocations::<R>))
+        .route_layer(middleware::from_fn_with_state(
+            Scope::AllocationsRead,
+            authorize,
+        ));
+    let write = Router::new()
+        .route("/import", post(import_allocations::<R>))
+        .route_layer(middleware::from_fn_with_state(
+            Scope::AllocationsWrite,
+            authorize,
+        ));
+    read.merge(write)
+}
+
+fn report_routes<R: AllocationRepo + InitiativeRepo>() -> Router<AppState<R>> {
+    Router::new()
+        .route("/overallocated", get(get_overallocated::<R>))
+        .route("/initiative-costs", get(get_initiative_costs::<R>))
+        .route("/utilization", get(get_utilization::<R>))
+        .route_layer(middleware::from_fn_with_state(
+            Scope::ReportsRead,
+            authorize,
+        ))
+}
+
+fn audit_routes<R: AuditRepo>() -> Router<AppState<R>> {
+    // Handlers drop employee salaries for callers without salary:read.
+    Router::new()
+        .route("/", get(get_audit_entries::<R>))
+        .route_layer(middleware::from_fn_with_state(Scope::AuditRead, authorize))
+}
+
+fn admin_routes<R: UserRepo + RoleRepo>() -> Router<AppState<R>> {
+    Router::new()
+        .route("/roles", post(create_role::<R>))
+        .route("/roles", get(get_roles::<R>))
+        .route("/users", post(create_user::<R>))
+        .route("/users", get(get_users::<R>))
+        .route("/users/{id}", get(get_user::<R>))
+        .route("/users/{id}/role", put(set_user_role::<R>))
+        .route("/users/{id}/deactivate", post(deactivate_user::<R>))
+        .route_layer(middleware::from_fn_with_state(Scope::UsersAdmin, authorize))
 }
@@ -1,81 +1,209 @@
+use crate::http::auth::AuthError;
 use axum::Json;
-use axum::http::StatusCode;
+use axum::extract::rejection::{JsonRejection, QueryRejection};
+use axum::extract::{FromRequest, FromRequestParts, Query, Request};
+use axum::http::request::Parts;
+use axum::http::{HeaderValue, StatusCode, header};
 use axum::response::{IntoResponse, Response};
+use repository::assignment_errors::*;
+use repository::audit_errors::*;
+use repository::employee_assignment_errors::*;
 use repository::employee_errors::*;
+use repository::initiative_errors::*;
+use repository::role_errors::*;
+use repository::tag_errors::*;
+use repository::user_errors::*;
 use serde::Serialize;
+use serde::de::DeserializeOwned;
+use service::allocation_errors::*;
+use service::validation::{FieldError, RowError, describe};
+use utoipa::ToSchema;
 
 #[derive(Debug, Clone)]
-pub struct ApiSuccess<T: Serialize + PartialEq>(StatusCode, Json<ApiResponseBody<T>>);
+pub struct ApiSuccess<T: Serialize + PartialEq>(StatusCode, Json<ApiResponseBody<T>>, Option<i32>);
 
 impl<T> PartialEq for ApiSuccess<T>
 where
     T: Serialize + PartialEq,
 {
     fn eq(&self, other: &Self) -> bool {
-        self.0 == other.0 && self.1.0 == other.1.0
+        self.0 == other.0 && self.1.0 == other.1.0 && self.2 == other.2
     }
 }
 
 impl<T: Serialize + PartialEq> ApiSuccess<T> {
     pub fn new(status: StatusCode, data: T) -> Self {
-        ApiSuccess(status, Json(ApiResponseBody::new(status, data)))
+        ApiSuccess(status, Json(ApiResponseBody::new(status, data)), None)
+    }
+
+    /// Sends `version` as the `ETag` so the client can make its next write conditional on it.
+    pub fn with_version(mut self, version: i32) -> Self {
+        self.2 = Some(version);
+        self
     }
 }
 
 impl<T: Serialize + PartialEq> IntoResponse for ApiSuccess<T> {
     fn into_response(self) -> Response {
-        (self.0, self.1).into_response()
+        let mut response = (self.0, self.1).into_response();
+        if let Some(version) = self.2 {
+            let etag = HeaderValue::from_str(&etag(version)).expect("ETags are ASCII");
+            response.headers_mut().insert(header::ETAG, etag);
+        }
+        response
     }
 }
 
+/// The strong entity tag of a row at `version`.
+pub fn etag(version: i32) -> String {
+    format!("\"{}\"", version)
+}
+
+/// Stable, machine readable identifiers for each kind of failure, sent as the `code` member of
+/// every problem response. Clients should branch on these rather than on `detail`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// The body or query string could not be parsed.
+    MalformedRequest,
+    /// A path segment is not a valid UUID.
+    InvalidId,
+    /// The bearer token is missing, invalid or belongs to no active user.
+    Unauthenticated,
+    /// The caller's role lacks a scope the route requires.
+    MissingScope,
+    NotFound,
+    /// A unique value such as an email address is already taken.
+    Duplicate,
+    /// The request parsed but its values are not acceptable.
+    ValidationFailed,
+    /// The request refers to a row that does not exist.
+    InvalidReference,
+    /// The change would allocate an employee beyond their capacity.
+    Overallocated,
+    /// Only an employee who has left can be restored.
+    NotTerminated,
+    /// The employee left before the year the change is for.
+    Terminated,
+    /// The pagination cursor no longer refers to a row.
+    InvalidCursor,
+    /// Links changed while an imported plan was stored; nothing was stored, import it again.
+    PlanChanged,
+    /// The row changed since the version named in `If-Match`; fetch it again and retry.
+    PreconditionFailed,
+    /// The write must name the version it expects in `If-Match`.
+    PreconditionRequired,
+    Internal,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ApiError {
+    BadRequest(ErrorCode, String),
     InternalServerError(String),
-    UnprocessableEntity(String),
+    UnprocessableEntity(ErrorCode, String),
+    /// Every field that failed validation, reported together as a 422.
+    InvalidFields(Vec<FieldError>),
+    /// Every failing field of a bulk request with the row it is in, reported as a 422.
+    InvalidRows(Vec<RowError>),
     NotFound(String),
+    Conflict(ErrorCode, String),
+    Unauthorized(String),
+    Forbidden(String),
+    /// The row is no longer at the version named in `If-Match`.
+    PreconditionFailed(String),
+    /// A conditional write arrived without `If-Match`.
+    PreconditionRequired(String),
 }
 
-impl IntoResponse for ApiError {
-    fn into_response(self) -> Response {
+impl ApiError {
+    pub fn status(&self) -> StatusCode {
         use ApiError::*;
 
         match self {
-            NotFound(e) => {
-                tracing::info!("{}", e);
-                (
-                    StatusCode::NOT_FOUND,
-                    Json(ApiResponseBody::new_error(
-                        StatusCode::NOT_FOUND,
-                        "Item not found".to_string(),
-                    )),
-                )
-                    .into_response()
+            BadRequest(..) => StatusCode::BAD_REQUEST,
+            InternalServerError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            UnprocessableEntity(..) | InvalidFields(_) | InvalidRows(_) => {
+                StatusCode::UNPROCESSABLE_ENTITY
             }
+            NotFound(_) => StatusCode::NOT_FOUND,
+            Conflict(..) => StatusCode::CONFLICT,
+            Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            Forbidden(_) => StatusCode::FORBIDDEN,
+            PreconditionFailed(_) => StatusCode::PRECONDITION_FAILED,
+            PreconditionRequired(_) => StatusCode::PRECONDITION_REQUIRED,
+        }
+    }
+
+    pub fn code(&self) -> ErrorCode {
+        use ApiError::*;
+
+        match self {
+            BadRequest(code, _) | UnprocessableEntity(code, _) | Conflict(code, _) => *code,
+            InternalServerError(_) => ErrorCode::Internal,
+            InvalidFields(_) | InvalidRows(_) => ErrorCode::ValidationFailed,
+            NotFound(_) => ErrorCode::NotFound,
+            Unauthorized(_) => ErrorCode::Unauthenticated,
+            Forbidden(_) => ErrorCode::MissingScope,
+            PreconditionFailed(_) => ErrorCode::PreconditionFailed,
+            PreconditionRequired(_) => ErrorCode::PreconditionRequired,
+        }
+    }
+}
+
+const PROBLEM_JSON: &str = "application/problem+json";
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        use ApiError::*;
+
+        let status = self.status();
+        let code = self.code();
+        let mut invalid_params = Vec::new();
+        let detail = match self {
+            // Internal messages carry database errors, so they only go to the log.
             InternalServerError(e) => {
                 tracing::error!("{}", e);
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ApiResponseBody::new_error(
-                        StatusCode::INTERNAL_SERVER_ERROR,
-                        e.to_string(),
-                    )),
-                )
-                    .into_response()
+                "The server was unable to complete the request".to_string()
+            }
+            NotFound(e) | Unauthorized(e) => {
+                tracing::info!("{}", e);
+                e
+            }
+            InvalidFields(errors) => {
+                invalid_params = errors.iter().map(InvalidParam::from).collect();
+                format!("Invalid fields: {}", describe(&errors))
             }
-            UnprocessableEntity(message) => (
-                StatusCode::UNPROCESSABLE_ENTITY,
-                Json(ApiResponseBody::new_error(
-                    StatusCode::UNPROCESSABLE_ENTITY,
-                    message,
-                )),
-            )
-                .into_response(),
+            InvalidRows(errors) => {
+                invalid_params = errors.iter().map(InvalidParam::from).collect();
+                format!("Invalid rows: {}", describe(&errors))
+            }
+            BadRequest(_, e)
+            | UnprocessableEntity(_, e)
+            | Conflict(_, e)
+            | Forbidden(e)
+            | PreconditionFailed(e)
+            | PreconditionRequired(e) => e,
+        };
+        let mut response = (
+            status,
+            [(header::CONTENT_TYPE, PROBLEM_JSON)],
+            Json(ProblemDetails {
+                invalid_params,
+                ..ProblemDetails::new(status, code, detail)
+            }),
+        )
+            .into_response();
+        if status == StatusCode::UNAUTHORIZED {
+            response
+                .headers_mut()
+                .insert(header::WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
         }
+        response
     }
 }
 
 /// Generic response structure shared by all API responses.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
 pub struct ApiResponseBody<T: Serialize + PartialEq> {
     status_code: u16,
     data: T,
@@ -90,38 +218,275 @@ impl<T: Serialize + PartialEq> ApiResponseBody<T> {
     }
 }
 
-impl ApiResponseBody<ApiErrorData> {
-    pub fn new_error(status_code: StatusCode, message: String) -> Self {
+/// The `application/problem+json` body (RFC 7807) of every error response.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct ProblemDetails {
+    /// Always `about:blank`; `code` identifies the kind of problem.
+    #[serde(rename = "type")]
+    pub problem_type: String,
+    /// The reason phrase of `status`.
+    pub title: String,
+    pub status: u16,
+    /// A human readable explanation specific to this occurrence.
+    pub detail: String,
+    pub code: ErrorCode,
+    /// The fields that failed validation; only present with `validation_failed`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub invalid_params: Vec<InvalidParam>,
+}
+
+/// One rejected field of a request body.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct InvalidParam {
+    pub name: String,
+    pub reason: String,
+    /// The line of the uploaded file the field is on; only present for imports.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub row: Option<usize>,
+}
+
+impl From<&FieldError> for InvalidParam {
+    fn from(value: &FieldError) -> Self {
+        InvalidParam {
+            name: value.field.to_string(),
+            reason: value.message.clone(),
+            row: None,
+        }
+    }
+}
+
+impl From<&RowError> for InvalidParam {
+    fn from(value: &RowError) -> Self {
+        InvalidParam {
+            row: Some(value.row),
+            ..InvalidParam::from(&value.error)
+        }
+    }
+}
+
+impl ProblemDetails {
+    pub fn new(status: StatusCode, code: ErrorCode, detail: String) -> Self {
         Self {
-            status_code: status_code.as_u16(),
-            data: ApiErrorData { message },
+            problem_type: "about:blank".to_string(),
+            title: status.canonical_reason().unwrap_or_default().to_string(),
+            status: status.as_u16(),
+            detail,
+            code,
+            invalid_params: Vec::new(),
+        }
+    }
+}
+
+/// [axum::Json] that rejects unparseable bodies with a problem response.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ApiJson<T>(pub T);
+
+impl<T: DeserializeOwned, S: Send + Sync> FromRequest<S> for ApiJson<T> {
+    type Rejection = ApiError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Json(value) = Json::<T>::from_request(req, state).await?;
+        Ok(ApiJson(value))
+    }
+}
+
+/// [axum::extract::Query] that rejects unparseable query strings with a problem response.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ApiQuery<T>(pub T);
+
+impl<T: DeserializeOwned, S: Send + Sync> FromRequestParts<S> for ApiQuery<T> {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Query(value) = Query::<T>::from_request_parts(parts, state).await?;
+        Ok(ApiQuery(value))
+    }
+}
+
+/// The version named by a strong `If-Match: "<version>"` header, which every PUT, PATCH and
+/// DELETE of a versioned row requires so that it cannot overwrite a change it has not seen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IfMatch(pub i32);
+
+impl<S: Send + Sync> FromRequestParts<S> for IfMatch {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let mut values = parts.headers.get_all(header::IF_MATCH).iter();
+        let (Some(value), None) = (values.next(), values.next()) else {
+            return Err(ApiError::PreconditionRequired(
+                "Send the ETag of the row being changed in If-Match".to_string(),
+            ));
+        };
+        value
+            .to_str()
+            .ok()
+            .and_then(|x| x.trim().strip_prefix('"')?.strip_suffix('"')?.parse().ok())
+            .map(IfMatch)
+            .ok_or_else(|| {
+                ApiError::BadRequest(
+                    ErrorCode::MalformedRequest,
+                    "If-Match must be a single ETag returned by this API".to_string(),
+                )
+            })
+    }
+}
+
+impl From<JsonRejection> for ApiError {
+    fn from(rejection: JsonRejection) -> Self {
+        match rejection {
+            // The body is well formed JSON but has missing fields or values of the wrong type.
+            JsonRejection::JsonDataError(e) => {
+                Self::UnprocessableEntity(ErrorCode::ValidationFailed, e.body_text())
+            }
+            e => Self::BadRequest(ErrorCode::MalformedRequest, e.body_text()),
         }
     }
 }
 
-/// The response data format for all error responses.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
-pub struct ApiErrorData {
-    pub message: String,
+impl From<QueryRejection> for ApiError {
+    fn from(rejection: QueryRejection) -> Self {
+        Self::BadRequest(ErrorCode::MalformedRequest, rejection.body_text())
+    }
 }
 
 impl From<uuid::Error> for ApiError {
     fn from(error: uuid::Error) -> Self {
-        ApiError::NotFound(format!("Invalid UUID: {}", error))
+        ApiError::BadRequest(ErrorCode::InvalidId, format!("Invalid UUID: {}", error))
+    }
+}
+
+impl From<AuthError> for ApiError {
+    fn from(e: AuthError) -> Self {
+        match e {
+            AuthError::Lookup(e) => Self::from(e),
+            AuthError::RoleLookup(e) => Self::from(e),
+            AuthError::MissingScope(scope) => Self::Forbidden(format!("Missing scope {}", scope)),
+            e => Self::Unauthorized(e.to_string()),
+        }
+    }
+}
+
+impl From<GetUserError> for ApiError {
+    fn from(e: GetUserError) -> Self {
+        match e {
+            GetUserError::Unknown { source } => {
+                Self::InternalServerError(format!("Unable to process user from source {}", source))
+            }
+        }
+    }
+}
+
+impl From<GetRoleError> for ApiError {
+    fn from(e: GetRoleError) -> Self {
+        match e {
+            GetRoleError::Unknown { source } => {
+                Self::InternalServerError(format!("Unable to process role from source {}", source))
+            }
+        }
+    }
+}
+
+impl From<GetUsersError> for ApiError {
+    fn from(e: GetUsersError) -> Self {
+        match e {
+            GetUsersError::Unknown { source } => {
+                Self::InternalServerError(format!("Unable to process users from source {}", source))
+            }
+        }
+    }
+}
+
+impl From<CreateUserError> for ApiError {
+    fn from(e: CreateUserError) -> Self {
+        match e {
+            CreateUserError::Duplicate { email, source: _ } => Self::Conflict(
+                ErrorCode::Duplicate,
+                format!("A user with email {} already exists", email),
+            ),
+            CreateUserError::MissingRole { role_id, source: _ } => Self::UnprocessableEntity(
+                ErrorCode::InvalidReference,
+                format!("Role {} does not exist", role_id),
+            ),
+            CreateUserError::Unknown { source } => {
+                Self::InternalServerError(format!("Unable to process user from source {}", source))
+            }
+            CreateUserError::CommitFailed { source } => {
+                Self::InternalServerError(format!("CommitFailed for creating a user at {}", source))
+            }
+        }
+    }
+}
+
+impl From<UpdateUserError> for ApiError {
+    fn from(e: UpdateUserError) -> Self {
+        match e {
+            UpdateUserError::NotFound { id } => {
+                Self::NotFound(format!("User with id {} not found", id))
+            }
+            UpdateUserError::MissingRole { role_id, source: _ } => Self::UnprocessableEntity(
+                ErrorCode::InvalidReference,
+                format!("Role {} does not exist", role_id),
+            ),
+            UpdateUserError::Unknown { source } => {
+                Self::InternalServerError(format!("Unable to process user from source {}", source))
+            }
+            UpdateUserError::CommitFailed { source } => {
+                Self::InternalServerError(format!("CommitFailed for updating a user at {}", source))
+            }
+        }
+    }
+}
+
+impl From<CreateRoleError> for ApiError {
+    fn from(e: CreateRoleError) -> Self {
+        match e {
+            CreateRoleError::Unknown { source } => {
+                Self::InternalServerError(format!("Unable to process role from source {}", source))
+            }
+            CreateRoleError::CommitFailed { source } => {
+                Self::InternalServerError(format!("CommitFailed for creating a role at {}", source))
+            }
+        }
+    }
+}
+
+impl From<GetRolesError> for ApiError {
+    fn from(e: GetRolesError) -> Self {
+        match e {
+            GetRolesError::Unknown { source } => {
+                Self::InternalServerError(format!("Unable to process roles from source {}", source))
+            }
+        }
+    }
+}
+
+impl From<GetAuditEntriesError> for ApiError {
+    fn from(e: GetAuditEntriesError) -> Self {
+        match e {
+            GetAuditEntriesError::InvalidCursor { id } => Self::UnprocessableEntity(
+                ErrorCode::InvalidCursor,
+                format!("Cursor {} is no longer valid", id),
+            ),
+            GetAuditEntriesError::Unknown { source } => Self::InternalServerError(format!(
+                "Unable to process audit entries from source {}",
+                source
+            )),
+        }
     }
 }
 
 impl From<CreateEmployeeError> for ApiError {
     fn from(e: CreateEmployeeError) -> Self {
         match e {
-            CreateEmployeeError::CreateFailed { id, source } => Self::UnprocessableEntity(format!(
-                "employee with id {} already exists from source {}",
-                id, source
-            )),
-            CreateEmployeeError::Duplicate { id, source } => Self::InternalServerError(format!(
-                "Duplicate error updating or creating a employee {} from source {}",
+            CreateEmployeeError::CreateFailed { id, source } => Self::InternalServerError(format!(
+                "Failed to create employee {} from source {}",
                 id, source
             )),
+            CreateEmployeeError::Duplicate { id, source: _ } => Self::Conflict(
+                ErrorCode::Duplicate,
+                format!("An employee with email {} already exists", id),
+            ),
             CreateEmployeeError::Unknown { source } => Self::InternalServerError(format!(
                 "Unable to process employee from source {}",
                 source
@@ -137,6 +502,14 @@ impl From<CreateEmployeeError> for ApiError {
 impl From<UpdateEmployeeError> for ApiError {
     fn from(e: UpdateEmployeeError) -> Self {
         match e {
+            UpdateEmployeeError::NotFound { id } => {
+                Self::NotFound(format!("Employee with id {} not found", id))
+            }
+            UpdateEmployeeError::Stale { .. } => Self::PreconditionFailed(e.to_string()),
+            UpdateEmployeeError::Duplicate { email, source: _ } => Self::Conflict(
+                ErrorCode::Duplicate,
+                format!("An employee with email {} already exists", email),
+            ),
             UpdateEmployeeError::Unknown { source } => Self::InternalServerError(format!(
                 "Unable to process employee from source {}",
                 source
@@ -149,13 +522,39 @@ impl From<UpdateEmployeeError> for ApiError {
     }
 }
 
-impl From<DeleteEmployeeError> for ApiError {
-    fn from(e: DeleteEmployeeError) -> Self {
+impl From<TerminateEmployeeError> for ApiError {
+    fn from(e: TerminateEmployeeError) -> Self {
+        match e {
+            TerminateEmployeeError::NotFound { .. } => Self::NotFound(e.to_string()),
+            TerminateEmployeeError::Stale { .. } => Self::PreconditionFailed(e.to_string()),
+            TerminateEmployeeError::Unknown { source } => Self::InternalServerError(format!(
+                "Unable to process employee from source {}",
+                source
+            )),
+            TerminateEmployeeError::CommitFailed { source } => Self::InternalServerError(format!(
+                "CommitFailed for terminating an employee at {}",
+                source
+            )),
+        }
+    }
+}
+
+impl From<RestoreEmployeeError> for ApiError {
+    fn from(e: RestoreEmployeeError) -> Self {
         match e {
-            DeleteEmployeeError::Unknown { source } => Self::InternalServerError(format!(
+            RestoreEmployeeError::NotFound { .. } => Self::NotFound(e.to_string()),
+            RestoreEmployeeError::Stale { .. } => Self::PreconditionFailed(e.to_string()),
+            RestoreEmployeeError::NotTerminated { .. } => {
+                Self::Conflict(ErrorCode::NotTerminated, e.to_string())
+            }
+            RestoreEmployeeError::Unknown { source } => Self::InternalServerError(format!(
                 "Unable to process employee from source {}",
                 source
             )),
+            RestoreEmployeeError::CommitFailed { source } => Self::InternalServerError(format!(
+                "CommitFailed for restoring an employee at {}",
+                source
+            )),
         }
     }
 }
@@ -174,6 +573,10 @@ impl From<GetEmployeeError> for ApiError {
 impl From<GetEmployeesError> for ApiError {
     fn from(e: GetEmployeesError) -> Self {
         match e {
+            GetEmployeesError::InvalidCursor { id } => Self::UnprocessableEntity(
+                ErrorCode::InvalidCursor,
+                format!("Cursor {} is no longer valid", id),
+            ),
             GetEmployeesError::Unknown { source } => Self::InternalServerError(format!(
                 "Unable to process employee from source {}",
                 source
@@ -181,3 +584,585 @@ impl From<GetEmployeesError> for ApiError {
         }
     }
 }
+
+impl From<AddSalaryError> for ApiError {
+    fn from(e: AddSalaryError) -> Self {
+        match e {
+            AddSalaryError::NotFound { .. } => Self::NotFound(e.to_string()),
+            AddSalaryError::Stale { .. } => Self::PreconditionFailed(e.to_string()),
+            AddSalaryError::Unknown { source } => Self::InternalServerError(format!(
+                "Unable to process salary from source {}",
+                source
+            )),
+            AddSalaryError::CommitFailed { source } => {
+                Self::InternalServerError(format!("CommitFailed for adding a salary at {}", source))
+            }
+        }
+    }
+}
+
+impl From<GetSalariesError> for ApiError {
+    fn from(e: GetSalariesError) -> Self {
+        match e {
+            GetSalariesError::Unknown { source } => Self::InternalServerError(format!(
+                "Unable to process salaries from source {}",
+                source
+            )),
+        }
+    }
+}
+
+impl From<CreateInitiativeError> for ApiError {
+    fn from(e: CreateInitiativeError) -> Self {
+        match e {
+            CreateInitiativeError::Unknown { source } => Self::InternalServerError(format!(
+                "Unable to process initiative from source {}",
+                source
+            )),
+            CreateInitiativeError::CommitFailed { source } => Self::InternalServerError(format!(
+                "CommitFailed for creating an initiative at {}",
+                source
+            )),
+        }
+    }
+}
+
+impl From<UpdateInitiativeError> for ApiError {
+    fn from(e: UpdateInitiativeError) -> Self {
+        match e {
+            UpdateInitiativeError::NotFound { id } => {
+                Self::NotFound(format!("Initiative {} not found", id))
+            }
+            UpdateInitiativeError::Stale { .. } => Self::PreconditionFailed(e.to_string()),
+            UpdateInitiativeError::Unknown { source } => Self::InternalServerError(format!(
+                "Unable to process initiative from source {}",
+                source
+            )),
+            UpdateInitiativeError::CommitFailed { source } => Self::InternalServerError(format!(
+                "CommitFailed for updating an initiative at {}",
+                source
+            )),
+        }
+    }
+}
+
+impl From<DeleteInitiativeError> for ApiError {
+    fn from(e: DeleteInitiativeError) -> Self {
+        match e {
+            DeleteInitiativeError::NotFound { .. } => Self::NotFound(e.to_string()),
+            DeleteInitiativeError::Stale { .. } => Self::PreconditionFailed(e.to_string()),
+            DeleteInitiativeError::Unknown { source } => Self::InternalServerError(format!(
+                "Unable to process initiative from source {}",
+                source
+            )),
+            DeleteInitiativeError::CommitFailed { source } => Self::InternalServerError(format!(
+                "CommitFailed for deleting an initiative at {}",
+                source
+            )),
+        }
+    }
+}
+
+impl From<GetInitiativeError> for ApiError {
+    fn from(e: GetInitiativeError) -> Self {
+        match e {
+            GetInitiativeError::Unknown { source } => Self::InternalServerError(format!(
+                "Unable to process initiative from source {}",
+                source
+            )),
+        }
+    }
+}
+
+impl From<GetInitiativesError> for ApiError {
+    fn from(e: GetInitiativesError) -> Self {
+        match e {
+            GetInitiativesError::Unknown { source } => Self::InternalServerError(format!(
+                "Unable to process initiatives from source {}",
+                source
+            )),
+        }
+    }
+}
+
+impl From<CreateTagError> for ApiError {
+    fn from(e: CreateTagError) -> Self {
+        match e {
+            CreateTagError::Unknown { source } => {
+                Self::InternalServerError(format!("Unable to process tag from source {}", source))
+            }
+            CreateTagError::CommitFailed { source } => {
+                Self::InternalServerError(format!("CommitFailed for creating a tag at {}", source))
+            }
+        }
+    }
+}
+
+impl From<RenameTagError> for ApiError {
+    fn from(e: RenameTagError) -> Self {
+        match e {
+            RenameTagError::NotFound { id } => Self::NotFound(format!("Tag {} not found", id)),
+            RenameTagError::Stale { .. } => Self::PreconditionFailed(e.to_string()),
+            RenameTagError::Unknown { source } => {
+                Self::InternalServerError(format!("Unable to process tag from source {}", source))
+            }
+            RenameTagError::CommitFailed { source } => {
+                Self::InternalServerError(format!("CommitFailed for renaming a tag at {}", source))
+            }
+        }
+    }
+}
+
+impl From<MergeTagsError> for ApiError {
+    fn from(e: MergeTagsError) -> Self {
+        match e {
+            MergeTagsError::NotFound { id } => Self::NotFound(format!("Tag {} not found", id)),
+            MergeTagsError::Stale { .. } => Self::PreconditionFailed(e.to_string()),
+            MergeTagsError::SameTag { id } => Self::UnprocessableEntity(
+                ErrorCode::ValidationFailed,
+                format!("Cannot merge tag {} into itself", id),
+            ),
+            MergeTagsError::Unknown { source } => {
+                Self::InternalServerError(format!("Unable to process tag from source {}", source))
+            }
+            MergeTagsError::CommitFailed { source } => {
+                Self::InternalServerError(format!("CommitFailed for merging tags at {}", source))
+            }
+        }
+    }
+}
+
+impl From<DeleteTagError> for ApiError {
+    fn from(e: DeleteTagError) -> Self {
+        match e {
+            DeleteTagError::NotFound { .. } => Self::NotFound(e.to_string()),
+            DeleteTagError::Stale { .. } => Self::PreconditionFailed(e.to_string()),
+            DeleteTagError::Unknown { source } => {
+                Self::InternalServerError(format!("Unable to process tag from source {}", source))
+            }
+            DeleteTagError::CommitFailed { source } => {
+                Self::InternalServerError(format!("CommitFailed for deleting a tag at {}", source))
+            }
+        }
+    }
+}
+
+impl From<GetTagError> for ApiError {
+    fn from(e: GetTagError) -> Self {
+        match e {
+            GetTagError::Unknown { source } => {
+                Self::InternalServerError(format!("Unable to process tag from source {}", source))
+            }
+        }
+    }
+}
+
+impl From<GetTagsError> for ApiError {
+    fn from(e: GetTagsError) -> Self {
+        match e {
+            GetTagsError::Unknown { source } => {
+                Self::InternalServerError(format!("Unable to process tags from source {}", source))
+            }
+        }
+    }
+}
+
+impl From<CreateAssignmentError> for ApiError {
+    fn from(e: CreateAssignmentError) -> Self {
+        match e {
+            CreateAssignmentError::Unknown { source } => Self::InternalServerError(format!(
+                "Unable to process assignment from source {}",
+                source
+            )),
+            CreateAssignmentError::CommitFailed { source } => Self::InternalServerError(format!(
+                "CommitFailed for creating an assignment at {}",
+                source
+            )),
+        }
+    }
+}
+
+impl From<UpdateAssignmentError> for ApiError {
+    fn from(e: UpdateAssignmentError) -> Self {
+        match e {
+            UpdateAssignmentError::NotFound { id } => {
+                Self::NotFound(format!("Assignment {} not found", id))
+            }
+            UpdateAssignmentError::Stale { .. } => Self::PreconditionFailed(e.to_string()),
+            UpdateAssignmentError::Overallocated { .. } => {
+                Self::UnprocessableEntity(ErrorCode::Overallocated, e.to_string())
+            }
+            UpdateAssignmentError::Unknown { source } => Self::InternalServerError(format!(
+                "Unable to process assignment from source {}",
+                source
+            )),
+            UpdateAssignmentError::CommitFailed { source } => Self::InternalServerError(format!(
+                "CommitFailed for updating an assignment at {}",
+                source
+            )),
+        }
+    }
+}
+
+impl From<DeleteAssignmentError> for ApiError {
+    fn from(e: DeleteAssignmentError) -> Self {
+        match e {
+            DeleteAssignmentError::NotFound { .. } => Self::NotFound(e.to_string()),
+            DeleteAssignmentError::Stale { .. } => Self::PreconditionFailed(e.to_string()),
+            DeleteAssignmentError::Unknown { source } => Self::InternalServerError(format!(
+                "Unable to process assignment from source {}",
+                source
+            )),
+            DeleteAssignmentError::CommitFailed { source } => Self::InternalServerError(format!(
+                "CommitFailed for deleting an assignment at {}",
+                source
+            )),
+        }
+    }
+}
+
+impl From<GetAssignmentError> for ApiError {
+    fn from(e: GetAssignmentError) -> Self {
+        match e {
+            GetAssignmentError::Unknown { source } => Self::InternalServerError(format!(
+                "Unable to process assignment from source {}",
+                source
+            )),
+        }
+    }
+}
+
+impl From<GetAssignmentsError> for ApiError {
+    fn from(e: GetAssignmentsError) -> Self {
+        match e {
+            GetAssignmentsError::Unknown { source } => Self::InternalServerError(format!(
+                "Unable to process assignments from source {}",
+                source
+            )),
+        }
+    }
+}
+
+impl From<CreateEmployeeAssignmentError> for ApiError {
+    fn from(e: CreateEmployeeAssignmentError) -> Self {
+        match e {
+            CreateEmployeeAssignmentError::Duplicate {
+                employee_id,
+                initiative_id,
+                source: _,
+            } => Self::Conflict(
+                ErrorCode::Duplicate,
+                format!(
+                    "Employee {} is already assigned to initiative {}",
+                    employee_id, initiative_id
+                ),
+            ),
+            CreateEmployeeAssignmentError::MissingReference { source: _ } => {
+                Self::UnprocessableEntity(
+                    ErrorCode::InvalidReference,
+                    "Employee, assignment or initiative does not exist".to_string(),
+                )
+            }
+            CreateEmployeeAssignmentError::InvalidAllocation { source: _ } => {
+                Self::UnprocessableEntity(
+                    ErrorCode::ValidationFailed,
+                    "Allocation must be between 0 and 1".to_string(),
+                )
+            }
+            CreateEmployeeAssignmentError::Overallocated { .. } => {
+                Self::UnprocessableEntity(ErrorCode::Overallocated, e.to_string())
+            }
+            CreateEmployeeAssignmentError::Unknown { source } => {
+                Self::InternalServerError(format!(
+                    "Unable to process employee assignment from source {}",
+                    source
+                ))
+            }
+            CreateEmployeeAssignmentError::CommitFailed { source } => {
+                Self::InternalServerError(format!(
+                    "CommitFailed for creating an employee assignment at {}",
+                    source
+                ))
+            }
+        }
+    }
+}
+
+impl From<UpdateEmployeeAssignmentError> for ApiError {
+    fn from(e: UpdateEmployeeAssignmentError) -> Self {
+        match e {
+            UpdateEmployeeAssignmentError::NotFound { id } => {
+                Self::NotFound(format!("Employee assignment {} not found", id))
+            }
+            UpdateEmployeeAssignmentError::Stale { .. } => Self::PreconditionFailed(e.to_string()),
+            UpdateEmployeeAssignmentError::Duplicate {
+                employee_id,
+                initiative_id,
+                source: _,
+            } => Self::Conflict(
+                ErrorCode::Duplicate,
+                format!(
+                    "Employee {} is already assigned to initiative {}",
+                    employee_id, initiative_id
+                ),
+            ),
+            UpdateEmployeeAssignmentError::MissingReference { source: _ } => {
+                Self::UnprocessableEntity(
+                    ErrorCode::InvalidReference,
+                    "Assignment or initiative does not exist".to_string(),
+                )
+            }
+            UpdateEmployeeAssignmentError::InvalidAllocation { source: _ } => {
+                Self::UnprocessableEntity(
+                    ErrorCode::ValidationFailed,
+                    "Allocation must be between 0 and 1".to_string(),
+                )
+            }
+            UpdateEmployeeAssignmentError::Overallocated { .. } => {
+                Self::UnprocessableEntity(ErrorCode::Overallocated, e.to_string())
+            }
+            UpdateEmployeeAssignmentError::Unknown { source } => {
+                Self::InternalServerError(format!(
+                    "Unable to process employee assignment from source {}",
+                    source
+                ))
+            }
+            UpdateEmployeeAssignmentError::CommitFailed { source } => {
+                Self::InternalServerError(format!(
+                    "CommitFailed for updating an employee assignment at {}",
+                    source
+                ))
+            }
+        }
+    }
+}
+
+impl From<DeleteEmployeeAssignmentError> for ApiError {
+    fn from(e: DeleteEmployeeAssignmentError) -> Self {
+        match e {
+            DeleteEmployeeAssignmentError::NotFound { .. } => Self::NotFound(e.to_string()),
+            DeleteEmployeeAssignmentError::Stale { .. } => Self::PreconditionFailed(e.to_string()),
+            DeleteEmployeeAssignmentError::Unknown { source } => {
+                Self::InternalServerError(format!(
+                    "Unable to process employee assignment from source {}",
+                    source
+                ))
+            }
+            DeleteEmployeeAssignmentError::CommitFailed { source } => {
+                Self::InternalServerError(format!(
+                    "CommitFailed for deleting an employee assignment at {}",
+                    source
+                ))
+            }
+        }
+    }
+}
+
+impl From<GetEmployeeAssignmentsError> for ApiError {
+    fn from(e: GetEmployeeAssignmentsError) -> Self {
+        match e {
+            GetEmployeeAssignmentsError::Unknown { source } => Self::InternalServerError(format!(
+                "Unable to process employee assignments from source {}",
+                source
+            )),
+        }
+    }
+}
+
+impl From<AddEmployeeError> for ApiError {
+    fn from(e: AddEmployeeError) -> Self {
+        match e {
+            AddEmployeeError::Invalid { errors } => Self::InvalidFields(errors),
+            AddEmployeeError::Create(e) => Self::from(e),
+        }
+    }
+}
+
+impl From<CreateEmployeesError> for ApiError {
+    fn from(e: CreateEmployeesError) -> Self {
+        match e {
+            CreateEmployeesError::Duplicate { .. } => {
+                Self::Conflict(ErrorCode::Duplicate, e.to_string())
+            }
+            CreateEmployeesError::CommitFailed { source } => Self::InternalServerError(format!(
+                "CommitFailed for importing employees at {}",
+                source
+            )),
+            CreateEmployeesError::Unknown { source } => Self::InternalServerError(format!(
+                "Unable to process employees from source {}",
+                source
+            )),
+        }
+    }
+}
+
+impl From<SavePlanError> for ApiError {
+    fn from(e: SavePlanError) -> Self {
+        match e {
+            SavePlanError::StaleAssignment { .. }
+            | SavePlanError::StaleLink { .. }
+            | SavePlanError::Duplicate { .. } => Self::Conflict(
+                ErrorCode::PlanChanged,
+                format!("{}; import the plan again", e),
+            ),
+            SavePlanError::Overallocated { .. } => {
+                Self::UnprocessableEntity(ErrorCode::Overallocated, e.to_string())
+            }
+            SavePlanError::Unknown { source } => Self::InternalServerError(format!(
+                "Unable to store allocation plan from source {}",
+                source
+            )),
+            SavePlanError::CommitFailed { source } => Self::InternalServerError(format!(
+                "CommitFailed for storing an allocation plan at {}",
+                source
+            )),
+        }
+    }
+}
+
+impl From<ImportAllocationPlanError> for ApiError {
+    fn from(e: ImportAllocationPlanError) -> Self {
+        match e {
+            ImportAllocationPlanError::Invalid { errors } => Self::InvalidRows(errors),
+            ImportAllocationPlanError::GetSchedule(e) => Self::from(e),
+            ImportAllocationPlanError::GetEmployees(e) => Self::from(e),
+            ImportAllocationPlanError::GetInitiatives(e) => Self::from(e),
+            ImportAllocationPlanError::Save(e) => Self::from(e),
+        }
+    }
+}
+
+impl From<ImportEmployeesError> for ApiError {
+    fn from(e: ImportEmployeesError) -> Self {
+        match e {
+            ImportEmployeesError::Invalid { errors } => Self::InvalidRows(errors),
+            ImportEmployeesError::Create(e) => Self::from(e),
+        }
+    }
+}
+
+impl From<EditEmployeeError> for ApiError {
+    fn from(e: EditEmployeeError) -> Self {
+        match e {
+            EditEmployeeError::Invalid { errors } => Self::InvalidFields(errors),
+            EditEmployeeError::Update(e) => Self::from(e),
+        }
+    }
+}
+
+impl From<ChangeSalaryError> for ApiError {
+    fn from(e: ChangeSalaryError) -> Self {
+        match e {
+            ChangeSalaryError::Invalid { errors } => Self::InvalidFields(errors),
+            ChangeSalaryError::Add(e) => Self::from(e),
+        }
+    }
+}
+
+impl From<AssignEmployeeError> for ApiError {
+    fn from(e: AssignEmployeeError) -> Self {
+        match e {
+            AssignEmployeeError::InvalidAllocation { .. } => {
+                Self::UnprocessableEntity(ErrorCode::ValidationFailed, e.to_string())
+            }
+            AssignEmployeeError::Overallocated { .. } => {
+                Self::UnprocessableEntity(ErrorCode::Overallocated, e.to_string())
+            }
+            AssignEmployeeError::Terminated { .. } => {
+                Self::UnprocessableEntity(ErrorCode::Terminated, e.to_string())
+            }
+            AssignEmployeeError::AssignmentNotFound { .. } => {
+                Self::UnprocessableEntity(ErrorCode::InvalidReference, e.to_string())
+            }
+            AssignEmployeeError::GetAssignment(e) => Self::from(e),
+            AssignEmployeeError::GetEmployee(e) => Self::from(e),
+            AssignEmployeeError::Create(e) => Self::from(e),
+        }
+    }
+}
+
+impl From<ReassignEmployeeError> for ApiError {
+    fn from(e: ReassignEmployeeError) -> Self {
+        match e {
+            ReassignEmployeeError::InvalidAllocation { .. } => {
+                Self::UnprocessableEntity(ErrorCode::ValidationFailed, e.to_string())
+            }
+            ReassignEmployeeError::Overallocated { .. } => {
+                Self::UnprocessableEntity(ErrorCode::Overallocated, e.to_string())
+            }
+            ReassignEmployeeError::Terminated { .. } => {
+                Self::UnprocessableEntity(ErrorCode::Terminated, e.to_string())
+            }
+            ReassignEmployeeError::AssignmentNotFound { .. } => {
+                Self::UnprocessableEntity(ErrorCode::InvalidReference, e.to_string())
+            }
+            ReassignEmployeeError::GetAssignment(e) => Self::from(e),
+            ReassignEmployeeError::GetEmployee(e) => Self::from(e),
+            ReassignEmployeeError::Update(e) => Self::from(e),
+        }
+    }
+}
+
+impl From<CreateAllocationCurveError> for ApiError {
+    fn from(e: CreateAllocationCurveError) -> Self {
+        match e {
+            CreateAllocationCurveError::InvalidFraction { .. } => {
+                Self::UnprocessableEntity(ErrorCode::ValidationFailed, e.to_string())
+            }
+            CreateAllocationCurveError::Create(e) => Self::from(e),
+        }
+    }
+}
+
+impl From<UpdateAllocationCurveError> for ApiError {
+    fn from(e: UpdateAllocationCurveError) -> Self {
+        match e {
+            UpdateAllocationCurveError::InvalidFraction { .. } => {
+                Self::UnprocessableEntity(ErrorCode::ValidationFailed, e.to_string())
+            }
+            UpdateAllocationCurveError::Overallocated { .. } => {
+                Self::UnprocessableEntity(ErrorCode::Overallocated, e.to_string())
+            }
+            UpdateAllocationCurveError::Update(e) => Self::from(e),
+        }
+    }
+}
+
+impl From<GetOverallocatedError> for ApiError {
+    fn from(e: GetOverallocatedError) -> Self {
+        match e {
+            GetOverallocatedError::GetSchedule(e) => Self::from(e),
+            GetOverallocatedError::GetEmployees(e) => Self::from(e),
+        }
+    }
+}
+
+impl From<GetInitiativeCostsError> for ApiError {
+    fn from(e: GetInitiativeCostsError) -> Self {
+        match e {
+            GetInitiativeCostsError::GetSchedule(e) => Self::from(e),
+            GetInitiativeCostsError::GetEmployees(e) => Self::from(e),
+            GetInitiativeCostsError::GetSalaries(e) => Self::from(e),
+            GetInitiativeCostsError::GetInitiatives(e) => Self::from(e),
+        }
+    }
+}
+
+impl From<GetAllocationPlanError> for ApiError {
+    fn from(e: GetAllocationPlanError) -> Self {
+        match e {
+            GetAllocationPlanError::GetSchedule(e) => Self::from(e),
+            GetAllocationPlanError::GetEmployees(e) => Self::from(e),
+            GetAllocationPlanError::GetInitiatives(e) => Self::from(e),
+        }
+    }
+}
+
+impl From<GetUtilizationError> for ApiError {
+    fn from(e: GetUtilizationError) -> Self {
+        match e {
+            GetUtilizationError::GetSchedule(e) => Self::from(e),
+            GetUtilizationError::GetEmployees(e) => Self::from(e),
+        }
+    }
+}
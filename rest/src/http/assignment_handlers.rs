@@ -0,0 +1,167 @@
+use crate::http::api::{
+    ApiError, ApiJson, ApiQuery, ApiResponseBody, ApiSuccess, IfMatch, ProblemDetails,
+};
+use crate::models::assignment::*;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use repository::assignment_repo::AssignmentRepo;
+use service::allocation_service::AllocationRepo;
+use uuid::Uuid;
+
+use crate::http::AppState;
+use crate::http::auth::CurrentUser;
+
+#[utoipa::path(
+    post,
+    path = "/api/assignments",
+    tag = "assignments",
+    request_body = CreateAssignmentRequest,
+    responses(
+        (status = 201, description = "Allocation curve created", body = ApiResponseBody<GetAssignmentResponse>,
+            headers(("ETag" = String, description = "The allocation curve's version"))),
+        (status = 400, description = "Malformed body", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid bearer token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Missing allocations:write", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "A monthly fraction is outside 0..=1", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn create_assignment<AR: AssignmentRepo>(
+    State(state): State<AppState<AR>>,
+    user: CurrentUser,
+    ApiJson(body): ApiJson<CreateAssignmentRequest>,
+) -> Result<ApiSuccess<GetAssignmentResponse>, ApiError> {
+    let domain_req = (&body).into();
+    state
+        .allocation_service
+        .create_assignment(user.user.id, &domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref ri| ApiSuccess::new(StatusCode::CREATED, ri.into()).with_version(ri.version))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/assignments/{id}",
+    tag = "assignments",
+    params(
+        ("id" = Uuid, Path, description = "Assignment id"),
+        ("If-Match" = String, Header, description = "The ETag of the allocation curve being replaced"),
+    ),
+    request_body = UpdateAssignmentRequest,
+    responses(
+        (status = 200, description = "Allocation curve updated", body = ApiResponseBody<GetAssignmentResponse>,
+            headers(("ETag" = String, description = "The allocation curve's new version"))),
+        (status = 400, description = "Malformed id, body or If-Match", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid bearer token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Missing allocations:write", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Assignment not found", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 412, description = "The allocation curve changed since the ETag in If-Match", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "A fraction is invalid or an employee would be over-allocated", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 428, description = "If-Match is missing", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn update_assignment<R: AllocationRepo>(
+    State(state): State<AppState<R>>,
+    user: CurrentUser,
+    Path(id): Path<String>,
+    IfMatch(version): IfMatch,
+    ApiJson(body): ApiJson<UpdateAssignmentRequest>,
+) -> Result<ApiSuccess<GetAssignmentResponse>, ApiError> {
+    let assignment_id = Uuid::parse_str(&id).map_err(ApiError::from)?;
+    let domain_req = body.to_dto(assignment_id, version);
+    state
+        .allocation_service
+        .update_assignment(user.user.id, &domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref ri| ApiSuccess::new(StatusCode::OK, ri.into()).with_version(ri.version))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/assignments/{id}",
+    tag = "assignments",
+    params(("id" = Uuid, Path, description = "Assignment id")),
+    responses(
+        (status = 200, description = "The allocation curve", body = ApiResponseBody<GetAssignmentResponse>,
+            headers(("ETag" = String, description = "The allocation curve's version, for If-Match"))),
+        (status = 400, description = "Malformed id", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid bearer token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Missing allocations:read", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Assignment not found", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn get_assignment<AR: AssignmentRepo>(
+    State(state): State<AppState<AR>>,
+    Path(id): Path<String>,
+) -> Result<ApiSuccess<GetAssignmentResponse>, ApiError> {
+    let assignment_id = Uuid::parse_str(&id).map_err(ApiError::from)?;
+    match state.allocation_service.get_assignment(assignment_id).await {
+        Ok(Some(ri)) => Ok(
+            ApiSuccess::new(StatusCode::OK, GetAssignmentResponse::from(&ri))
+                .with_version(ri.version),
+        ),
+        Ok(None) => Err(ApiError::NotFound("Assignment not found".to_string())),
+        Err(e) => Err(ApiError::from(e)),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/assignments/{id}",
+    tag = "assignments",
+    params(
+        ("id" = Uuid, Path, description = "Assignment id"),
+        ("If-Match" = String, Header, description = "The ETag of the allocation curve being deleted"),
+    ),
+    responses(
+        (status = 200, description = "Allocation curve deleted", body = ApiResponseBody<DeleteAssignmentResponse>),
+        (status = 400, description = "Malformed id or If-Match", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid bearer token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Missing allocations:write", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Assignment not found", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 412, description = "The allocation curve changed since the ETag in If-Match", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 428, description = "If-Match is missing", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn delete_assignment<AR: AssignmentRepo>(
+    State(state): State<AppState<AR>>,
+    user: CurrentUser,
+    Path(id): Path<String>,
+    IfMatch(version): IfMatch,
+) -> Result<ApiSuccess<DeleteAssignmentResponse>, ApiError> {
+    let assignment_id = Uuid::parse_str(&id).map_err(ApiError::from)?;
+    state
+        .allocation_service
+        .delete_assignment(user.user.id, assignment_id, version)
+        .await
+        .map_err(ApiError::from)
+        .map(|()| ApiSuccess::new(StatusCode::OK, DeleteAssignmentResponse {}))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/assignments",
+    tag = "assignments",
+    params(GetAssignmentsQuery),
+    responses(
+        (status = 200, description = "Allocation curves", body = ApiResponseBody<Vec<GetAssignmentResponse>>),
+        (status = 400, description = "Malformed query string", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid bearer token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Missing allocations:read", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn get_assignments<AR: AssignmentRepo>(
+    State(state): State<AppState<AR>>,
+    ApiQuery(query): ApiQuery<GetAssignmentsQuery>,
+) -> Result<ApiSuccess<Vec<GetAssignmentResponse>>, ApiError> {
+    state
+        .allocation_service
+        .get_assignments(query.year)
+        .await
+        .map_err(ApiError::from)
+        .map(|items| {
+            let response_data = items.iter().map(GetAssignmentResponse::from).collect();
+            ApiSuccess::new(StatusCode::OK, response_data)
+        })
+}
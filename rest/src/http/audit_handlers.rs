@@ -0,0 +1,43 @@
+use crate::http::api::{ApiError, ApiQuery, ApiResponseBody, ApiSuccess, ProblemDetails};
+use crate::http::auth::CurrentUser;
+use crate::models::audit::*;
+use axum::extract::State;
+use axum::http::StatusCode;
+use domain::dto::Scope;
+use repository::audit_repo::AuditRepo;
+use service::allocation_service::DEFAULT_PAGE_SIZE;
+
+use crate::http::AppState;
+
+#[utoipa::path(
+    get,
+    path = "/api/audit",
+    tag = "audit",
+    params(GetAuditQuery),
+    responses(
+        (status = 200, description = "One page of matching audit entries, newest first", body = ApiResponseBody<GetAuditEntriesResponse>),
+        (status = 400, description = "Malformed query string", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid bearer token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Missing audit:read", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "The cursor no longer refers to an audit entry", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+/// Lists who changed rows of one kind, optionally a single row, with the row before and after.
+pub async fn get_audit_entries<R: AuditRepo>(
+    State(state): State<AppState<R>>,
+    user: CurrentUser,
+    ApiQuery(query): ApiQuery<GetAuditQuery>,
+) -> Result<ApiSuccess<GetAuditEntriesResponse>, ApiError> {
+    let show_salary = user.has(Scope::SalaryRead);
+    state
+        .allocation_service
+        .get_audit_entries(&query.to_dto(DEFAULT_PAGE_SIZE))
+        .await
+        .map_err(ApiError::from)
+        .map(|page| {
+            ApiSuccess::new(
+                StatusCode::OK,
+                GetAuditEntriesResponse::new(&page, show_salary),
+            )
+        })
+}
@@ -0,0 +1,282 @@
+/*!
+    Bearer token authentication. Every `/api` route runs [authenticate], which verifies the JWT in
+    the `Authorization` header and resolves its subject and issuer to a row in `users`, along
+    with the scopes of the user's role. Routes then require scopes with [authorize], and handlers
+    that need the caller take a [CurrentUser] argument.
+*/
+
+use crate::http::AppState;
+use crate::http::api::ApiError;
+use axum::extract::{FromRequestParts, Request, State};
+use axum::http::header::AUTHORIZATION;
+use axum::http::request::Parts;
+use axum::middleware::Next;
+use axum::response::Response;
+use domain::dto;
+use jsonwebtoken::jwk::{AlgorithmParameters, JwkSet};
+use jsonwebtoken::{Algorithm, DecodingKey, Validation, decode, decode_header};
+use repository::role_errors::GetRoleError;
+use repository::role_repo::RoleRepo;
+use repository::user_errors::GetUserError;
+use repository::user_repo::UserRepo;
+use serde::Deserialize;
+use std::fmt;
+use thiserror::Error;
+
+/// Where the keys that sign bearer tokens come from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TokenKeySource {
+    /// A shared HMAC secret.
+    Secret(String),
+    /// A PEM encoded RSA public key file.
+    PublicKeyFile(String),
+    /// A JSON Web Key Set file; tokens pick their key with the `kid` header.
+    JwksFile(String),
+}
+
+/// Configuration for verifying bearer tokens.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthConfig {
+    pub keys: TokenKeySource,
+    /// When set, tokens must carry this `iss` claim.
+    pub issuer: Option<String>,
+    /// When set, tokens must carry this `aud` claim.
+    pub audience: Option<String>,
+}
+
+#[derive(Debug, Error)]
+pub enum AuthError {
+    #[error("Missing bearer token")]
+    MissingToken,
+
+    #[error("Invalid bearer token: {source}")]
+    InvalidToken { source: jsonwebtoken::errors::Error },
+
+    #[error("No signing key matches the bearer token")]
+    UnknownKey,
+
+    #[error("No user is registered for the bearer token")]
+    UnknownUser,
+
+    #[error("User has been deactivated")]
+    Deactivated,
+
+    #[error("Missing scope {0}")]
+    MissingScope(dto::Scope),
+
+    #[error(transparent)]
+    Lookup(#[from] GetUserError),
+
+    #[error(transparent)]
+    RoleLookup(#[from] GetRoleError),
+}
+
+impl From<jsonwebtoken::errors::Error> for AuthError {
+    fn from(source: jsonwebtoken::errors::Error) -> Self {
+        AuthError::InvalidToken { source }
+    }
+}
+
+/// The claims a token must carry to identify a user.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Claims {
+    pub sub: String,
+    pub iss: String,
+}
+
+struct VerificationKey {
+    kid: Option<String>,
+    key: DecodingKey,
+    algorithms: Vec<Algorithm>,
+}
+
+impl fmt::Debug for VerificationKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VerificationKey")
+            .field("kid", &self.kid)
+            .field("algorithms", &self.algorithms)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Checks token signatures and standard claims against the configured keys.
+#[derive(Debug)]
+pub struct TokenVerifier {
+    keys: Vec<VerificationKey>,
+    issuer: Option<String>,
+    audience: Option<String>,
+}
+
+const HMAC: [Algorithm; 3] = [Algorithm::HS256, Algorithm::HS384, Algorithm::HS512];
+const RSA: [Algorithm; 6] = [
+    Algorithm::RS256,
+    Algorithm::RS384,
+    Algorithm::RS512,
+    Algorithm::PS256,
+    Algorithm::PS384,
+    Algorithm::PS512,
+];
+const EC: [Algorithm; 2] = [Algorithm::ES256, Algorithm::ES384];
+
+impl TokenVerifier {
+    /// Loads the signing keys named by `config`.
+    pub fn load(config: &AuthConfig) -> anyhow::Result<Self> {
+        let keys = match &config.keys {
+            TokenKeySource::Secret(secret) => vec![VerificationKey {
+                kid: None,
+                key: DecodingKey::from_secret(secret.as_bytes()),
+                algorithms: HMAC.to_vec(),
+            }],
+            TokenKeySource::PublicKeyFile(path) => {
+                let pem = std::fs::read(path)
+                    .map_err(|e| anyhow::anyhow!("failed to read public key {path}: {e}"))?;
+                vec![VerificationKey {
+                    kid: None,
+                    key: DecodingKey::from_rsa_pem(&pem)
+                        .map_err(|e| anyhow::anyhow!("invalid public key {path}: {e}"))?,
+                    algorithms: RSA.to_vec(),
+                }]
+            }
+            TokenKeySource::JwksFile(path) => {
+                let json = std::fs::read_to_string(path)
+                    .map_err(|e| anyhow::anyhow!("failed to read JWKS {path}: {e}"))?;
+                let set: JwkSet = serde_json::from_str(&json)
+                    .map_err(|e| anyhow::anyhow!("invalid JWKS {path}: {e}"))?;
+                set.keys
+                    .iter()
+                    .map(|jwk| {
+                        let algorithms = match &jwk.algorithm {
+                            AlgorithmParameters::RSA(_) => RSA.to_vec(),
+                            AlgorithmParameters::EllipticCurve(_) => EC.to_vec(),
+                            AlgorithmParameters::OctetKey(_) => HMAC.to_vec(),
+                            AlgorithmParameters::OctetKeyPair(_) => vec![Algorithm::EdDSA],
+                        };
+                        Ok(VerificationKey {
+                            kid: jwk.common.key_id.clone(),
+                            key: DecodingKey::from_jwk(jwk)
+                                .map_err(|e| anyhow::anyhow!("invalid key in JWKS {path}: {e}"))?,
+                            algorithms,
+                        })
+                    })
+                    .collect::<anyhow::Result<Vec<_>>>()?
+            }
+        };
+        Ok(TokenVerifier {
+            keys,
+            issuer: config.issuer.clone(),
+            audience: config.audience.clone(),
+        })
+    }
+
+    /// Verifies the token's signature, expiry, issuer and audience and returns its claims.
+    pub fn verify(&self, token: &str) -> Result<Claims, AuthError> {
+        let header = decode_header(token)?;
+        let key = self
+            .keys
+            .iter()
+            .find(|k| {
+                (k.kid.is_none() || k.kid == header.kid) && k.algorithms.contains(&header.alg)
+            })
+            .ok_or(AuthError::UnknownKey)?;
+
+        let mut validation = Validation::new(header.alg);
+        validation.set_required_spec_claims(&["exp", "sub", "iss"]);
+        if let Some(issuer) = &self.issuer {
+            validation.set_issuer(&[issuer]);
+        }
+        match &self.audience {
+            Some(audience) => validation.set_audience(&[audience]),
+            None => validation.validate_aud = false,
+        }
+        Ok(decode::<Claims>(token, &key.key, &validation)?.claims)
+    }
+}
+
+/// The authenticated caller, placed in the request extensions by [authenticate].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CurrentUser {
+    pub user: dto::User,
+    /// Granted by the user's role; empty when they have none.
+    pub scopes: Vec<dto::Scope>,
+}
+
+impl CurrentUser {
+    pub fn has(&self, scope: dto::Scope) -> bool {
+        self.scopes.contains(&scope)
+    }
+
+    /// Fails with 403 naming `scope` when the caller lacks it.
+    pub fn require(&self, scope: dto::Scope) -> Result<(), AuthError> {
+        if self.has(scope) {
+            Ok(())
+        } else {
+            Err(AuthError::MissingScope(scope))
+        }
+    }
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for CurrentUser {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<CurrentUser>()
+            .cloned()
+            .ok_or_else(|| ApiError::from(AuthError::MissingToken))
+    }
+}
+
+fn bearer_token(request: &Request) -> Option<&str> {
+    let value = request.headers().get(AUTHORIZATION)?.to_str().ok()?;
+    let (scheme, token) = value.split_once(' ')?;
+    scheme
+        .eq_ignore_ascii_case("bearer")
+        .then(|| token.trim())
+        .filter(|x| !x.is_empty())
+}
+
+/// Rejects requests without a valid bearer token for a known user with 401.
+pub async fn authenticate<R: UserRepo + RoleRepo>(
+    State(state): State<AppState<R>>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let token = bearer_token(&request).ok_or(AuthError::MissingToken)?;
+    let claims = state.token_verifier.verify(token)?;
+    let user = state
+        .allocation_service
+        .get_user_by_external_id(&claims.sub, &claims.iss)
+        .await
+        .map_err(AuthError::from)?
+        .ok_or(AuthError::UnknownUser)?;
+    if !user.active {
+        return Err(AuthError::Deactivated.into());
+    }
+    let scopes = match user.role_id {
+        Some(role_id) => state
+            .allocation_service
+            .get_role(role_id)
+            .await
+            .map_err(AuthError::from)?
+            .map(|x| x.scopes)
+            .unwrap_or_default(),
+        None => Vec::new(),
+    };
+    request
+        .extensions_mut()
+        .insert(CurrentUser { user, scopes });
+    Ok(next.run(request).await)
+}
+
+/// Route layer rejecting callers without `scope` with 403. Layer it with
+/// `middleware::from_fn_with_state(scope, authorize)` inside the routes [authenticate] covers.
+pub async fn authorize(
+    State(scope): State<dto::Scope>,
+    user: CurrentUser,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    user.require(scope)?;
+    Ok(next.run(request).await)
+}
@@ -0,0 +1,174 @@
+use crate::http::api::{ApiError, ApiJson, ApiResponseBody, ApiSuccess, IfMatch, ProblemDetails};
+use crate::models::employee_assignment::*;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use repository::employee_assignment_repo::EmployeeAssignmentRepo;
+use service::allocation_service::AllocationRepo;
+use uuid::Uuid;
+
+use crate::http::AppState;
+use crate::http::auth::CurrentUser;
+
+#[utoipa::path(
+    post,
+    path = "/api/employees/{employee_id}/assignments",
+    tag = "employee assignments",
+    params(("employee_id" = Uuid, Path, description = "Employee id")),
+    request_body = CreateEmployeeAssignmentRequest,
+    responses(
+        (status = 201, description = "Employee assigned", body = ApiResponseBody<EmployeeAssignmentResponse>,
+            headers(("ETag" = String, description = "The assignment's version"))),
+        (status = 400, description = "Malformed id or body", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid bearer token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Missing allocations:write", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 409, description = "Employee is already assigned to the initiative", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid allocation, missing reference, over-allocation or an employee who left before the curve's year", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn create_employee_assignment<R: AllocationRepo>(
+    State(state): State<AppState<R>>,
+    user: CurrentUser,
+    Path(employee_id): Path<String>,
+    ApiJson(body): ApiJson<CreateEmployeeAssignmentRequest>,
+) -> Result<ApiSuccess<EmployeeAssignmentResponse>, ApiError> {
+    let employee_id = Uuid::parse_str(&employee_id).map_err(ApiError::from)?;
+    let domain_req = body.to_dto(employee_id);
+    state
+        .allocation_service
+        .assign(user.user.id, &domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref ri| ApiSuccess::new(StatusCode::CREATED, ri.into()).with_version(ri.version))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/employees/{employee_id}/assignments/{id}",
+    tag = "employee assignments",
+    params(
+        ("employee_id" = Uuid, Path, description = "Employee id"),
+        ("id" = Uuid, Path, description = "Employee assignment id"),
+        ("If-Match" = String, Header, description = "The ETag of the assignment being replaced"),
+    ),
+    request_body = UpdateEmployeeAssignmentRequest,
+    responses(
+        (status = 200, description = "Assignment updated", body = ApiResponseBody<EmployeeAssignmentResponse>,
+            headers(("ETag" = String, description = "The assignment's new version"))),
+        (status = 400, description = "Malformed id, body or If-Match", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid bearer token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Missing allocations:write", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Employee assignment not found", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 409, description = "Employee is already assigned to the initiative", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 412, description = "The assignment changed since the ETag in If-Match", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid allocation, missing reference, over-allocation or an employee who left before the curve's year", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 428, description = "If-Match is missing", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn update_employee_assignment<R: AllocationRepo>(
+    State(state): State<AppState<R>>,
+    user: CurrentUser,
+    Path((employee_id, id)): Path<(String, String)>,
+    IfMatch(version): IfMatch,
+    ApiJson(body): ApiJson<UpdateEmployeeAssignmentRequest>,
+) -> Result<ApiSuccess<EmployeeAssignmentResponse>, ApiError> {
+    let employee_id = Uuid::parse_str(&employee_id).map_err(ApiError::from)?;
+    let id = Uuid::parse_str(&id).map_err(ApiError::from)?;
+    let domain_req = body.to_dto(employee_id, id, version);
+    state
+        .allocation_service
+        .reassign(user.user.id, &domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref ri| ApiSuccess::new(StatusCode::OK, ri.into()).with_version(ri.version))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/employees/{employee_id}/assignments/{id}",
+    tag = "employee assignments",
+    params(
+        ("employee_id" = Uuid, Path, description = "Employee id"),
+        ("id" = Uuid, Path, description = "Employee assignment id"),
+        ("If-Match" = String, Header, description = "The ETag of the assignment being removed"),
+    ),
+    responses(
+        (status = 200, description = "Employee unassigned", body = ApiResponseBody<DeleteEmployeeAssignmentResponse>),
+        (status = 400, description = "Malformed id or If-Match", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid bearer token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Missing allocations:write", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Employee assignment not found", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 412, description = "The assignment changed since the ETag in If-Match", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 428, description = "If-Match is missing", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn delete_employee_assignment<EAR: EmployeeAssignmentRepo>(
+    State(state): State<AppState<EAR>>,
+    user: CurrentUser,
+    Path((employee_id, id)): Path<(String, String)>,
+    IfMatch(version): IfMatch,
+) -> Result<ApiSuccess<DeleteEmployeeAssignmentResponse>, ApiError> {
+    let employee_id = Uuid::parse_str(&employee_id).map_err(ApiError::from)?;
+    let id = Uuid::parse_str(&id).map_err(ApiError::from)?;
+    state
+        .allocation_service
+        .unassign(user.user.id, employee_id, id, version)
+        .await
+        .map_err(ApiError::from)
+        .map(|()| ApiSuccess::new(StatusCode::OK, DeleteEmployeeAssignmentResponse {}))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/employees/{employee_id}/assignments",
+    tag = "employee assignments",
+    params(("employee_id" = Uuid, Path, description = "Employee id")),
+    responses(
+        (status = 200, description = "The employee's assignments", body = ApiResponseBody<Vec<EmployeeAssignmentResponse>>),
+        (status = 400, description = "Malformed id", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid bearer token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Missing allocations:read", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn get_employee_assignments<EAR: EmployeeAssignmentRepo>(
+    State(state): State<AppState<EAR>>,
+    Path(employee_id): Path<String>,
+) -> Result<ApiSuccess<Vec<EmployeeAssignmentResponse>>, ApiError> {
+    let employee_id = Uuid::parse_str(&employee_id).map_err(ApiError::from)?;
+    state
+        .allocation_service
+        .get_employee_assignments(employee_id)
+        .await
+        .map_err(ApiError::from)
+        .map(|items| {
+            let response_data = items.iter().map(EmployeeAssignmentResponse::from).collect();
+            ApiSuccess::new(StatusCode::OK, response_data)
+        })
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/initiatives/{initiative_id}/staff",
+    tag = "employee assignments",
+    params(("initiative_id" = Uuid, Path, description = "Initiative id")),
+    responses(
+        (status = 200, description = "Everyone assigned to the initiative", body = ApiResponseBody<Vec<EmployeeAssignmentResponse>>),
+        (status = 400, description = "Malformed id", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid bearer token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Missing allocations:read", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn get_initiative_staff<EAR: EmployeeAssignmentRepo>(
+    State(state): State<AppState<EAR>>,
+    Path(initiative_id): Path<String>,
+) -> Result<ApiSuccess<Vec<EmployeeAssignmentResponse>>, ApiError> {
+    let initiative_id = Uuid::parse_str(&initiative_id).map_err(ApiError::from)?;
+    state
+        .allocation_service
+        .get_initiative_staff(initiative_id)
+        .await
+        .map_err(ApiError::from)
+        .map(|items| {
+            let response_data = items.iter().map(EmployeeAssignmentResponse::from).collect();
+            ApiSuccess::new(StatusCode::OK, response_data)
+        })
+}
@@ -1,48 +1,296 @@
-use crate::http::api::{ApiError, ApiSuccess};
+use crate::http::api::{
+    ApiError, ApiJson, ApiQuery, ApiResponseBody, ApiSuccess, ErrorCode, IfMatch, ProblemDetails,
+};
+use crate::http::auth::CurrentUser;
 use crate::models::employee::*;
-use axum::Json;
+use axum::body::Bytes;
 use axum::extract::{Path, State};
 use axum::http::StatusCode;
+use chrono::Utc;
+use domain::dto::{self, Scope};
 use repository::employee_repo::EmployeeRepo;
+use service::allocation_errors::ImportEmployeesError;
+use service::allocation_service::DEFAULT_PAGE_SIZE;
+use service::validation::RowError;
 use uuid::Uuid;
 
 use crate::http::AppState;
 
+#[utoipa::path(
+    post,
+    path = "/api/employees",
+    tag = "employees",
+    request_body = CreateEmployeeRequest,
+    responses(
+        (status = 201, description = "Employee created", body = ApiResponseBody<CreateEmployeeResponse>,
+            headers(("ETag" = String, description = "The employee's version"))),
+        (status = 400, description = "Malformed body", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid bearer token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Missing employees:write or salary:write", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 409, description = "An employee with the email already exists", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Missing, mistyped or invalid fields, each listed in invalid_params", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
 pub async fn create_employee<RR: EmployeeRepo>(
     State(state): State<AppState<RR>>,
-    Json(body): Json<CreateEmployeeRequest>,
+    user: CurrentUser,
+    ApiJson(body): ApiJson<CreateEmployeeRequest>,
 ) -> Result<ApiSuccess<CreateEmployeeResponse>, ApiError> {
+    // Every new employee starts with a salary, so creating one sets compensation.
+    user.require(Scope::SalaryWrite)?;
     let domain_req = (&body).into();
     state
-        .employee_repo
-        .create(&domain_req)
+        .allocation_service
+        .create_employee(user.user.id, &domain_req)
         .await
         .map_err(ApiError::from)
-        .map(|ref ri| ApiSuccess::new(StatusCode::CREATED, ri.into()))
+        .map(|ref ri| {
+            ApiSuccess::new(
+                StatusCode::CREATED,
+                CreateEmployeeResponse::from(ri).redacted(user.has(Scope::SalaryRead)),
+            )
+            .with_version(ri.version)
+        })
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/employees/import",
+    tag = "employees",
+    params(ImportEmployeesQuery),
+    request_body(content = String, content_type = "text/csv",
+        description = "A `first_name,last_name,email,salary` header, then one employee per line"),
+    responses(
+        (status = 201, description = "Every row was created", body = ApiResponseBody<ImportEmployeesResponse>),
+        (status = 200, description = "Dry run: every row would be created", body = ApiResponseBody<ImportEmployeesResponse>),
+        (status = 400, description = "Malformed query string, unreadable file or wrong header", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid bearer token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Missing employees:write or salary:write", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Nothing was created; every failing field is listed in invalid_params with its line", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+/// Creates every employee of a CSV file in one transaction, or nobody if any row fails.
+pub async fn import_employees<RR: EmployeeRepo>(
+    State(state): State<AppState<RR>>,
+    user: CurrentUser,
+    ApiQuery(query): ApiQuery<ImportEmployeesQuery>,
+    body: Bytes,
+) -> Result<ApiSuccess<ImportEmployeesResponse>, ApiError> {
+    user.require(Scope::SalaryWrite)?;
+    let rows = parse_employees_csv(&body)
+        .map_err(|e| ApiError::BadRequest(ErrorCode::MalformedRequest, e))?;
+    let mut errors = Vec::new();
+    let mut lines = Vec::new();
+    let mut employees = Vec::new();
+    for row in rows {
+        match row.employee {
+            Ok(employee) => {
+                lines.push(row.line);
+                employees.push(employee);
+            }
+            Err(invalid) => errors.extend(invalid.into_iter().map(|error| RowError {
+                row: row.line,
+                error,
+            })),
+        }
+    }
+    // Rows that parsed are still validated so the response lists every problem at once.
+    let dry_run = query.dry_run || !errors.is_empty();
+    match state
+        .allocation_service
+        .import_employees(user.user.id, &employees, dry_run)
+        .await
+    {
+        Ok(created) if errors.is_empty() => {
+            let status = if query.dry_run {
+                StatusCode::OK
+            } else {
+                StatusCode::CREATED
+            };
+            let show_salary = user.has(Scope::SalaryRead);
+            return Ok(ApiSuccess::new(
+                status,
+                ImportEmployeesResponse {
+                    dry_run: query.dry_run,
+                    count: employees.len(),
+                    employees: created
+                        .iter()
+                        .map(|x| {
+                            ImportedEmployeeResponse::new(x, query.dry_run).redacted(show_salary)
+                        })
+                        .collect(),
+                },
+            ));
+        }
+        Ok(_) => {}
+        Err(ImportEmployeesError::Invalid { errors: invalid }) => {
+            errors.extend(invalid.into_iter().map(|x| RowError {
+                row: lines[x.row],
+                ..x
+            }));
+        }
+        Err(e) => return Err(ApiError::from(e)),
+    }
+    errors.sort_by_key(|x| x.row);
+    Err(ApiError::InvalidRows(errors))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/employees/{id}",
+    tag = "employees",
+    params(
+        ("id" = Uuid, Path, description = "Employee id"),
+        ("If-Match" = String, Header, description = "The ETag of the employee being replaced"),
+    ),
+    request_body = UpdateEmployeeRequest,
+    responses(
+        (status = 200, description = "Employee updated", body = ApiResponseBody<UpdateEmployeeResponse>,
+            headers(("ETag" = String, description = "The employee's new version"))),
+        (status = 400, description = "Malformed id, body or If-Match", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid bearer token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Missing employees:write, or salary:write when setting a salary", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Employee not found", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 409, description = "Another employee already uses the email", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 412, description = "The employee changed since the ETag in If-Match", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Missing, mistyped or invalid fields, each listed in invalid_params", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 428, description = "If-Match is missing", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
 pub async fn update_employee<RR: EmployeeRepo>(
     State(state): State<AppState<RR>>,
-    Json(body): Json<UpdateEmployeeRequest>,
+    user: CurrentUser,
+    Path(id): Path<String>,
+    IfMatch(version): IfMatch,
+    ApiJson(body): ApiJson<UpdateEmployeeRequest>,
 ) -> Result<ApiSuccess<UpdateEmployeeResponse>, ApiError> {
-    let domain_req = (&body).into();
+    let employee_id = Uuid::parse_str(&id).map_err(ApiError::from)?;
+    check_body_id(employee_id, body.id)?;
+    // Callers without salary:write omit the salary, so they can neither change nor probe it.
+    let domain_req = match &body.salary {
+        Some(salary) => {
+            user.require(Scope::SalaryWrite)?;
+            body.to_dto(employee_id, version, salary)
+        }
+        None => {
+            let current = state
+                .allocation_service
+                .get_employee(employee_id)
+                .await
+                .map_err(ApiError::from)?
+                .ok_or_else(|| ApiError::NotFound("Employee item not found".to_string()))?;
+            body.to_dto(employee_id, version, &current.salary)
+        }
+    };
     state
-        .employee_repo
-        .update(&domain_req)
+        .allocation_service
+        .update_employee(user.user.id, &domain_req)
         .await
         .map_err(ApiError::from)
-        .map(|ref pet| ApiSuccess::new(StatusCode::CREATED, pet.into()))
+        .map(|ref pet| {
+            ApiSuccess::new(
+                StatusCode::OK,
+                UpdateEmployeeResponse::from(pet).redacted(user.has(Scope::SalaryRead)),
+            )
+            .with_version(pet.version)
+        })
 }
 
+#[utoipa::path(
+    patch,
+    path = "/api/employees/{id}",
+    tag = "employees",
+    params(
+        ("id" = Uuid, Path, description = "Employee id"),
+        ("If-Match" = String, Header, description = "The ETag of the employee being patched"),
+    ),
+    request_body(content = PatchEmployeeRequest, content_type = "application/merge-patch+json"),
+    responses(
+        (status = 200, description = "Employee updated", body = ApiResponseBody<UpdateEmployeeResponse>,
+            headers(("ETag" = String, description = "The employee's new version"))),
+        (status = 400, description = "Malformed id, body or If-Match", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid bearer token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Missing employees:write, or salary:write when patching the salary", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Employee not found", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 409, description = "Another employee already uses the email", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 412, description = "The employee changed since the ETag in If-Match", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Unknown, null, mistyped or invalid fields", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 428, description = "If-Match is missing", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn patch_employee<RR: EmployeeRepo>(
+    State(state): State<AppState<RR>>,
+    user: CurrentUser,
+    Path(id): Path<String>,
+    IfMatch(version): IfMatch,
+    ApiJson(body): ApiJson<PatchEmployeeRequest>,
+) -> Result<ApiSuccess<UpdateEmployeeResponse>, ApiError> {
+    let employee_id = Uuid::parse_str(&id).map_err(ApiError::from)?;
+    check_body_id(employee_id, body.id.flatten())?;
+    if body.sets_salary() {
+        user.require(Scope::SalaryWrite)?;
+    }
+    let current = state
+        .allocation_service
+        .get_employee(employee_id)
+        .await
+        .map_err(ApiError::from)?
+        .ok_or_else(|| ApiError::NotFound(format!("Employee with id {} not found", employee_id)))?;
+    // The patch applies to what was just read, but only lands if that is what the client saw.
+    let domain_req = dto::Employee {
+        version,
+        ..body.apply(&current).map_err(ApiError::InvalidFields)?
+    };
+    state
+        .allocation_service
+        .update_employee(user.user.id, &domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref employee| {
+            ApiSuccess::new(
+                StatusCode::OK,
+                UpdateEmployeeResponse::from(employee).redacted(user.has(Scope::SalaryRead)),
+            )
+            .with_version(employee.version)
+        })
+}
+
+/// Bodies may repeat the employee id, but the path decides which employee changes.
+fn check_body_id(path_id: Uuid, body_id: Option<Uuid>) -> Result<(), ApiError> {
+    match body_id {
+        Some(body_id) if body_id != path_id => Err(ApiError::UnprocessableEntity(
+            ErrorCode::ValidationFailed,
+            format!("Body id {} does not match path id {}", body_id, path_id),
+        )),
+        _ => Ok(()),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/employees/{id}",
+    tag = "employees",
+    params(("id" = Uuid, Path, description = "Employee id")),
+    responses(
+        (status = 200, description = "The employee; salary only with salary:read", body = ApiResponseBody<GetEmployeeResponse>,
+            headers(("ETag" = String, description = "The employee's version, for If-Match"))),
+        (status = 400, description = "Malformed id", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid bearer token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Missing employees:read", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Employee not found", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
 pub async fn get_employee<RR: EmployeeRepo>(
     State(state): State<AppState<RR>>,
+    user: CurrentUser,
     Path(id): Path<String>,
 ) -> Result<ApiSuccess<GetEmployeeResponse>, ApiError> {
     let employee_id = uuid::Uuid::parse_str(&id).map_err(ApiError::from)?;
-    match state.employee_repo.get(employee_id).await {
+    match state.allocation_service.get_employee(employee_id).await {
         Ok(Some(ri)) => {
-            let response_data = GetEmployeeResponse::from(&ri);
-            Ok(ApiSuccess::new(StatusCode::OK, response_data))
+            let response_data =
+                GetEmployeeResponse::from(&ri).redacted(user.has(Scope::SalaryRead));
+            Ok(ApiSuccess::new(StatusCode::OK, response_data).with_version(ri.version))
         }
         Ok(None) => {
             // Handle not found case
@@ -51,33 +299,206 @@ pub async fn get_employee<RR: EmployeeRepo>(
         Err(e) => Err(ApiError::from(e)),
     }
 }
+#[utoipa::path(
+    delete,
+    path = "/api/employees/{id}",
+    tag = "employees",
+    params(
+        ("id" = Uuid, Path, description = "Employee id"),
+        ("If-Match" = String, Header, description = "The ETag of the employee who is leaving"),
+        TerminateEmployeeQuery,
+    ),
+    responses(
+        (status = 200, description = "Employee terminated; the row and its allocations are kept", body = ApiResponseBody<GetEmployeeResponse>,
+            headers(("ETag" = String, description = "The employee's new version"))),
+        (status = 400, description = "Malformed id, date or If-Match", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid bearer token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Missing employees:write", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Employee not found", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 412, description = "The employee changed since the ETag in If-Match", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 428, description = "If-Match is missing", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+/// Records that the employee left instead of deleting them, so reports keep their history.
 pub async fn delete_employee<RR: EmployeeRepo>(
     State(state): State<AppState<RR>>,
+    user: CurrentUser,
     Path(id): Path<String>,
-) -> Result<ApiSuccess<DeleteEmployeeResponse>, ApiError> {
-    // Handle the Result<Option<Pet>, GetPetError>
-    let rid = Uuid::parse_str(&id).map_err(ApiError::from)?;
-    match state.employee_repo.delete(rid).await {
-        Ok(()) => {
-            let response_data = DeleteEmployeeResponse {};
-            Ok(ApiSuccess::new(StatusCode::OK, response_data))
-        }
-        Err(e) => Err(ApiError::from(e)),
-    }
+    IfMatch(version): IfMatch,
+    ApiQuery(query): ApiQuery<TerminateEmployeeQuery>,
+) -> Result<ApiSuccess<GetEmployeeResponse>, ApiError> {
+    let employee_id = Uuid::parse_str(&id).map_err(ApiError::from)?;
+    let terminated_on = query
+        .terminated_on
+        .unwrap_or_else(|| Utc::now().date_naive());
+    state
+        .allocation_service
+        .terminate_employee(user.user.id, employee_id, version, terminated_on)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref employee| {
+            ApiSuccess::new(
+                StatusCode::OK,
+                GetEmployeeResponse::from(employee).redacted(user.has(Scope::SalaryRead)),
+            )
+            .with_version(employee.version)
+        })
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/employees/{id}/restore",
+    tag = "employees",
+    params(
+        ("id" = Uuid, Path, description = "Employee id"),
+        ("If-Match" = String, Header, description = "The ETag of the terminated employee"),
+    ),
+    responses(
+        (status = 200, description = "Employee is current again", body = ApiResponseBody<GetEmployeeResponse>,
+            headers(("ETag" = String, description = "The employee's new version"))),
+        (status = 400, description = "Malformed id or If-Match", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid bearer token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Missing employees:write", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Employee not found", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 409, description = "The employee has not left", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 412, description = "The employee changed since the ETag in If-Match", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 428, description = "If-Match is missing", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn restore_employee<RR: EmployeeRepo>(
+    State(state): State<AppState<RR>>,
+    user: CurrentUser,
+    Path(id): Path<String>,
+    IfMatch(version): IfMatch,
+) -> Result<ApiSuccess<GetEmployeeResponse>, ApiError> {
+    let employee_id = Uuid::parse_str(&id).map_err(ApiError::from)?;
+    state
+        .allocation_service
+        .restore_employee(user.user.id, employee_id, version)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref employee| {
+            ApiSuccess::new(
+                StatusCode::OK,
+                GetEmployeeResponse::from(employee).redacted(user.has(Scope::SalaryRead)),
+            )
+            .with_version(employee.version)
+        })
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/employees",
+    tag = "employees",
+    params(GetEmployeesQuery),
+    responses(
+        (status = 200, description = "One page of employees", body = ApiResponseBody<GetEmployeesResponse>),
+        (status = 400, description = "Malformed query string", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid bearer token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Missing employees:read, or salary:read when filtering or sorting by salary", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "The cursor no longer refers to an employee", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
 pub async fn get_employees<RR: EmployeeRepo>(
     State(state): State<AppState<RR>>,
-) -> Result<ApiSuccess<Vec<GetEmployeeResponse>>, ApiError> {
-    // Handle the Result<Option<Pet>, GetPetError>
-    match state.employee_repo.get_all().await {
-        Ok(items) => {
-            let response_data = items
-                .into_iter()
-                .map(|x| GetEmployeeResponse::from(&x))
-                .collect();
-            Ok(ApiSuccess::new(StatusCode::OK, response_data))
-        }
+    user: CurrentUser,
+    ApiQuery(query): ApiQuery<GetEmployeesQuery>,
+) -> Result<ApiSuccess<GetEmployeesResponse>, ApiError> {
+    if query.uses_salary() {
+        user.require(Scope::SalaryRead)?;
+    }
+    match state
+        .allocation_service
+        .get_employees(&query.to_dto(DEFAULT_PAGE_SIZE))
+        .await
+    {
+        Ok(page) => Ok(ApiSuccess::new(
+            StatusCode::OK,
+            GetEmployeesResponse::new(&page, user.has(Scope::SalaryRead)),
+        )),
         Err(e) => Err(ApiError::from(e)),
     }
 }
+
+#[utoipa::path(
+    get,
+    path = "/api/employees/{id}/salaries",
+    tag = "employees",
+    params(("id" = Uuid, Path, description = "Employee id")),
+    responses(
+        (status = 200, description = "Every rate of the employee, oldest first", body = ApiResponseBody<Vec<GetSalaryResponse>>),
+        (status = 400, description = "Malformed id", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid bearer token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Missing employees:read or salary:read", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Employee not found", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn get_salaries<RR: EmployeeRepo>(
+    State(state): State<AppState<RR>>,
+    user: CurrentUser,
+    Path(id): Path<String>,
+) -> Result<ApiSuccess<Vec<GetSalaryResponse>>, ApiError> {
+    user.require(Scope::SalaryRead)?;
+    let employee_id = Uuid::parse_str(&id).map_err(ApiError::from)?;
+    let salaries = state
+        .allocation_service
+        .get_salaries(employee_id)
+        .await
+        .map_err(ApiError::from)?;
+    // Every employee starts with a rate, so an empty history means there is no such employee.
+    if salaries.is_empty() {
+        return Err(ApiError::NotFound(format!(
+            "Employee with id {} not found",
+            employee_id
+        )));
+    }
+    Ok(ApiSuccess::new(
+        StatusCode::OK,
+        salaries.iter().map(GetSalaryResponse::from).collect(),
+    ))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/employees/{id}/salaries",
+    tag = "employees",
+    params(
+        ("id" = Uuid, Path, description = "Employee id"),
+        ("If-Match" = String, Header, description = "The ETag of the employee being paid"),
+    ),
+    request_body = CreateSalaryRequest,
+    responses(
+        (status = 200, description = "Rate recorded; the employee with the salary in force today", body = ApiResponseBody<GetEmployeeResponse>,
+            headers(("ETag" = String, description = "The employee's new version"))),
+        (status = 400, description = "Malformed id, body or If-Match", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid bearer token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Missing employees:write or salary:write", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Employee not found", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 412, description = "The employee changed since the ETag in If-Match", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid salary", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 428, description = "If-Match is missing", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+/// Adds a rate to the employee's salary history, replacing one that starts the same day.
+pub async fn create_salary<RR: EmployeeRepo>(
+    State(state): State<AppState<RR>>,
+    user: CurrentUser,
+    Path(id): Path<String>,
+    IfMatch(version): IfMatch,
+    ApiJson(body): ApiJson<CreateSalaryRequest>,
+) -> Result<ApiSuccess<GetEmployeeResponse>, ApiError> {
+    user.require(Scope::SalaryWrite)?;
+    let employee_id = Uuid::parse_str(&id).map_err(ApiError::from)?;
+    state
+        .allocation_service
+        .change_salary(user.user.id, version, &body.to_dto(employee_id))
+        .await
+        .map_err(ApiError::from)
+        .map(|ref employee| {
+            ApiSuccess::new(
+                StatusCode::OK,
+                GetEmployeeResponse::from(employee).redacted(user.has(Scope::SalaryRead)),
+            )
+            .with_version(employee.version)
+        })
+}
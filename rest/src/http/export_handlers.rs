@@ -0,0 +1,212 @@
+use crate::http::api::{ApiError, ApiQuery, ProblemDetails};
+use crate::http::auth::CurrentUser;
+use crate::models::export::*;
+use axum::BoxError;
+use axum::body::{Body, Bytes};
+use axum::extract::State;
+use axum::http::HeaderMap;
+use axum::http::header::{ACCEPT, CONTENT_DISPOSITION, CONTENT_TYPE};
+use axum::response::{IntoResponse, Response};
+use domain::dto::{self, Scope};
+use futures_util::{Stream, StreamExt, TryStreamExt, stream};
+use repository::employee_errors::GetEmployeesError;
+use repository::employee_repo::EmployeeRepo;
+use repository::initiative_repo::InitiativeRepo;
+use service::allocation_errors::GetAllocationPlanError;
+use service::allocation_service::{AllocationRepo, AllocationService, MAX_PAGE_SIZE};
+use uuid::Uuid;
+
+use crate::http::AppState;
+
+/// Picks the explicit `format` when given, otherwise XLSX if the client asks for a workbook.
+fn negotiate(format: Option<ExportFormat>, headers: &HeaderMap) -> ExportFormat {
+    format.unwrap_or_else(|| {
+        let accept = headers
+            .get(ACCEPT)
+            .and_then(|x| x.to_str().ok())
+            .unwrap_or_default();
+        if accept.contains(XLSX_CONTENT_TYPE) {
+            ExportFormat::Xlsx
+        } else {
+            ExportFormat::Csv
+        }
+    })
+}
+
+/// Sends `body` as a download named `name` with the format's extension.
+fn attachment(format: ExportFormat, name: &str, body: Body) -> Response {
+    (
+        [
+            (CONTENT_TYPE, format.content_type().to_string()),
+            (
+                CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}.{}\"", name, format.extension()),
+            ),
+        ],
+        body,
+    )
+        .into_response()
+}
+
+fn render_error(e: impl std::fmt::Display) -> ApiError {
+    ApiError::InternalServerError(format!("Unable to render export {}", e))
+}
+
+/// The pages after `next`, each read from the repository only once the previous one was sent.
+fn remaining_pages<RR: EmployeeRepo>(
+    service: AllocationService<RR>,
+    query: dto::EmployeeQuery,
+    next: Option<Uuid>,
+) -> impl Stream<Item = Result<Vec<dto::Employee>, GetEmployeesError>> {
+    stream::try_unfold(next, move |next| {
+        let service = service.clone();
+        let query = query.clone();
+        async move {
+            let Some(after) = next else {
+                return Ok(None);
+            };
+            let page = service
+                .get_employees(&dto::EmployeeQuery {
+                    after: Some(after),
+                    ..query
+                })
+                .await?;
+            Ok(Some((page.items, page.next)))
+        }
+    })
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/employees/export",
+    tag = "employees",
+    params(EmployeeExportQuery),
+    responses(
+        (status = 200, description = "Every employee ordered by name; the salary column only with salary:read", content(
+            (String = "text/csv"),
+            (Vec<u8> = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet")
+        ), headers(("Content-Disposition" = String, description = "Suggested file name"))),
+        (status = 400, description = "Malformed query string", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid bearer token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Missing employees:read", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn export_employees<RR: EmployeeRepo>(
+    State(state): State<AppState<RR>>,
+    user: CurrentUser,
+    ApiQuery(query): ApiQuery<EmployeeExportQuery>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let show_salary = user.has(Scope::SalaryRead);
+    let format = negotiate(query.format, &headers);
+    let columns = employee_columns(show_salary);
+    let query = dto::EmployeeQuery {
+        sort: dto::EmployeeSort::Name,
+        include_terminated: query.include_terminated,
+        limit: MAX_PAGE_SIZE,
+        ..Default::default()
+    };
+    // The first page is read up front so a failing query still gets a problem response.
+    let first = state
+        .allocation_service
+        .get_employees(&query)
+        .await
+        .map_err(ApiError::from)?;
+    let rest = remaining_pages(state.allocation_service.clone(), query, first.next);
+    match format {
+        ExportFormat::Csv => {
+            let head = csv_chunk(
+                Some(&columns),
+                first.items.iter().map(|x| employee_row(x, show_salary)),
+            )
+            .map_err(render_error)?;
+            let rest = rest
+                .map_err(BoxError::from)
+                .and_then(move |page| async move {
+                    csv_chunk(None, page.iter().map(|x| employee_row(x, show_salary)))
+                        .map(Bytes::from)
+                        .map_err(BoxError::from)
+                });
+            let body = stream::once(async { Ok(Bytes::from(head)) }).chain(rest);
+            Ok(attachment(format, "employees", Body::from_stream(body)))
+        }
+        ExportFormat::Xlsx => {
+            let rest: Vec<Vec<dto::Employee>> = rest.try_collect().await?;
+            let rows = first
+                .items
+                .iter()
+                .chain(rest.iter().flatten())
+                .map(|x| employee_row(x, show_salary));
+            let body = xlsx_workbook("Employees", &columns, rows).map_err(render_error)?;
+            Ok(attachment(format, "employees", Body::from(body)))
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/allocations/export",
+    tag = "assignments",
+    params(AllocationExportQuery),
+    responses(
+        (status = 200, description = "Effective monthly allocation per employee and initiative, ordered by email and initiative", content(
+            (String = "text/csv"),
+            (Vec<u8> = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet")
+        ), headers(("Content-Disposition" = String, description = "Suggested file name"))),
+        (status = 400, description = "Malformed query string", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid bearer token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Missing allocations:read", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn export_allocations<R: AllocationRepo + InitiativeRepo>(
+    State(state): State<AppState<R>>,
+    ApiQuery(query): ApiQuery<AllocationExportQuery>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let format = negotiate(query.format, &headers);
+    let name = format!("allocations-{}", query.year);
+    let year = query.year;
+    let service = state.allocation_service.clone();
+    let employees = dto::EmployeeQuery {
+        sort: dto::EmployeeSort::Email,
+        include_terminated: true,
+        limit: MAX_PAGE_SIZE,
+        ..Default::default()
+    };
+    // As with employees, the first page is read up front so a failing query still gets a
+    // problem response; the plan of every later page is only read once the previous was sent.
+    let first = service
+        .get_employees(&employees)
+        .await
+        .map_err(ApiError::from)?;
+    let head = service
+        .allocation_plan(year, &first.items)
+        .await
+        .map_err(ApiError::from)?;
+    let rest = remaining_pages(service.clone(), employees, first.next)
+        .map_err(GetAllocationPlanError::from)
+        .and_then(move |page| {
+            let service = service.clone();
+            async move { service.allocation_plan(year, &page).await }
+        });
+    match format {
+        ExportFormat::Csv => {
+            let head = csv_chunk(Some(&allocation_columns()), head.iter().map(allocation_row))
+                .map_err(render_error)?;
+            let rest = rest.map_err(BoxError::from).and_then(|page| async move {
+                csv_chunk(None, page.iter().map(allocation_row))
+                    .map(Bytes::from)
+                    .map_err(BoxError::from)
+            });
+            let body = stream::once(async { Ok(Bytes::from(head)) }).chain(rest);
+            Ok(attachment(format, &name, Body::from_stream(body)))
+        }
+        ExportFormat::Xlsx => {
+            let rest: Vec<Vec<dto::PlannedAllocation>> = rest.try_collect().await?;
+            let rows = head.iter().chain(rest.iter().flatten()).map(allocation_row);
+            let body =
+                xlsx_workbook("Allocations", &allocation_columns(), rows).map_err(render_error)?;
+            Ok(attachment(format, &name, Body::from(body)))
+        }
+    }
+}
@@ -0,0 +1,161 @@
+use crate::http::api::{ApiError, ApiJson, ApiResponseBody, ApiSuccess, IfMatch, ProblemDetails};
+use crate::models::initiative::*;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use repository::initiative_repo::InitiativeRepo;
+use uuid::Uuid;
+
+use crate::http::AppState;
+use crate::http::auth::CurrentUser;
+
+#[utoipa::path(
+    post,
+    path = "/api/initiatives",
+    tag = "initiatives",
+    request_body = CreateInitiativeRequest,
+    responses(
+        (status = 201, description = "Initiative created", body = ApiResponseBody<CreateInitiativeResponse>,
+            headers(("ETag" = String, description = "The initiative's version"))),
+        (status = 400, description = "Malformed body", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid bearer token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Missing allocations:write", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Body has missing or mistyped fields", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn create_initiative<IR: InitiativeRepo>(
+    State(state): State<AppState<IR>>,
+    user: CurrentUser,
+    ApiJson(body): ApiJson<CreateInitiativeRequest>,
+) -> Result<ApiSuccess<CreateInitiativeResponse>, ApiError> {
+    let domain_req = (&body).into();
+    state
+        .allocation_service
+        .create_initiative(user.user.id, &domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref ri| ApiSuccess::new(StatusCode::CREATED, ri.into()).with_version(ri.version))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/initiatives/{id}",
+    tag = "initiatives",
+    params(
+        ("id" = Uuid, Path, description = "Initiative id"),
+        ("If-Match" = String, Header, description = "The ETag of the initiative being replaced"),
+    ),
+    request_body = UpdateInitiativeRequest,
+    responses(
+        (status = 200, description = "Initiative updated", body = ApiResponseBody<UpdateInitiativeResponse>,
+            headers(("ETag" = String, description = "The initiative's new version"))),
+        (status = 400, description = "Malformed id, body or If-Match", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid bearer token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Missing allocations:write", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Initiative not found", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 412, description = "The initiative changed since the ETag in If-Match", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Body has missing or mistyped fields", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 428, description = "If-Match is missing", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn update_initiative<IR: InitiativeRepo>(
+    State(state): State<AppState<IR>>,
+    user: CurrentUser,
+    Path(id): Path<String>,
+    IfMatch(version): IfMatch,
+    ApiJson(body): ApiJson<UpdateInitiativeRequest>,
+) -> Result<ApiSuccess<UpdateInitiativeResponse>, ApiError> {
+    let initiative_id = Uuid::parse_str(&id).map_err(ApiError::from)?;
+    let domain_req = body.to_dto(initiative_id, version);
+    state
+        .allocation_service
+        .update_initiative(user.user.id, &domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref ri| ApiSuccess::new(StatusCode::OK, ri.into()).with_version(ri.version))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/initiatives/{id}",
+    tag = "initiatives",
+    params(("id" = Uuid, Path, description = "Initiative id")),
+    responses(
+        (status = 200, description = "The initiative", body = ApiResponseBody<GetInitiativeResponse>,
+            headers(("ETag" = String, description = "The initiative's version, for If-Match"))),
+        (status = 400, description = "Malformed id", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid bearer token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Missing allocations:read", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Initiative not found", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn get_initiative<IR: InitiativeRepo>(
+    State(state): State<AppState<IR>>,
+    Path(id): Path<String>,
+) -> Result<ApiSuccess<GetInitiativeResponse>, ApiError> {
+    let initiative_id = Uuid::parse_str(&id).map_err(ApiError::from)?;
+    match state.allocation_service.get_initiative(initiative_id).await {
+        Ok(Some(ri)) => Ok(
+            ApiSuccess::new(StatusCode::OK, GetInitiativeResponse::from(&ri))
+                .with_version(ri.version),
+        ),
+        Ok(None) => Err(ApiError::NotFound("Initiative not found".to_string())),
+        Err(e) => Err(ApiError::from(e)),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/initiatives/{id}",
+    tag = "initiatives",
+    params(
+        ("id" = Uuid, Path, description = "Initiative id"),
+        ("If-Match" = String, Header, description = "The ETag of the initiative being deleted"),
+    ),
+    responses(
+        (status = 200, description = "Initiative deleted", body = ApiResponseBody<DeleteInitiativeResponse>),
+        (status = 400, description = "Malformed id or If-Match", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid bearer token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Missing allocations:write", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Initiative not found", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 412, description = "The initiative changed since the ETag in If-Match", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 428, description = "If-Match is missing", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn delete_initiative<IR: InitiativeRepo>(
+    State(state): State<AppState<IR>>,
+    user: CurrentUser,
+    Path(id): Path<String>,
+    IfMatch(version): IfMatch,
+) -> Result<ApiSuccess<DeleteInitiativeResponse>, ApiError> {
+    let initiative_id = Uuid::parse_str(&id).map_err(ApiError::from)?;
+    state
+        .allocation_service
+        .delete_initiative(user.user.id, initiative_id, version)
+        .await
+        .map_err(ApiError::from)
+        .map(|()| ApiSuccess::new(StatusCode::OK, DeleteInitiativeResponse {}))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/initiatives",
+    tag = "initiatives",
+    responses(
+        (status = 200, description = "All initiatives", body = ApiResponseBody<Vec<GetInitiativeResponse>>),
+        (status = 401, description = "Missing or invalid bearer token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Missing allocations:read", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn get_initiatives<IR: InitiativeRepo>(
+    State(state): State<AppState<IR>>,
+) -> Result<ApiSuccess<Vec<GetInitiativeResponse>>, ApiError> {
+    state
+        .allocation_service
+        .get_initiatives()
+        .await
+        .map_err(ApiError::from)
+        .map(|items| {
+            let response_data = items.iter().map(GetInitiativeResponse::from).collect();
+            ApiSuccess::new(StatusCode::OK, response_data)
+        })
+}
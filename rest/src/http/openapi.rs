@@ -0,0 +1,97 @@
+/*!
+    The OpenAPI description of the `/api` routes, generated from the handler annotations and the
+    request and response models. `rest/openapi.json` is the committed copy; regenerate it with
+    `cargo run -p allocations-rest -- openapi > rest/openapi.json` after changing the API.
+*/
+
+use crate::http::{
+    assignment_handlers, audit_handlers, employee_assignment_handlers, employee_handlers,
+    export_handlers, initiative_handlers, plan_handlers, report_handlers, role_handlers,
+    tag_handlers, user_handlers,
+};
+use utoipa::openapi::security::{Http, HttpAuthScheme, SecurityScheme};
+use utoipa::{Modify, OpenApi};
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Allocations API",
+        description = "Employees, initiatives and how people are allocated to them over the year."
+    ),
+    paths(
+        employee_handlers::create_employee,
+        employee_handlers::update_employee,
+        employee_handlers::patch_employee,
+        employee_handlers::get_employee,
+        employee_handlers::delete_employee,
+        employee_handlers::restore_employee,
+        employee_handlers::import_employees,
+        employee_handlers::get_salaries,
+        employee_handlers::create_salary,
+        employee_handlers::get_employees,
+        export_handlers::export_employees,
+        initiative_handlers::create_initiative,
+        initiative_handlers::update_initiative,
+        initiative_handlers::get_initiative,
+        initiative_handlers::delete_initiative,
+        initiative_handlers::get_initiatives,
+        tag_handlers::create_tag,
+        tag_handlers::rename_tag,
+        tag_handlers::merge_tag,
+        tag_handlers::get_tag,
+        tag_handlers::delete_tag,
+        tag_handlers::get_tags,
+        assignment_handlers::create_assignment,
+        assignment_handlers::update_assignment,
+        assignment_handlers::get_assignment,
+        assignment_handlers::delete_assignment,
+        assignment_handlers::get_assignments,
+        employee_assignment_handlers::create_employee_assignment,
+        employee_assignment_handlers::update_employee_assignment,
+        employee_assignment_handlers::delete_employee_assignment,
+        employee_assignment_handlers::get_employee_assignments,
+        employee_assignment_handlers::get_initiative_staff,
+        export_handlers::export_allocations,
+        plan_handlers::import_allocations,
+        report_handlers::get_overallocated,
+        report_handlers::get_initiative_costs,
+        report_handlers::get_utilization,
+        user_handlers::get_current_user,
+        user_handlers::create_user,
+        user_handlers::get_user,
+        user_handlers::get_users,
+        user_handlers::set_user_role,
+        user_handlers::deactivate_user,
+        role_handlers::create_role,
+        role_handlers::get_roles,
+        audit_handlers::get_audit_entries,
+    ),
+    modifiers(&BearerAuth),
+    security(("bearer" = []))
+)]
+pub struct ApiDoc;
+
+/// Declares the JWT bearer scheme every `/api` route requires.
+struct BearerAuth;
+
+impl Modify for BearerAuth {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearer",
+            SecurityScheme::Http(
+                Http::builder()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .bearer_format("JWT")
+                    .build(),
+            ),
+        );
+    }
+}
+
+/// The API description as pretty printed JSON, as served and committed.
+pub fn openapi_json() -> String {
+    ApiDoc::openapi()
+        .to_pretty_json()
+        .expect("the OpenAPI document always serializes")
+}
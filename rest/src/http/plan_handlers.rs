@@ -0,0 +1,111 @@
+use crate::http::api::{
+    ApiError, ApiQuery, ApiResponseBody, ApiSuccess, ErrorCode, ProblemDetails,
+};
+use crate::http::auth::CurrentUser;
+use crate::models::export::{ExportFormat, XLSX_CONTENT_TYPE};
+use crate::models::plan::*;
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::header::CONTENT_TYPE;
+use axum::http::{HeaderMap, StatusCode};
+use repository::initiative_repo::InitiativeRepo;
+use service::allocation_errors::ImportAllocationPlanError;
+use service::allocation_service::AllocationRepo;
+use service::validation::RowError;
+
+use crate::http::AppState;
+
+/// A workbook when the body says so, otherwise CSV.
+fn upload_format(headers: &HeaderMap) -> Result<ExportFormat, ApiError> {
+    let content_type = headers
+        .get(CONTENT_TYPE)
+        .and_then(|x| x.to_str().ok())
+        .unwrap_or("text/csv");
+    if content_type.starts_with(XLSX_CONTENT_TYPE) {
+        Ok(ExportFormat::Xlsx)
+    } else if content_type.starts_with("text/csv") {
+        Ok(ExportFormat::Csv)
+    } else {
+        Err(ApiError::BadRequest(
+            ErrorCode::MalformedRequest,
+            format!(
+                "Unable to import {}; send text/csv or an XLSX workbook",
+                content_type
+            ),
+        ))
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/allocations/import",
+    tag = "assignments",
+    params(ImportPlanQuery),
+    request_body(
+        description = "Effective fractions in one of two layouts. Long, as the allocation export writes it: a header naming email, initiative and jan to dec, then one employee and initiative per row. Wide: a header naming email and each initiative above its first month, a second header row naming jan to dec under every initiative, then one employee per row; initiatives left blank on a row are skipped",
+        content(
+            (String = "text/csv"),
+            (Vec<u8> = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet")
+        )
+    ),
+    responses(
+        (status = 200, description = "What the import changed, or would change for a dry run", body = ApiResponseBody<ImportPlanResponse>),
+        (status = 400, description = "Malformed query string, unsupported content type, unreadable file or incomplete header", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid bearer token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Missing allocations:write", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 409, description = "A link or curve changed while the plan was stored; nothing was stored", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Nothing was stored; every failing field is listed in invalid_params with its line, or a link written meanwhile would put an employee above 100%", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+/// Adds and updates the links of a spreadsheet plan in one transaction, or nothing if any row
+/// fails. Links the file leaves out are kept.
+pub async fn import_allocations<R: AllocationRepo + InitiativeRepo>(
+    State(state): State<AppState<R>>,
+    user: CurrentUser,
+    ApiQuery(query): ApiQuery<ImportPlanQuery>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<ApiSuccess<ImportPlanResponse>, ApiError> {
+    let format = upload_format(&headers)?;
+    let rows = parse_plan(&body, format, query.year)
+        .map_err(|e| ApiError::BadRequest(ErrorCode::MalformedRequest, e))?;
+    let mut errors = Vec::new();
+    let mut lines = Vec::new();
+    let mut plan = Vec::new();
+    for row in rows {
+        match row.row {
+            Ok(ri) => {
+                lines.push(row.line);
+                plan.push(ri);
+            }
+            Err(invalid) => errors.extend(invalid.into_iter().map(|error| RowError {
+                row: row.line,
+                error,
+            })),
+        }
+    }
+    // Rows that parsed are still checked so the response lists every problem at once.
+    let dry_run = query.dry_run || !errors.is_empty();
+    match state
+        .allocation_service
+        .import_allocation_plan(user.user.id, query.year, &plan, dry_run)
+        .await
+    {
+        Ok(changes) if errors.is_empty() => {
+            return Ok(ApiSuccess::new(
+                StatusCode::OK,
+                ImportPlanResponse::new(query.year, query.dry_run, &changes),
+            ));
+        }
+        Ok(_) => {}
+        Err(ImportAllocationPlanError::Invalid { errors: invalid }) => {
+            errors.extend(invalid.into_iter().map(|x| RowError {
+                row: lines[x.row],
+                ..x
+            }));
+        }
+        Err(e) => return Err(ApiError::from(e)),
+    }
+    errors.sort_by_key(|x| x.row);
+    Err(ApiError::InvalidRows(errors))
+}
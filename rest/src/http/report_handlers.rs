@@ -0,0 +1,126 @@
+use crate::http::api::{ApiError, ApiQuery, ApiResponseBody, ApiSuccess, ProblemDetails};
+use crate::http::auth::CurrentUser;
+use crate::models::report::*;
+use axum::extract::State;
+use axum::http::header::{ACCEPT, CONTENT_TYPE};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use domain::dto::Scope;
+use repository::initiative_repo::InitiativeRepo;
+use service::allocation_service::AllocationRepo;
+
+use crate::http::AppState;
+
+/// Picks the explicit `format` when given, otherwise CSV if the client prefers `text/csv`.
+fn negotiate(format: Option<ReportFormat>, headers: &HeaderMap) -> ReportFormat {
+    format.unwrap_or_else(|| {
+        let accept = headers
+            .get(ACCEPT)
+            .and_then(|x| x.to_str().ok())
+            .unwrap_or_default();
+        if accept.contains("text/csv") {
+            ReportFormat::Csv
+        } else {
+            ReportFormat::Json
+        }
+    })
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/reports/overallocated",
+    tag = "reports",
+    params(ReportQuery),
+    responses(
+        (status = 200, description = "Employees allocated above 100% in some month", body = ApiResponseBody<Vec<OverallocatedEmployeeResponse>>),
+        (status = 400, description = "Malformed query string", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid bearer token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Missing reports:read", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn get_overallocated<R: AllocationRepo>(
+    State(state): State<AppState<R>>,
+    ApiQuery(query): ApiQuery<ReportQuery>,
+) -> Result<ApiSuccess<Vec<OverallocatedEmployeeResponse>>, ApiError> {
+    state
+        .allocation_service
+        .overallocated(query.year)
+        .await
+        .map_err(ApiError::from)
+        .map(|items| {
+            let response_data = items
+                .iter()
+                .map(OverallocatedEmployeeResponse::from)
+                .collect();
+            ApiSuccess::new(StatusCode::OK, response_data)
+        })
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/reports/initiative-costs",
+    tag = "reports",
+    params(CostReportQuery),
+    responses(
+        (status = 200, description = "Monthly costs per initiative; the breakdown only with salary:read", content(
+            (ApiResponseBody<Vec<InitiativeCostResponse>> = "application/json"),
+            (String = "text/csv")
+        )),
+        (status = 400, description = "Malformed query string", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid bearer token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Missing reports:read", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn get_initiative_costs<R: AllocationRepo + InitiativeRepo>(
+    State(state): State<AppState<R>>,
+    user: CurrentUser,
+    ApiQuery(query): ApiQuery<CostReportQuery>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    // Totals are always computed from real salaries; only the breakdown is hidden.
+    let show_months = user.has(Scope::SalaryRead);
+    let items = state
+        .allocation_service
+        .initiative_costs(query.year)
+        .await
+        .map_err(ApiError::from)?;
+    match negotiate(query.format, &headers) {
+        ReportFormat::Json => {
+            let response_data: Vec<InitiativeCostResponse> = items
+                .iter()
+                .map(|x| InitiativeCostResponse::new(x, show_months))
+                .collect();
+            Ok(ApiSuccess::new(StatusCode::OK, response_data).into_response())
+        }
+        ReportFormat::Csv => {
+            let body = initiative_costs_csv(&items, show_months).map_err(|e| {
+                ApiError::InternalServerError(format!("Unable to render cost report {}", e))
+            })?;
+            Ok(([(CONTENT_TYPE, "text/csv; charset=utf-8")], body).into_response())
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/reports/utilization",
+    tag = "reports",
+    params(ReportQuery),
+    responses(
+        (status = 200, description = "Employees by months utilization grid", body = ApiResponseBody<UtilizationResponse>),
+        (status = 400, description = "Malformed query string", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid bearer token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Missing reports:read", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn get_utilization<R: AllocationRepo>(
+    State(state): State<AppState<R>>,
+    ApiQuery(query): ApiQuery<ReportQuery>,
+) -> Result<ApiSuccess<UtilizationResponse>, ApiError> {
+    state
+        .allocation_service
+        .utilization(query.year)
+        .await
+        .map_err(ApiError::from)
+        .map(|items| ApiSuccess::new(StatusCode::OK, UtilizationResponse::new(query.year, &items)))
+}
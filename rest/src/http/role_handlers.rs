@@ -0,0 +1,65 @@
+use crate::http::AppState;
+use crate::http::api::{ApiError, ApiJson, ApiResponseBody, ApiSuccess, ErrorCode, ProblemDetails};
+use crate::http::auth::CurrentUser;
+use crate::models::user::*;
+use axum::extract::State;
+use axum::http::StatusCode;
+use repository::role_repo::RoleRepo;
+
+#[utoipa::path(
+    post,
+    path = "/api/admin/roles",
+    tag = "admin",
+    request_body = CreateRoleRequest,
+    responses(
+        (status = 201, description = "Role created", body = ApiResponseBody<RoleResponse>),
+        (status = 400, description = "Malformed body", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid bearer token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Missing users:admin", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Unknown scopes", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn create_role<R: RoleRepo>(
+    State(state): State<AppState<R>>,
+    user: CurrentUser,
+    ApiJson(body): ApiJson<CreateRoleRequest>,
+) -> Result<ApiSuccess<RoleResponse>, ApiError> {
+    let role = body.to_dto().map_err(|unknown| {
+        ApiError::UnprocessableEntity(
+            ErrorCode::ValidationFailed,
+            format!("Unknown scopes: {}", unknown.join(", ")),
+        )
+    })?;
+    state
+        .allocation_service
+        .create_role(user.user.id, &role)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref ri| ApiSuccess::new(StatusCode::CREATED, ri.into()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/roles",
+    tag = "admin",
+    responses(
+        (status = 200, description = "All roles", body = ApiResponseBody<Vec<RoleResponse>>),
+        (status = 401, description = "Missing or invalid bearer token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Missing users:admin", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn get_roles<R: RoleRepo>(
+    State(state): State<AppState<R>>,
+) -> Result<ApiSuccess<Vec<RoleResponse>>, ApiError> {
+    state
+        .allocation_service
+        .get_roles()
+        .await
+        .map_err(ApiError::from)
+        .map(|items| {
+            ApiSuccess::new(
+                StatusCode::OK,
+                items.iter().map(RoleResponse::from).collect(),
+            )
+        })
+}
@@ -0,0 +1,200 @@
+use crate::http::api::{ApiError, ApiJson, ApiResponseBody, ApiSuccess, IfMatch, ProblemDetails};
+use crate::models::tag::*;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use repository::tag_repo::TagRepo;
+use uuid::Uuid;
+
+use crate::http::AppState;
+use crate::http::auth::CurrentUser;
+
+#[utoipa::path(
+    post,
+    path = "/api/tags",
+    tag = "tags",
+    request_body = CreateTagRequest,
+    responses(
+        (status = 201, description = "Tag created", body = ApiResponseBody<TagResponse>,
+            headers(("ETag" = String, description = "The tag's version"))),
+        (status = 400, description = "Malformed body", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid bearer token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Missing allocations:write", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 409, description = "Tag already exists", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Body has missing or mistyped fields", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn create_tag<TR: TagRepo>(
+    State(state): State<AppState<TR>>,
+    user: CurrentUser,
+    ApiJson(body): ApiJson<CreateTagRequest>,
+) -> Result<ApiSuccess<TagResponse>, ApiError> {
+    let domain_req = (&body).into();
+    state
+        .allocation_service
+        .create_tag(user.user.id, &domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref ri| ApiSuccess::new(StatusCode::CREATED, ri.into()).with_version(ri.version))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/tags/{id}",
+    tag = "tags",
+    params(
+        ("id" = Uuid, Path, description = "Tag id"),
+        ("If-Match" = String, Header, description = "The ETag of the tag being renamed"),
+    ),
+    request_body = RenameTagRequest,
+    responses(
+        (status = 200, description = "Tag renamed", body = ApiResponseBody<TagResponse>,
+            headers(("ETag" = String, description = "The tag's new version"))),
+        (status = 400, description = "Malformed id, body or If-Match", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid bearer token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Missing allocations:write", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Tag not found", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 409, description = "Tag already exists", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 412, description = "The tag changed since the ETag in If-Match", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Body has missing or mistyped fields", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 428, description = "If-Match is missing", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn rename_tag<TR: TagRepo>(
+    State(state): State<AppState<TR>>,
+    user: CurrentUser,
+    Path(id): Path<String>,
+    IfMatch(version): IfMatch,
+    ApiJson(body): ApiJson<RenameTagRequest>,
+) -> Result<ApiSuccess<TagResponse>, ApiError> {
+    let tag_id = Uuid::parse_str(&id).map_err(ApiError::from)?;
+    let domain_req = body.to_dto(tag_id, version);
+    state
+        .allocation_service
+        .rename_tag(user.user.id, &domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref ri| ApiSuccess::new(StatusCode::OK, ri.into()).with_version(ri.version))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/tags/{id}/merge",
+    tag = "tags",
+    params(
+        ("id" = Uuid, Path, description = "Tag to merge and delete"),
+        ("If-Match" = String, Header, description = "The ETag of the tag being merged"),
+    ),
+    request_body = MergeTagRequest,
+    responses(
+        (status = 200, description = "The tag everything was merged into", body = ApiResponseBody<GetTagResponse>,
+            headers(("ETag" = String, description = "The merged tag's version"))),
+        (status = 400, description = "Malformed id, If-Match or body", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid bearer token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Missing allocations:write", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Tag not found", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 412, description = "The merged tag changed since the ETag in If-Match", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "A tag cannot be merged into itself", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 428, description = "If-Match is missing", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn merge_tag<TR: TagRepo>(
+    State(state): State<AppState<TR>>,
+    user: CurrentUser,
+    Path(id): Path<String>,
+    IfMatch(version): IfMatch,
+    ApiJson(body): ApiJson<MergeTagRequest>,
+) -> Result<ApiSuccess<GetTagResponse>, ApiError> {
+    let tag_id = Uuid::parse_str(&id).map_err(ApiError::from)?;
+    state
+        .allocation_service
+        .merge_tags(user.user.id, tag_id, version, body.into)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref ri| ApiSuccess::new(StatusCode::OK, ri.into()).with_version(ri.tag.version))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/tags/{id}",
+    tag = "tags",
+    params(("id" = Uuid, Path, description = "Tag id")),
+    responses(
+        (status = 200, description = "The tag and its usage", body = ApiResponseBody<GetTagResponse>,
+            headers(("ETag" = String, description = "The tag's version, for If-Match"))),
+        (status = 400, description = "Malformed id", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid bearer token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Missing allocations:read", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Tag not found", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn get_tag<TR: TagRepo>(
+    State(state): State<AppState<TR>>,
+    Path(id): Path<String>,
+) -> Result<ApiSuccess<GetTagResponse>, ApiError> {
+    let tag_id = Uuid::parse_str(&id).map_err(ApiError::from)?;
+    match state.allocation_service.get_tag(tag_id).await {
+        Ok(Some(ri)) => {
+            Ok(ApiSuccess::new(StatusCode::OK, GetTagResponse::from(&ri))
+                .with_version(ri.tag.version))
+        }
+        Ok(None) => Err(ApiError::NotFound("Tag not found".to_string())),
+        Err(e) => Err(ApiError::from(e)),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/tags/{id}",
+    tag = "tags",
+    params(
+        ("id" = Uuid, Path, description = "Tag id"),
+        ("If-Match" = String, Header, description = "The ETag of the tag being deleted"),
+    ),
+    responses(
+        (status = 200, description = "Tag deleted", body = ApiResponseBody<DeleteTagResponse>),
+        (status = 400, description = "Malformed id or If-Match", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid bearer token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Missing allocations:write", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Tag not found", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 412, description = "The tag changed since the ETag in If-Match", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 428, description = "If-Match is missing", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn delete_tag<TR: TagRepo>(
+    State(state): State<AppState<TR>>,
+    user: CurrentUser,
+    Path(id): Path<String>,
+    IfMatch(version): IfMatch,
+) -> Result<ApiSuccess<DeleteTagResponse>, ApiError> {
+    let tag_id = Uuid::parse_str(&id).map_err(ApiError::from)?;
+    state
+        .allocation_service
+        .delete_tag(user.user.id, tag_id, version)
+        .await
+        .map_err(ApiError::from)
+        .map(|()| ApiSuccess::new(StatusCode::OK, DeleteTagResponse {}))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/tags",
+    tag = "tags",
+    responses(
+        (status = 200, description = "All tags and their usage", body = ApiResponseBody<Vec<GetTagResponse>>),
+        (status = 401, description = "Missing or invalid bearer token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Missing allocations:read", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn get_tags<TR: TagRepo>(
+    State(state): State<AppState<TR>>,
+) -> Result<ApiSuccess<Vec<GetTagResponse>>, ApiError> {
+    state
+        .allocation_service
+        .get_tags()
+        .await
+        .map_err(ApiError::from)
+        .map(|items| {
+            let response_data = items.iter().map(GetTagResponse::from).collect();
+            ApiSuccess::new(StatusCode::OK, response_data)
+        })
+}
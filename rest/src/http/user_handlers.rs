@@ -0,0 +1,162 @@
+use crate::http::AppState;
+use crate::http::api::{ApiError, ApiJson, ApiResponseBody, ApiSuccess, ProblemDetails};
+use crate::http::auth::CurrentUser;
+use crate::models::user::*;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use repository::user_repo::UserRepo;
+use uuid::Uuid;
+
+#[utoipa::path(
+    get,
+    path = "/api/me",
+    tag = "users",
+    responses(
+        (status = 200, description = "The caller and their scopes", body = ApiResponseBody<CurrentUserResponse>),
+        (status = 401, description = "Missing or invalid bearer token", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+/// Returns the user the bearer token was issued for.
+pub async fn get_current_user(
+    user: CurrentUser,
+) -> Result<ApiSuccess<CurrentUserResponse>, ApiError> {
+    Ok(ApiSuccess::new(
+        StatusCode::OK,
+        CurrentUserResponse::new(&user.user, &user.scopes),
+    ))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/admin/users",
+    tag = "admin",
+    request_body = CreateUserRequest,
+    responses(
+        (status = 201, description = "User created", body = ApiResponseBody<GetUserResponse>),
+        (status = 400, description = "Malformed body", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid bearer token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Missing users:admin", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 409, description = "A user with the email already exists", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Role does not exist", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn create_user<R: UserRepo>(
+    State(state): State<AppState<R>>,
+    user: CurrentUser,
+    ApiJson(body): ApiJson<CreateUserRequest>,
+) -> Result<ApiSuccess<GetUserResponse>, ApiError> {
+    state
+        .allocation_service
+        .create_user(user.user.id, &(&body).into())
+        .await
+        .map_err(ApiError::from)
+        .map(|ref ri| ApiSuccess::new(StatusCode::CREATED, ri.into()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/users/{id}",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "User id")),
+    responses(
+        (status = 200, description = "The user", body = ApiResponseBody<GetUserResponse>),
+        (status = 400, description = "Malformed id", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid bearer token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Missing users:admin", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "User not found", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn get_user<R: UserRepo>(
+    State(state): State<AppState<R>>,
+    Path(id): Path<String>,
+) -> Result<ApiSuccess<GetUserResponse>, ApiError> {
+    let user_id = Uuid::parse_str(&id).map_err(ApiError::from)?;
+    match state.allocation_service.get_user(user_id).await {
+        Ok(Some(ri)) => Ok(ApiSuccess::new(StatusCode::OK, (&ri).into())),
+        Ok(None) => Err(ApiError::NotFound(format!("User with id {} not found", id))),
+        Err(e) => Err(ApiError::from(e)),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/users",
+    tag = "admin",
+    responses(
+        (status = 200, description = "All users", body = ApiResponseBody<Vec<GetUserResponse>>),
+        (status = 401, description = "Missing or invalid bearer token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Missing users:admin", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn get_users<R: UserRepo>(
+    State(state): State<AppState<R>>,
+) -> Result<ApiSuccess<Vec<GetUserResponse>>, ApiError> {
+    state
+        .allocation_service
+        .get_users()
+        .await
+        .map_err(ApiError::from)
+        .map(|items| {
+            ApiSuccess::new(
+                StatusCode::OK,
+                items.iter().map(GetUserResponse::from).collect(),
+            )
+        })
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/admin/users/{id}/role",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "User id")),
+    request_body = SetUserRoleRequest,
+    responses(
+        (status = 200, description = "Role assigned", body = ApiResponseBody<GetUserResponse>),
+        (status = 400, description = "Malformed id or body", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid bearer token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Missing users:admin", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "User not found", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Role does not exist", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn set_user_role<R: UserRepo>(
+    State(state): State<AppState<R>>,
+    user: CurrentUser,
+    Path(id): Path<String>,
+    ApiJson(body): ApiJson<SetUserRoleRequest>,
+) -> Result<ApiSuccess<GetUserResponse>, ApiError> {
+    let user_id = Uuid::parse_str(&id).map_err(ApiError::from)?;
+    state
+        .allocation_service
+        .set_user_role(user.user.id, user_id, body.role_id)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref ri| ApiSuccess::new(StatusCode::OK, ri.into()))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/admin/users/{id}/deactivate",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "User id")),
+    responses(
+        (status = 200, description = "User deactivated", body = ApiResponseBody<GetUserResponse>),
+        (status = 400, description = "Malformed id", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid bearer token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Missing users:admin", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "User not found", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn deactivate_user<R: UserRepo>(
+    State(state): State<AppState<R>>,
+    user: CurrentUser,
+    Path(id): Path<String>,
+) -> Result<ApiSuccess<GetUserResponse>, ApiError> {
+    let user_id = Uuid::parse_str(&id).map_err(ApiError::from)?;
+    state
+        .allocation_service
+        .deactivate_user(user.user.id, user_id)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref ri| ApiSuccess::new(StatusCode::OK, ri.into()))
+}
@@ -1,6 +1,6 @@
-mod http;
-mod models;
-use crate::http::{HttpServer, HttpServerConfig};
+use allocations_rest::http::{
+    AuthConfig, HttpServer, HttpServerConfig, TokenKeySource, openapi_json,
+};
 use repository::postgres_db::PostgresDb;
 use std::env;
 use thiserror::Error;
@@ -9,16 +9,32 @@ use thiserror::Error;
 pub enum ConfigError {
     #[error("Error Loading the config from the environment")]
     LoadEnvError { name: String },
+
+    #[error(
+        "Exactly one of {JWT_SECRET_KEY}, {JWT_PUBLIC_KEY_FILE_KEY} or {JWT_JWKS_FILE_KEY} must be set"
+    )]
+    TokenKeysError,
 }
 
 const DATABASE_URL_KEY: &str = "DATABASE_URL";
 
 const SERVER_PORT_KEY: &str = "SERVER_PORT";
 
+const JWT_SECRET_KEY: &str = "JWT_SECRET";
+
+const JWT_PUBLIC_KEY_FILE_KEY: &str = "JWT_PUBLIC_KEY_FILE";
+
+const JWT_JWKS_FILE_KEY: &str = "JWT_JWKS_FILE";
+
+const JWT_ISSUER_KEY: &str = "JWT_ISSUER";
+
+const JWT_AUDIENCE_KEY: &str = "JWT_AUDIENCE";
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Config {
     pub server_port: String,
     pub database_url: String,
+    pub auth: AuthConfig,
 }
 
 impl Config {
@@ -26,10 +42,25 @@ impl Config {
         dotenvy::dotenv().ok();
         let server_port = load_env(SERVER_PORT_KEY)?;
         let database_url = load_env(DATABASE_URL_KEY)?;
+        let keys = match (
+            env::var(JWT_SECRET_KEY).ok(),
+            env::var(JWT_PUBLIC_KEY_FILE_KEY).ok(),
+            env::var(JWT_JWKS_FILE_KEY).ok(),
+        ) {
+            (Some(secret), None, None) => TokenKeySource::Secret(secret),
+            (None, Some(path), None) => TokenKeySource::PublicKeyFile(path),
+            (None, None, Some(path)) => TokenKeySource::JwksFile(path),
+            _ => return Err(ConfigError::TokenKeysError),
+        };
 
         Ok(Config {
             server_port,
             database_url,
+            auth: AuthConfig {
+                keys,
+                issuer: env::var(JWT_ISSUER_KEY).ok(),
+                audience: env::var(JWT_AUDIENCE_KEY).ok(),
+            },
         })
     }
 }
@@ -42,11 +73,17 @@ fn load_env(key: &str) -> Result<String, ConfigError> {
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
+    // `allocations-rest openapi` prints the API description instead of serving it.
+    if env::args().nth(1).as_deref() == Some("openapi") {
+        println!("{}", openapi_json());
+        return Ok(());
+    }
     let config = Config::from_env()?;
     tracing_subscriber::fmt::init();
 
     let server_config = HttpServerConfig {
         port: &config.server_port,
+        auth: config.auth.clone(),
     };
 
     let pool = PostgresDb::create_db_pool(&config.database_url).await?;
@@ -1 +1,10 @@
+pub mod assignment;
+pub mod audit;
 pub mod employee;
+pub mod employee_assignment;
+pub mod export;
+pub mod initiative;
+pub mod plan;
+pub mod report;
+pub mod tag;
+pub mod user;
@@ -0,0 +1,164 @@
+use bigdecimal::BigDecimal;
+use domain::dto;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+/// A year's allocation curve keyed by month. Months that are left out are unplanned.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct Months {
+    #[serde(default, with = "bigdecimal::serde::json_num_option")]
+    #[schema(value_type = Option<f64>)]
+    pub jan: Option<BigDecimal>,
+    #[serde(default, with = "bigdecimal::serde::json_num_option")]
+    #[schema(value_type = Option<f64>)]
+    pub feb: Option<BigDecimal>,
+    #[serde(default, with = "bigdecimal::serde::json_num_option")]
+    #[schema(value_type = Option<f64>)]
+    pub mar: Option<BigDecimal>,
+    #[serde(default, with = "bigdecimal::serde::json_num_option")]
+    #[schema(value_type = Option<f64>)]
+    pub apr: Option<BigDecimal>,
+    #[serde(default, with = "bigdecimal::serde::json_num_option")]
+    #[schema(value_type = Option<f64>)]
+    pub may: Option<BigDecimal>,
+    #[serde(default, with = "bigdecimal::serde::json_num_option")]
+    #[schema(value_type = Option<f64>)]
+    pub jun: Option<BigDecimal>,
+    #[serde(default, with = "bigdecimal::serde::json_num_option")]
+    #[schema(value_type = Option<f64>)]
+    pub jul: Option<BigDecimal>,
+    #[serde(default, with = "bigdecimal::serde::json_num_option")]
+    #[schema(value_type = Option<f64>)]
+    pub aug: Option<BigDecimal>,
+    #[serde(default, with = "bigdecimal::serde::json_num_option")]
+    #[schema(value_type = Option<f64>)]
+    pub sep: Option<BigDecimal>,
+    #[serde(default, with = "bigdecimal::serde::json_num_option")]
+    #[schema(value_type = Option<f64>)]
+    pub oct: Option<BigDecimal>,
+    #[serde(default, with = "bigdecimal::serde::json_num_option")]
+    #[schema(value_type = Option<f64>)]
+    pub nov: Option<BigDecimal>,
+    #[serde(default, with = "bigdecimal::serde::json_num_option")]
+    #[schema(value_type = Option<f64>)]
+    pub dec: Option<BigDecimal>,
+}
+
+impl Months {
+    pub fn to_dto(&self, id: Uuid, year: i32, version: i32) -> dto::Assignment {
+        dto::Assignment {
+            id,
+            year,
+            version,
+            jan: self.jan.clone(),
+            feb: self.feb.clone(),
+            mar: self.mar.clone(),
+            apr: self.apr.clone(),
+            may: self.may.clone(),
+            jun: self.jun.clone(),
+            jul: self.jul.clone(),
+            aug: self.aug.clone(),
+            sep: self.sep.clone(),
+            oct: self.oct.clone(),
+            nov: self.nov.clone(),
+            dec: self.dec.clone(),
+        }
+    }
+}
+
+impl From<&dto::Assignment> for Months {
+    fn from(value: &dto::Assignment) -> Self {
+        Months {
+            jan: value.jan.clone(),
+            feb: value.feb.clone(),
+            mar: value.mar.clone(),
+            apr: value.apr.clone(),
+            may: value.may.clone(),
+            jun: value.jun.clone(),
+            jul: value.jul.clone(),
+            aug: value.aug.clone(),
+            sep: value.sep.clone(),
+            oct: value.oct.clone(),
+            nov: value.nov.clone(),
+            dec: value.dec.clone(),
+        }
+    }
+}
+
+impl From<&[BigDecimal; 12]> for Months {
+    fn from(value: &[BigDecimal; 12]) -> Self {
+        let [jan, feb, mar, apr, may, jun, jul, aug, sep, oct, nov, dec] = value.clone();
+        Months {
+            jan: Some(jan),
+            feb: Some(feb),
+            mar: Some(mar),
+            apr: Some(apr),
+            may: Some(may),
+            jun: Some(jun),
+            jul: Some(jul),
+            aug: Some(aug),
+            sep: Some(sep),
+            oct: Some(oct),
+            nov: Some(nov),
+            dec: Some(dec),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct GetAssignmentResponse {
+    pub id: Uuid,
+    pub year: i32,
+    pub months: Months,
+    /// Changes with every write; send it quoted in `If-Match` to change or delete this assignment.
+    pub version: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct DeleteAssignmentResponse {}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct CreateAssignmentRequest {
+    pub year: i32,
+    #[serde(default)]
+    pub months: Months,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct UpdateAssignmentRequest {
+    pub year: i32,
+    #[serde(default)]
+    pub months: Months,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct GetAssignmentsQuery {
+    pub year: Option<i32>,
+}
+
+impl From<&CreateAssignmentRequest> for dto::Assignment {
+    fn from(value: &CreateAssignmentRequest) -> Self {
+        // The id will get replaced properly on down the line
+        value.months.to_dto(uuid::Uuid::nil(), value.year, 0)
+    }
+}
+
+impl UpdateAssignmentRequest {
+    /// Builds assignment `id` to store if it is still at `version`.
+    pub fn to_dto(&self, id: Uuid, version: i32) -> dto::Assignment {
+        self.months.to_dto(id, self.year, version)
+    }
+}
+
+impl From<&dto::Assignment> for GetAssignmentResponse {
+    fn from(value: &dto::Assignment) -> Self {
+        GetAssignmentResponse {
+            id: value.id,
+            year: value.year,
+            months: value.into(),
+            version: value.version,
+        }
+    }
+}
@@ -0,0 +1,163 @@
+use chrono::{DateTime, Utc};
+use domain::dto;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+/// Query string accepted when reading the audit log.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct GetAuditQuery {
+    pub entity: AuditEntityParam,
+    /// Only return the entries for this row.
+    pub id: Option<Uuid>,
+    /// The `next_cursor` of the previous page.
+    pub cursor: Option<Uuid>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditEntityParam {
+    Employee,
+    Initiative,
+    Tag,
+    Assignment,
+    EmployeeAssignment,
+    User,
+    Role,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditActionResponse {
+    Create,
+    Update,
+    Delete,
+    Merge,
+    Terminate,
+    Restore,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct GetAuditEntryResponse {
+    pub id: Uuid,
+    pub actor_id: Uuid,
+    /// Absent once the actor's user no longer exists.
+    pub actor_email: Option<String>,
+    pub entity: AuditEntityParam,
+    pub entity_id: Uuid,
+    pub action: AuditActionResponse,
+    /// The row before the change; absent for creates. Employee salaries are omitted for
+    /// callers without `salary:read`.
+    #[schema(value_type = Option<Object>)]
+    pub before: Option<Value>,
+    /// The row after the change; absent for deletes. For merges, the tag merged into.
+    #[schema(value_type = Option<Object>)]
+    pub after: Option<Value>,
+    pub changed_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct GetAuditEntriesResponse {
+    pub items: Vec<GetAuditEntryResponse>,
+    /// Pass back as `cursor` to fetch the following page; absent on the last page.
+    pub next_cursor: Option<Uuid>,
+}
+
+impl From<AuditEntityParam> for dto::AuditEntity {
+    fn from(value: AuditEntityParam) -> Self {
+        match value {
+            AuditEntityParam::Employee => dto::AuditEntity::Employee,
+            AuditEntityParam::Initiative => dto::AuditEntity::Initiative,
+            AuditEntityParam::Tag => dto::AuditEntity::Tag,
+            AuditEntityParam::Assignment => dto::AuditEntity::Assignment,
+            AuditEntityParam::EmployeeAssignment => dto::AuditEntity::EmployeeAssignment,
+            AuditEntityParam::User => dto::AuditEntity::User,
+            AuditEntityParam::Role => dto::AuditEntity::Role,
+        }
+    }
+}
+
+impl From<dto::AuditEntity> for AuditEntityParam {
+    fn from(value: dto::AuditEntity) -> Self {
+        match value {
+            dto::AuditEntity::Employee => AuditEntityParam::Employee,
+            dto::AuditEntity::Initiative => AuditEntityParam::Initiative,
+            dto::AuditEntity::Tag => AuditEntityParam::Tag,
+            dto::AuditEntity::Assignment => AuditEntityParam::Assignment,
+            dto::AuditEntity::EmployeeAssignment => AuditEntityParam::EmployeeAssignment,
+            dto::AuditEntity::User => AuditEntityParam::User,
+            dto::AuditEntity::Role => AuditEntityParam::Role,
+        }
+    }
+}
+
+impl From<dto::AuditAction> for AuditActionResponse {
+    fn from(value: dto::AuditAction) -> Self {
+        match value {
+            dto::AuditAction::Create => AuditActionResponse::Create,
+            dto::AuditAction::Update => AuditActionResponse::Update,
+            dto::AuditAction::Delete => AuditActionResponse::Delete,
+            dto::AuditAction::Merge => AuditActionResponse::Merge,
+            dto::AuditAction::Terminate => AuditActionResponse::Terminate,
+            dto::AuditAction::Restore => AuditActionResponse::Restore,
+        }
+    }
+}
+
+impl GetAuditQuery {
+    pub fn to_dto(&self, default_limit: i64) -> dto::AuditQuery {
+        dto::AuditQuery {
+            entity: self.entity.into(),
+            entity_id: self.id,
+            after: self.cursor,
+            limit: self.limit.unwrap_or(default_limit),
+        }
+    }
+}
+
+impl From<&dto::AuditEntry> for GetAuditEntryResponse {
+    fn from(value: &dto::AuditEntry) -> Self {
+        GetAuditEntryResponse {
+            id: value.id,
+            actor_id: value.actor_id,
+            actor_email: value.actor_email.clone(),
+            entity: value.entity.into(),
+            entity_id: value.entity_id,
+            action: value.action.into(),
+            before: value.before.clone(),
+            after: value.after.clone(),
+            changed_at: value.changed_at,
+        }
+    }
+}
+
+impl GetAuditEntriesResponse {
+    pub fn new(value: &dto::Page<dto::AuditEntry>, show_salary: bool) -> Self {
+        GetAuditEntriesResponse {
+            items: value
+                .items
+                .iter()
+                .map(|x| GetAuditEntryResponse::from(x).redacted(show_salary))
+                .collect(),
+            next_cursor: value.next,
+        }
+    }
+}
+
+impl GetAuditEntryResponse {
+    /// Drops employee salaries from the snapshots unless the caller may see them.
+    pub fn redacted(mut self, show_salary: bool) -> Self {
+        if !show_salary && self.entity == AuditEntityParam::Employee {
+            for snapshot in [&mut self.before, &mut self.after].into_iter().flatten() {
+                if let Value::Object(fields) = snapshot {
+                    fields.remove("salary");
+                    fields.remove("salaries");
+                }
+            }
+        }
+        self
+    }
+}
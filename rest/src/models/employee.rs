@@ -1,54 +1,221 @@
-use bigdecimal::BigDecimal;
+use bigdecimal::{BigDecimal, Zero};
+use chrono::NaiveDate;
 use domain::dto;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
+use service::validation::{FieldError, validate_employee};
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
 pub struct GetEmployeeResponse {
     pub id: Uuid,
     pub first_name: String,
     pub last_name: String,
     email: String,
+    /// Omitted for callers without `salary:read`.
+    #[serde(
+        with = "bigdecimal::serde::json_num_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    #[schema(value_type = Option<f64>)]
+    pub salary: Option<BigDecimal>,
+    /// Changes with every write; send it quoted in `If-Match` to change or delete this employee.
+    pub version: i32,
+    /// The employee's last day, once they have left.
+    pub terminated_on: Option<NaiveDate>,
+}
+
+/// Query string accepted when listing employees.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct GetEmployeesQuery {
+    pub name_prefix: Option<String>,
+    #[param(value_type = Option<f64>)]
+    pub min_salary: Option<BigDecimal>,
+    #[param(value_type = Option<f64>)]
+    pub max_salary: Option<BigDecimal>,
+    #[serde(default)]
+    pub sort: EmployeeSortParam,
+    #[serde(default)]
+    pub order: SortOrderParam,
+    /// The `next_cursor` of the previous page.
+    pub cursor: Option<Uuid>,
+    pub limit: Option<i64>,
+    /// Also list employees who have left.
+    #[serde(default)]
+    pub include_terminated: bool,
+}
+
+/// Query string accepted when terminating an employee.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct TerminateEmployeeQuery {
+    /// The employee's last day; today when omitted.
+    pub terminated_on: Option<NaiveDate>,
+}
+
+/// Query string accepted when importing employees.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ImportEmployeesQuery {
+    /// Check every row and report what would fail, but create nobody.
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct ImportEmployeesResponse {
+    pub dry_run: bool,
+    /// How many employees were created, or would be without `dry_run`.
+    pub count: usize,
+    /// The employees created, or for a dry run the rows that would be.
+    pub employees: Vec<ImportedEmployeeResponse>,
+}
+
+/// One row of an employee import. A dry run stores nothing, so it has no `id` or `version`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct ImportedEmployeeResponse {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<Uuid>,
+    first_name: String,
+    last_name: String,
+    email: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
+    salary: Option<BigDecimal>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version: Option<i32>,
+}
+
+/// The header line an employee import starts with.
+pub const IMPORT_COLUMNS: [&str; 4] = ["first_name", "last_name", "email", "salary"];
+
+/// One data row of an employee import and the line of the file it is on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportRow {
+    pub line: usize,
+    pub employee: Result<dto::Employee, Vec<FieldError>>,
+}
+
+/// A rate to pay an employee from `effective_from` until their next one starts.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct CreateSalaryRequest {
+    /// The first day of the rate; past dates correct history and future ones schedule a raise.
+    pub effective_from: NaiveDate,
+    #[schema(value_type = f64)]
+    pub salary: BigDecimal,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct GetSalaryResponse {
+    pub effective_from: NaiveDate,
     #[serde(with = "bigdecimal::serde::json_num")]
+    #[schema(value_type = f64)]
     pub salary: BigDecimal,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
-pub struct DeleteEmployeeResponse {}
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum EmployeeSortParam {
+    #[default]
+    Id,
+    Name,
+    Email,
+    Salary,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrderParam {
+    #[default]
+    Asc,
+    Desc,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct GetEmployeesResponse {
+    pub items: Vec<GetEmployeeResponse>,
+    /// Pass back as `cursor` to fetch the following page; absent on the last page.
+    pub next_cursor: Option<Uuid>,
+}
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
 pub struct CreateEmployeeRequest {
     pub first_name: String,
     pub last_name: String,
     pub email: String,
+    #[schema(value_type = f64)]
     pub salary: BigDecimal,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
 pub struct UpdateEmployeeRequest {
-    pub id: Uuid,
+    /// Optional; the path id is authoritative and a different id here is rejected.
+    pub id: Option<Uuid>,
     pub first_name: String,
     pub last_name: String,
     pub email: String,
-    pub salary: BigDecimal,
+    /// Left unchanged when omitted; only `salary:write` callers may send it.
+    #[schema(value_type = Option<f64>)]
+    pub salary: Option<BigDecimal>,
+}
+
+/// A JSON Merge Patch (RFC 7396) of an employee: absent fields are kept, present ones replaced.
+/// Every field is required, so `null` (which would remove it) is rejected.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct PatchEmployeeRequest {
+    /// Optional; the path id is authoritative and a different id here is rejected.
+    #[serde(default, deserialize_with = "nullable")]
+    #[schema(value_type = Uuid, required = false)]
+    pub id: Option<Option<Uuid>>,
+    #[serde(default, deserialize_with = "nullable")]
+    #[schema(value_type = String, required = false)]
+    pub first_name: Option<Option<String>>,
+    #[serde(default, deserialize_with = "nullable")]
+    #[schema(value_type = String, required = false)]
+    pub last_name: Option<Option<String>>,
+    #[serde(default, deserialize_with = "nullable")]
+    #[schema(value_type = String, required = false)]
+    pub email: Option<Option<String>>,
+    /// Only `salary:write` callers may send it.
+    #[serde(default, deserialize_with = "nullable")]
+    #[schema(value_type = f64, required = false)]
+    pub salary: Option<Option<BigDecimal>>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+/// Deserializes a present field, keeping `null` apart from absence (which `default` covers).
+fn nullable<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
 pub struct UpdateEmployeeResponse {
     id: Uuid,
     first_name: String,
     last_name: String,
     email: String,
-    salary: BigDecimal,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
+    salary: Option<BigDecimal>,
+    version: i32,
+    terminated_on: Option<NaiveDate>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
 pub struct CreateEmployeeResponse {
     id: Uuid,
     first_name: String,
     last_name: String,
     email: String,
-    salary: BigDecimal,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
+    salary: Option<BigDecimal>,
+    version: i32,
 }
 
 impl From<&CreateEmployeeRequest> for dto::Employee {
@@ -59,22 +226,78 @@ impl From<&CreateEmployeeRequest> for dto::Employee {
             last_name: value.last_name.clone(),
             email: value.email.clone(),
             salary: value.salary.clone(),
+            version: 0,
+            terminated_on: None,
         }
     }
 }
 
-impl From<&UpdateEmployeeRequest> for dto::Employee {
-    fn from(value: &UpdateEmployeeRequest) -> Self {
+impl UpdateEmployeeRequest {
+    /// Builds employee `id` to store if it is still at `version`, keeping `current_salary`
+    /// unless the request sets one. Updates never change the termination date.
+    pub fn to_dto(&self, id: Uuid, version: i32, current_salary: &BigDecimal) -> dto::Employee {
         dto::Employee {
-            id: value.id,
-            first_name: value.first_name.clone(),
-            last_name: value.last_name.clone(),
-            email: value.email.clone(),
-            salary: value.salary.clone(),
+            id,
+            version,
+            first_name: self.first_name.clone(),
+            last_name: self.last_name.clone(),
+            email: self.email.clone(),
+            salary: self
+                .salary
+                .clone()
+                .unwrap_or_else(|| current_salary.clone()),
+            terminated_on: None,
+        }
+    }
+}
+
+impl PatchEmployeeRequest {
+    pub fn sets_salary(&self) -> bool {
+        self.salary.is_some()
+    }
+
+    /// Applies the patch to `current`, or lists every field set to `null`.
+    pub fn apply(&self, current: &dto::Employee) -> Result<dto::Employee, Vec<FieldError>> {
+        let mut errors = Vec::new();
+        let mut patched = current.clone();
+        patch(
+            &mut errors,
+            "first_name",
+            &self.first_name,
+            &mut patched.first_name,
+        );
+        patch(
+            &mut errors,
+            "last_name",
+            &self.last_name,
+            &mut patched.last_name,
+        );
+        patch(&mut errors, "email", &self.email, &mut patched.email);
+        patch(&mut errors, "salary", &self.salary, &mut patched.salary);
+        if errors.is_empty() {
+            Ok(patched)
+        } else {
+            Err(errors)
         }
     }
 }
 
+fn patch<T: Clone>(
+    errors: &mut Vec<FieldError>,
+    field: &'static str,
+    value: &Option<Option<T>>,
+    target: &mut T,
+) {
+    match value {
+        None => {}
+        Some(Some(value)) => *target = value.clone(),
+        Some(None) => errors.push(FieldError {
+            field,
+            message: "must not be null".to_string(),
+        }),
+    }
+}
+
 impl From<&dto::Employee> for UpdateEmployeeResponse {
     fn from(value: &dto::Employee) -> Self {
         UpdateEmployeeResponse {
@@ -82,7 +305,9 @@ impl From<&dto::Employee> for UpdateEmployeeResponse {
             first_name: value.first_name.clone(),
             last_name: value.last_name.clone(),
             email: value.email.clone(),
-            salary: value.salary.clone(),
+            salary: Some(value.salary.clone()),
+            version: value.version,
+            terminated_on: value.terminated_on,
         }
     }
 }
@@ -94,7 +319,9 @@ impl From<&dto::Employee> for GetEmployeeResponse {
             first_name: value.first_name.clone(),
             last_name: value.last_name.clone(),
             email: value.email.clone(),
-            salary: value.salary.clone(),
+            salary: Some(value.salary.clone()),
+            version: value.version,
+            terminated_on: value.terminated_on,
         }
     }
 }
@@ -106,7 +333,203 @@ impl From<&dto::Employee> for CreateEmployeeResponse {
             first_name: value.first_name.clone(),
             last_name: value.last_name.clone(),
             email: value.email.clone(),
+            salary: Some(value.salary.clone()),
+            version: value.version,
+        }
+    }
+}
+
+/// Reads an employee import: the [IMPORT_COLUMNS] header, then one employee per line. Rows
+/// with the wrong number of fields or a salary that is not a number come back as errors; the
+/// others are left for the service to validate. Fails only when the file cannot be read.
+pub fn parse_employees_csv(body: &[u8]) -> Result<Vec<ImportRow>, String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(body);
+    let header = reader
+        .headers()
+        .map_err(|e| format!("Unable to read the header: {}", e))?;
+    if header.iter().ne(IMPORT_COLUMNS) {
+        return Err(format!("The header must be {}", IMPORT_COLUMNS.join(",")));
+    }
+    reader
+        .records()
+        .map(|record| {
+            let record = record.map_err(|e| format!("Unable to read the file: {}", e))?;
+            Ok(ImportRow {
+                line: csv_line(body, &record),
+                employee: employee_from_record(&record),
+            })
+        })
+        .collect()
+}
+
+/// The line of `body` that `record` is on. The reader's own line count leaves out blank lines,
+/// and its byte offset points before those preceding the record, so both are counted here.
+pub fn csv_line(body: &[u8], record: &csv::StringRecord) -> usize {
+    let offset = record
+        .position()
+        .map_or(0, |x| usize::try_from(x.byte()).unwrap_or(usize::MAX))
+        .min(body.len());
+    let (before, rest) = body.split_at(offset);
+    let blank = rest.iter().take_while(|&&x| x == b'\n' || x == b'\r');
+    before.iter().chain(blank).filter(|&&x| x == b'\n').count() + 1
+}
+
+fn employee_from_record(record: &csv::StringRecord) -> Result<dto::Employee, Vec<FieldError>> {
+    let [first_name, last_name, email, salary] = record.iter().collect::<Vec<_>>()[..] else {
+        return Err(vec![FieldError {
+            field: "row",
+            message: format!("must have {} fields", IMPORT_COLUMNS.len()),
+        }]);
+    };
+    let parsed = salary.parse::<BigDecimal>();
+    let employee = dto::Employee {
+        id: Uuid::nil(),
+        first_name: first_name.to_string(),
+        last_name: last_name.to_string(),
+        email: email.to_string(),
+        salary: parsed.clone().unwrap_or_else(|_| BigDecimal::zero()),
+        version: 0,
+        terminated_on: None,
+    };
+    if parsed.is_ok() {
+        return Ok(employee);
+    }
+    // The row is rejected either way, but its other fields are reported with it.
+    let mut errors = validate_employee(&employee).err().unwrap_or_default();
+    errors.push(FieldError {
+        field: "salary",
+        message: "must be a number".to_string(),
+    });
+    Err(errors)
+}
+
+impl CreateSalaryRequest {
+    pub fn to_dto(&self, employee_id: Uuid) -> dto::Salary {
+        dto::Salary {
+            employee_id,
+            effective_from: self.effective_from,
+            salary: self.salary.clone(),
+        }
+    }
+}
+
+impl From<&dto::Salary> for GetSalaryResponse {
+    fn from(value: &dto::Salary) -> Self {
+        GetSalaryResponse {
+            effective_from: value.effective_from,
             salary: value.salary.clone(),
         }
     }
 }
+
+impl GetEmployeeResponse {
+    /// Drops the salary unless the caller may see it.
+    pub fn redacted(mut self, show_salary: bool) -> Self {
+        if !show_salary {
+            self.salary = None;
+        }
+        self
+    }
+}
+
+impl UpdateEmployeeResponse {
+    /// Drops the salary unless the caller may see it.
+    pub fn redacted(mut self, show_salary: bool) -> Self {
+        if !show_salary {
+            self.salary = None;
+        }
+        self
+    }
+}
+
+impl ImportedEmployeeResponse {
+    /// The row as stored, or without `id` and `version` when `dry_run` stored nothing.
+    pub fn new(value: &dto::Employee, dry_run: bool) -> Self {
+        ImportedEmployeeResponse {
+            id: (!dry_run).then_some(value.id),
+            first_name: value.first_name.clone(),
+            last_name: value.last_name.clone(),
+            email: value.email.clone(),
+            salary: Some(value.salary.clone()),
+            version: (!dry_run).then_some(value.version),
+        }
+    }
+
+    /// Drops the salary unless the caller may see it.
+    pub fn redacted(mut self, show_salary: bool) -> Self {
+        if !show_salary {
+            self.salary = None;
+        }
+        self
+    }
+}
+
+impl CreateEmployeeResponse {
+    /// Drops the salary unless the caller may see it.
+    pub fn redacted(mut self, show_salary: bool) -> Self {
+        if !show_salary {
+            self.salary = None;
+        }
+        self
+    }
+}
+
+impl GetEmployeesQuery {
+    /// Whether the query filters or sorts on salary, which would reveal it.
+    pub fn uses_salary(&self) -> bool {
+        self.min_salary.is_some()
+            || self.max_salary.is_some()
+            || self.sort == EmployeeSortParam::Salary
+    }
+}
+
+impl From<EmployeeSortParam> for dto::EmployeeSort {
+    fn from(value: EmployeeSortParam) -> Self {
+        match value {
+            EmployeeSortParam::Id => dto::EmployeeSort::Id,
+            EmployeeSortParam::Name => dto::EmployeeSort::Name,
+            EmployeeSortParam::Email => dto::EmployeeSort::Email,
+            EmployeeSortParam::Salary => dto::EmployeeSort::Salary,
+        }
+    }
+}
+
+impl From<SortOrderParam> for dto::SortOrder {
+    fn from(value: SortOrderParam) -> Self {
+        match value {
+            SortOrderParam::Asc => dto::SortOrder::Asc,
+            SortOrderParam::Desc => dto::SortOrder::Desc,
+        }
+    }
+}
+
+impl GetEmployeesQuery {
+    pub fn to_dto(&self, default_limit: i64) -> dto::EmployeeQuery {
+        dto::EmployeeQuery {
+            name_prefix: self.name_prefix.clone().filter(|x| !x.is_empty()),
+            min_salary: self.min_salary.clone(),
+            max_salary: self.max_salary.clone(),
+            sort: self.sort.into(),
+            order: self.order.into(),
+            after: self.cursor,
+            include_terminated: self.include_terminated,
+            limit: self.limit.unwrap_or(default_limit),
+        }
+    }
+}
+
+impl GetEmployeesResponse {
+    pub fn new(value: &dto::Page<dto::Employee>, show_salary: bool) -> Self {
+        GetEmployeesResponse {
+            items: value
+                .items
+                .iter()
+                .map(|x| GetEmployeeResponse::from(x).redacted(show_salary))
+                .collect(),
+            next_cursor: value.next,
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use bigdecimal::BigDecimal;
+use domain::dto;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct EmployeeAssignmentResponse {
+    pub id: Uuid,
+    pub employee_id: Uuid,
+    pub assignment_id: Uuid,
+    pub initiative_id: Uuid,
+    #[serde(with = "bigdecimal::serde::json_num")]
+    #[schema(value_type = f64)]
+    pub allocation: BigDecimal,
+    /// Changes with every write; send it quoted in `If-Match` to change or delete this link.
+    pub version: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct DeleteEmployeeAssignmentResponse {}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct CreateEmployeeAssignmentRequest {
+    pub assignment_id: Uuid,
+    pub initiative_id: Uuid,
+    #[schema(value_type = f64)]
+    pub allocation: BigDecimal,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct UpdateEmployeeAssignmentRequest {
+    pub assignment_id: Uuid,
+    pub initiative_id: Uuid,
+    #[schema(value_type = f64)]
+    pub allocation: BigDecimal,
+}
+
+impl CreateEmployeeAssignmentRequest {
+    pub fn to_dto(&self, employee_id: Uuid) -> dto::EmployeeAssignment {
+        dto::EmployeeAssignment {
+            id: uuid::Uuid::nil(), // This will get replaced properly on down the line
+            employee_id,
+            assignment_id: self.assignment_id,
+            allocation: self.allocation.clone(),
+            initiative_id: self.initiative_id,
+            version: 0,
+        }
+    }
+}
+
+impl UpdateEmployeeAssignmentRequest {
+    /// Builds link `id` of `employee_id` to store if it is still at `version`.
+    pub fn to_dto(&self, employee_id: Uuid, id: Uuid, version: i32) -> dto::EmployeeAssignment {
+        dto::EmployeeAssignment {
+            id,
+            employee_id,
+            assignment_id: self.assignment_id,
+            allocation: self.allocation.clone(),
+            initiative_id: self.initiative_id,
+            version,
+        }
+    }
+}
+
+impl From<&dto::EmployeeAssignment> for EmployeeAssignmentResponse {
+    fn from(value: &dto::EmployeeAssignment) -> Self {
+        EmployeeAssignmentResponse {
+            id: value.id,
+            employee_id: value.employee_id,
+            assignment_id: value.assignment_id,
+            initiative_id: value.initiative_id,
+            allocation: value.allocation.clone(),
+            version: value.version,
+        }
+    }
+}
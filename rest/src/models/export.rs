@@ -0,0 +1,193 @@
+use bigdecimal::{BigDecimal, ToPrimitive};
+use chrono::NaiveDate;
+use domain::dto;
+use rust_xlsxwriter::{Format, Workbook, XlsxError};
+use serde::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+
+/// Media type of an Office Open XML workbook.
+pub const XLSX_CONTENT_TYPE: &str =
+    "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet";
+
+/// The spreadsheet formats an export can be downloaded as.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    Xlsx,
+}
+
+impl ExportFormat {
+    pub fn content_type(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv; charset=utf-8",
+            ExportFormat::Xlsx => XLSX_CONTENT_TYPE,
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Xlsx => "xlsx",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct EmployeeExportQuery {
+    /// Overrides the `Accept` header when given.
+    pub format: Option<ExportFormat>,
+    /// Also export employees who have left.
+    #[serde(default)]
+    pub include_terminated: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AllocationExportQuery {
+    pub year: i32,
+    /// Overrides the `Accept` header when given.
+    pub format: Option<ExportFormat>,
+}
+
+/// One spreadsheet cell. Numbers and dates keep their type so a workbook stores them as such
+/// rather than as text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Cell {
+    Text(String),
+    Number(BigDecimal),
+    Date(NaiveDate),
+}
+
+impl Cell {
+    fn to_csv(&self) -> String {
+        match self {
+            Cell::Text(value) => value.clone(),
+            // Products like allocation × fraction carry trailing zeros nobody wants to see.
+            Cell::Number(value) => value.normalized().to_string(),
+            Cell::Date(value) => value.to_string(),
+        }
+    }
+}
+
+/// Column headers of the employee export; `salary` only appears with `show_salary`.
+pub fn employee_columns(show_salary: bool) -> Vec<&'static str> {
+    let mut columns = vec!["id", "first_name", "last_name", "email"];
+    if show_salary {
+        columns.push("salary");
+    }
+    columns.push("terminated_on");
+    columns
+}
+
+/// One row of the employee export, matching [`employee_columns`]. Employees still working leave
+/// `terminated_on` empty.
+pub fn employee_row(employee: &dto::Employee, show_salary: bool) -> Vec<Option<Cell>> {
+    let mut row = vec![
+        Some(Cell::Text(employee.id.to_string())),
+        Some(Cell::Text(employee.first_name.clone())),
+        Some(Cell::Text(employee.last_name.clone())),
+        Some(Cell::Text(employee.email.clone())),
+    ];
+    if show_salary {
+        row.push(Some(Cell::Number(employee.salary.clone())));
+    }
+    row.push(employee.terminated_on.map(Cell::Date));
+    row
+}
+
+/// Column headers of the allocation export: who, on what, and one column per month.
+pub fn allocation_columns() -> Vec<&'static str> {
+    let mut columns = vec![
+        "employee_id",
+        "email",
+        "first_name",
+        "last_name",
+        "initiative_id",
+        "initiative",
+        "year",
+    ];
+    columns.extend(dto::MONTH_NAMES);
+    columns
+}
+
+/// One row of the allocation export, matching [`allocation_columns`]. Months hold the effective
+/// fraction of the person, i.e. allocation × monthly curve.
+pub fn allocation_row(item: &dto::PlannedAllocation) -> Vec<Option<Cell>> {
+    let mut row = vec![
+        Some(Cell::Text(item.employee.id.to_string())),
+        Some(Cell::Text(item.employee.email.clone())),
+        Some(Cell::Text(item.employee.first_name.clone())),
+        Some(Cell::Text(item.employee.last_name.clone())),
+        Some(Cell::Text(item.initiative.id.to_string())),
+        Some(Cell::Text(item.initiative.initiative.clone())),
+        Some(Cell::Number(BigDecimal::from(item.year))),
+    ];
+    row.extend(item.months.iter().map(|x| Some(Cell::Number(x.clone()))));
+    row
+}
+
+/// Renders `rows` as CSV, preceded by `header` when given. Chunks rendered separately
+/// concatenate into one file, which lets a large export be sent as it is read.
+pub fn csv_chunk(
+    header: Option<&[&str]>,
+    rows: impl IntoIterator<Item = Vec<Option<Cell>>>,
+) -> Result<Vec<u8>, csv::Error> {
+    let mut writer = csv::Writer::from_writer(vec![]);
+    if let Some(header) = header {
+        writer.write_record(header)?;
+    }
+    for row in rows {
+        writer.write_record(
+            row.iter()
+                .map(|cell| cell.as_ref().map(Cell::to_csv).unwrap_or_default()),
+        )?;
+    }
+    writer
+        .into_inner()
+        .map_err(|e| csv::Error::from(e.into_error()))
+}
+
+/// Builds a one-sheet workbook with a bold, frozen header row. A workbook is a zip archive whose
+/// directory comes last, so unlike CSV it can only be sent once it is complete.
+pub fn xlsx_workbook(
+    sheet: &str,
+    header: &[&str],
+    rows: impl IntoIterator<Item = Vec<Option<Cell>>>,
+) -> Result<Vec<u8>, XlsxError> {
+    let bold = Format::new().set_bold();
+    let date = Format::new().set_num_format("yyyy-mm-dd");
+    let mut workbook = Workbook::new();
+    let worksheet = workbook.add_worksheet();
+    worksheet.set_name(sheet)?;
+    for (col, title) in (0..).zip(header) {
+        worksheet.write_string_with_format(0, col, *title, &bold)?;
+    }
+    worksheet.set_freeze_panes(1, 0)?;
+    for (row_num, row) in (1..).zip(rows) {
+        for (col, cell) in (0..).zip(row) {
+            match cell {
+                None => {}
+                Some(Cell::Text(value)) => {
+                    worksheet.write_string(row_num, col, value)?;
+                }
+                // Spreadsheets hold doubles; a value out of that range is kept as text.
+                Some(Cell::Number(value)) => match value.to_f64() {
+                    Some(number) => {
+                        worksheet.write_number(row_num, col, number)?;
+                    }
+                    None => {
+                        worksheet.write_string(row_num, col, value.to_string())?;
+                    }
+                },
+                Some(Cell::Date(value)) => {
+                    worksheet.write_datetime_with_format(row_num, col, value, &date)?;
+                }
+            }
+        }
+    }
+    worksheet.autofit();
+    workbook.save_to_buffer()
+}
@@ -0,0 +1,90 @@
+use domain::dto;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct GetInitiativeResponse {
+    pub id: Uuid,
+    pub initiative: String,
+    /// Changes with every write; send it quoted in `If-Match` to change or delete this initiative.
+    pub version: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct DeleteInitiativeResponse {}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct CreateInitiativeRequest {
+    pub initiative: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct UpdateInitiativeRequest {
+    pub initiative: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct CreateInitiativeResponse {
+    id: Uuid,
+    initiative: String,
+    version: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct UpdateInitiativeResponse {
+    id: Uuid,
+    initiative: String,
+    version: i32,
+}
+
+impl From<&CreateInitiativeRequest> for dto::Initiative {
+    fn from(value: &CreateInitiativeRequest) -> Self {
+        dto::Initiative {
+            id: uuid::Uuid::nil(), // This will get replaced properly on down the line
+            initiative: value.initiative.clone(),
+            version: 0,
+        }
+    }
+}
+
+impl UpdateInitiativeRequest {
+    /// Builds initiative `id` to store if it is still at `version`.
+    pub fn to_dto(&self, id: Uuid, version: i32) -> dto::Initiative {
+        dto::Initiative {
+            id,
+            initiative: self.initiative.clone(),
+            version,
+        }
+    }
+}
+
+impl From<&dto::Initiative> for GetInitiativeResponse {
+    fn from(value: &dto::Initiative) -> Self {
+        GetInitiativeResponse {
+            id: value.id,
+            initiative: value.initiative.clone(),
+            version: value.version,
+        }
+    }
+}
+
+impl From<&dto::Initiative> for CreateInitiativeResponse {
+    fn from(value: &dto::Initiative) -> Self {
+        CreateInitiativeResponse {
+            id: value.id,
+            initiative: value.initiative.clone(),
+            version: value.version,
+        }
+    }
+}
+
+impl From<&dto::Initiative> for UpdateInitiativeResponse {
+    fn from(value: &dto::Initiative) -> Self {
+        UpdateInitiativeResponse {
+            id: value.id,
+            initiative: value.initiative.clone(),
+            version: value.version,
+        }
+    }
+}
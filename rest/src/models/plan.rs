@@ -0,0 +1,311 @@
+use crate::models::assignment::Months;
+use crate::models::employee::csv_line;
+use crate::models::export::ExportFormat;
+use bigdecimal::BigDecimal;
+use calamine::{Reader, Xlsx};
+use domain::dto;
+use serde::{Deserialize, Serialize};
+use service::validation::FieldError;
+use std::io::Cursor;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ImportPlanQuery {
+    pub year: i32,
+    /// Work out and return the changes, but store nothing.
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum PlanChangeKindResponse {
+    Added,
+    Changed,
+    Unchanged,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct PlanChangeResponse {
+    pub employee_id: Uuid,
+    pub email: String,
+    pub initiative_id: Uuid,
+    pub initiative: String,
+    pub change: PlanChangeKindResponse,
+    /// Effective allocation per month before the import; absent when the employee was not on
+    /// the initiative.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before: Option<Months>,
+    pub after: Months,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct ImportPlanResponse {
+    pub dry_run: bool,
+    pub year: i32,
+    pub added: usize,
+    pub changed: usize,
+    pub unchanged: usize,
+    /// Every added or changed link, in file order; unchanged rows are only counted.
+    pub changes: Vec<PlanChangeResponse>,
+}
+
+/// The columns a plan import in the long layout needs besides the months, found by name in its
+/// header row. Other columns, such as the names and ids of an allocation export, are ignored.
+pub const PLAN_COLUMNS: [&str; 2] = ["email", "initiative"];
+
+/// One data row of a plan import and the line of the file it is on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlanImportRow {
+    pub line: usize,
+    pub row: Result<dto::PlanRow, Vec<FieldError>>,
+}
+
+impl From<dto::PlanChangeKind> for PlanChangeKindResponse {
+    fn from(value: dto::PlanChangeKind) -> Self {
+        match value {
+            dto::PlanChangeKind::Added => PlanChangeKindResponse::Added,
+            dto::PlanChangeKind::Changed => PlanChangeKindResponse::Changed,
+            dto::PlanChangeKind::Unchanged => PlanChangeKindResponse::Unchanged,
+        }
+    }
+}
+
+/// Effective fractions are products like allocation × fraction; their trailing zeros are noise.
+fn normalized(months: &[BigDecimal; 12]) -> Months {
+    Months::from(&months.clone().map(|x| x.normalized()))
+}
+
+impl From<&dto::PlanChange> for PlanChangeResponse {
+    fn from(value: &dto::PlanChange) -> Self {
+        PlanChangeResponse {
+            employee_id: value.employee.id,
+            email: value.employee.email.clone(),
+            initiative_id: value.initiative.id,
+            initiative: value.initiative.initiative.clone(),
+            change: value.kind.into(),
+            before: value.before.as_ref().map(normalized),
+            after: normalized(&value.after),
+        }
+    }
+}
+
+impl ImportPlanResponse {
+    pub fn new(year: i32, dry_run: bool, changes: &[dto::PlanChange]) -> Self {
+        let count = |kind| changes.iter().filter(|x| x.kind == kind).count();
+        ImportPlanResponse {
+            dry_run,
+            year,
+            added: count(dto::PlanChangeKind::Added),
+            changed: count(dto::PlanChangeKind::Changed),
+            unchanged: count(dto::PlanChangeKind::Unchanged),
+            changes: changes
+                .iter()
+                .filter(|x| x.kind != dto::PlanChangeKind::Unchanged)
+                .map(PlanChangeResponse::from)
+                .collect(),
+        }
+    }
+}
+
+/// The cells of every row of a CSV file or of the first sheet of a workbook, with their line.
+fn read_sheet(body: &[u8], format: ExportFormat) -> Result<Vec<(usize, Vec<String>)>, String> {
+    match format {
+        ExportFormat::Csv => csv::ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .trim(csv::Trim::All)
+            .from_reader(body)
+            .records()
+            .map(|record| {
+                let record = record.map_err(|e| format!("Unable to read the file: {}", e))?;
+                Ok((
+                    csv_line(body, &record),
+                    record.iter().map(str::to_string).collect(),
+                ))
+            })
+            .collect(),
+        ExportFormat::Xlsx => {
+            let mut workbook = Xlsx::new(Cursor::new(body))
+                .map_err(|e| format!("Unable to read the workbook: {}", e))?;
+            let range = workbook
+                .worksheet_range_at(0)
+                .ok_or("The workbook has no sheets")?
+                .map_err(|e| format!("Unable to read the first sheet: {}", e))?;
+            let first = range.start().map_or(0, |(row, _)| row as usize);
+            Ok(range
+                .rows()
+                .enumerate()
+                .map(|(i, cells)| {
+                    let cells = cells.iter().map(|x| x.to_string().trim().to_string());
+                    (first + i + 1, cells.collect())
+                })
+                .collect())
+        }
+    }
+}
+
+/// Reads a plan for `year` in either of two layouts. The long one, as the allocation export
+/// writes it, has a header row naming at least [PLAN_COLUMNS] and every month, then one employee
+/// and initiative per row. The wide one planners build has a first header row naming `email`
+/// and each initiative above the first of its months, a second naming `jan` to `dec` under every
+/// initiative, then one employee per row; an initiative whose months are all blank on a row is
+/// left out of it. Both hold effective fractions, and other columns are ignored.
+///
+/// Blank rows are skipped and blank months are unplanned. Rows with a month that is not a number
+/// or a `year` other than `year` come back as errors; the others are left for the service to
+/// match and validate. Fails only when the file or its header cannot be read.
+pub fn parse_plan(
+    body: &[u8],
+    format: ExportFormat,
+    year: i32,
+) -> Result<Vec<PlanImportRow>, String> {
+    let mut rows = read_sheet(body, format)?.into_iter();
+    let (_, header) = rows.next().ok_or("The file is empty")?;
+    let column = |name: &str| header.iter().position(|x| x.eq_ignore_ascii_case(name));
+    if let (Some(email), None) = (column("email"), column("initiative")) {
+        let (_, months) = rows.next().ok_or_else(header_error)?;
+        let initiatives = wide_columns(&header, &months)?;
+        return Ok(rows
+            .filter(|(_, cells)| cells.iter().any(|x| !x.is_empty()))
+            .flat_map(|(line, cells)| {
+                let cell = |i: usize| cells.get(i).map_or("", String::as_str).to_string();
+                initiatives
+                    .iter()
+                    .filter(|(_, columns)| columns.iter().any(|&i| !cell(i).is_empty()))
+                    .map(|(initiative, columns)| PlanImportRow {
+                        line,
+                        row: plan_row(&cell(email), initiative, None, year, |month| {
+                            cell(columns[month])
+                        }),
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect());
+    }
+    let required: Option<Vec<usize>> = PLAN_COLUMNS
+        .iter()
+        .chain(&dto::MONTH_NAMES)
+        .map(|x| column(x))
+        .collect();
+    let Some(required) = required else {
+        return Err(header_error());
+    };
+    let year_column = column("year");
+    Ok(rows
+        .filter(|(_, cells)| cells.iter().any(|x| !x.is_empty()))
+        .map(|(line, cells)| {
+            let cell = |i: usize| cells.get(i).map_or("", String::as_str).to_string();
+            PlanImportRow {
+                line,
+                row: plan_row(
+                    &cell(required[0]),
+                    &cell(required[1]),
+                    year_column.map(cell).as_deref(),
+                    year,
+                    |month| cell(required[PLAN_COLUMNS.len() + month]),
+                ),
+            }
+        })
+        .collect())
+}
+
+fn header_error() -> String {
+    format!(
+        "The header must name the columns {}, or email and each initiative above a row naming \
+         jan to dec under every initiative",
+        [&PLAN_COLUMNS[..], &dto::MONTH_NAMES[..]]
+            .concat()
+            .join(",")
+    )
+}
+
+/// The initiatives of a wide plan header and the column of each of their months. A name in
+/// `initiatives` starts a block that runs over the blank cells after it, as merged cells read;
+/// `months` names the month of each column of the block.
+fn wide_columns(
+    initiatives: &[String],
+    months: &[String],
+) -> Result<Vec<(String, [usize; 12])>, String> {
+    let mut blocks: Vec<(String, [Option<usize>; 12])> = Vec::new();
+    let mut open = false;
+    for (i, month) in months.iter().enumerate() {
+        let month = dto::MONTH_NAMES
+            .iter()
+            .position(|x| x.eq_ignore_ascii_case(month));
+        let name = initiatives.get(i).map_or("", String::as_str);
+        if !name.is_empty() {
+            // Other columns, such as names, have no month below them and close the block.
+            open = month.is_some() && !name.eq_ignore_ascii_case("email");
+            if open {
+                blocks.push((name.to_string(), [None; 12]));
+            }
+        }
+        let (Some(month), true, Some((name, columns))) = (month, open, blocks.last_mut()) else {
+            continue;
+        };
+        if columns[month].replace(i).is_some() {
+            return Err(format!(
+                "The months under {} name {} twice",
+                name,
+                dto::MONTH_NAMES[month]
+            ));
+        }
+    }
+    if blocks.is_empty() {
+        return Err(header_error());
+    }
+    blocks
+        .into_iter()
+        .map(|(name, columns)| {
+            let full: Option<Vec<usize>> = columns.into_iter().collect();
+            match full {
+                Some(full) => Ok((name, full.try_into().expect("twelve months"))),
+                None => Err(format!("The months under {} must be jan to dec", name)),
+            }
+        })
+        .collect()
+}
+
+fn plan_row(
+    email: &str,
+    initiative: &str,
+    row_year: Option<&str>,
+    year: i32,
+    month_cell: impl Fn(usize) -> String,
+) -> Result<dto::PlanRow, Vec<FieldError>> {
+    let mut errors = Vec::new();
+    if let Some(row_year) = row_year
+        && !row_year.is_empty()
+        && row_year.parse::<i32>() != Ok(year)
+    {
+        errors.push(FieldError {
+            field: "year",
+            message: format!("must be {}", year),
+        });
+    }
+    let mut months: [Option<BigDecimal>; 12] = Default::default();
+    for (month, value) in months.iter_mut().enumerate() {
+        let text = month_cell(month);
+        if text.is_empty() {
+            continue;
+        }
+        match text.parse::<BigDecimal>() {
+            Ok(fraction) => *value = Some(fraction),
+            Err(_) => errors.push(FieldError {
+                field: dto::MONTH_NAMES[month],
+                message: "must be a number".to_string(),
+            }),
+        }
+    }
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    Ok(dto::PlanRow {
+        email: email.to_string(),
+        initiative: initiative.to_string(),
+        months,
+    })
+}
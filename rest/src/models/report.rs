@@ -0,0 +1,170 @@
+use crate::models::assignment::Months;
+use bigdecimal::BigDecimal;
+use domain::dto;
+use serde::{Deserialize, Serialize};
+use service::allocation::{average_utilization, overallocated_months, unallocated};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ReportQuery {
+    pub year: i32,
+}
+
+/// The representations a report can be rendered in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFormat {
+    #[default]
+    Json,
+    Csv,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct CostReportQuery {
+    pub year: i32,
+    /// Overrides the `Accept` header when given.
+    pub format: Option<ReportFormat>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct InitiativeCostResponse {
+    pub initiative_id: Uuid,
+    pub initiative: String,
+    pub year: i32,
+    /// Omitted for callers without `salary:read`; a month's cost can single out one salary.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub months: Option<Months>,
+    #[serde(with = "bigdecimal::serde::json_num")]
+    #[schema(value_type = f64)]
+    pub total: BigDecimal,
+}
+
+impl InitiativeCostResponse {
+    pub fn new(value: &dto::InitiativeCost, show_months: bool) -> Self {
+        InitiativeCostResponse {
+            initiative_id: value.initiative.id,
+            initiative: value.initiative.initiative.clone(),
+            year: value.year,
+            months: show_months.then(|| (&value.months).into()),
+            total: value.total.clone(),
+        }
+    }
+}
+
+/// Renders the cost report with one row per initiative and one column per month. The month
+/// cells are left empty unless `show_months` is set.
+pub fn initiative_costs_csv(
+    items: &[dto::InitiativeCost],
+    show_months: bool,
+) -> Result<String, csv::Error> {
+    let mut writer = csv::Writer::from_writer(vec![]);
+    let mut header = vec!["initiative_id", "initiative", "year"];
+    header.extend(dto::MONTH_NAMES);
+    header.push("total");
+    writer.write_record(&header)?;
+    for item in items {
+        let mut record = vec![
+            item.initiative.id.to_string(),
+            item.initiative.initiative.clone(),
+            item.year.to_string(),
+        ];
+        record.extend(item.months.iter().map(|x| {
+            if show_months {
+                x.to_string()
+            } else {
+                String::new()
+            }
+        }));
+        record.push(item.total.to_string());
+        writer.write_record(&record)?;
+    }
+    let bytes = writer
+        .into_inner()
+        .map_err(|e| csv::Error::from(e.into_error()))?;
+    Ok(String::from_utf8(bytes).expect("csv writer only emits utf-8 from utf-8 input"))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct OverallocatedEmployeeResponse {
+    pub employee_id: Uuid,
+    pub first_name: String,
+    pub last_name: String,
+    pub email: String,
+    pub year: i32,
+    /// Total allocation for every month of the year.
+    pub months: Months,
+    /// The months in which the total exceeds 100%.
+    #[schema(value_type = Vec<String>)]
+    pub overallocated_months: Vec<&'static str>,
+}
+
+impl From<&dto::EmployeeAllocation> for OverallocatedEmployeeResponse {
+    fn from(value: &dto::EmployeeAllocation) -> Self {
+        OverallocatedEmployeeResponse {
+            employee_id: value.employee.id,
+            first_name: value.employee.first_name.clone(),
+            last_name: value.employee.last_name.clone(),
+            email: value.employee.email.clone(),
+            year: value.year,
+            months: (&value.months).into(),
+            overallocated_months: overallocated_months(&value.months)
+                .into_iter()
+                .map(|month| dto::MONTH_NAMES[month])
+                .collect(),
+        }
+    }
+}
+
+/// Employees × months grid of allocated fractions for one year.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct UtilizationResponse {
+    pub year: i32,
+    /// Column headers for `allocated` and `unallocated` in every row.
+    #[schema(value_type = Vec<String>)]
+    pub months: [&'static str; 12],
+    pub rows: Vec<UtilizationRowResponse>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct UtilizationRowResponse {
+    pub employee_id: Uuid,
+    pub first_name: String,
+    pub last_name: String,
+    pub allocated: [JsonDecimal; 12],
+    /// Capacity left per month; non-zero cells are the ones to highlight.
+    pub unallocated: [JsonDecimal; 12],
+    #[serde(with = "bigdecimal::serde::json_num")]
+    #[schema(value_type = f64)]
+    pub average_utilization: BigDecimal,
+}
+
+/// A decimal rendered as a JSON number.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+#[schema(value_type = f64)]
+pub struct JsonDecimal(#[serde(with = "bigdecimal::serde::json_num")] pub BigDecimal);
+
+impl UtilizationResponse {
+    pub fn new(year: i32, items: &[dto::EmployeeAllocation]) -> Self {
+        UtilizationResponse {
+            year,
+            months: dto::MONTH_NAMES,
+            rows: items.iter().map(UtilizationRowResponse::from).collect(),
+        }
+    }
+}
+
+impl From<&dto::EmployeeAllocation> for UtilizationRowResponse {
+    fn from(value: &dto::EmployeeAllocation) -> Self {
+        UtilizationRowResponse {
+            employee_id: value.employee.id,
+            first_name: value.employee.first_name.clone(),
+            last_name: value.employee.last_name.clone(),
+            allocated: value.months.clone().map(JsonDecimal),
+            unallocated: unallocated(&value.months).map(JsonDecimal),
+            average_utilization: average_utilization(&value.months),
+        }
+    }
+}
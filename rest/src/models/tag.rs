@@ -0,0 +1,83 @@
+use domain::dto;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct GetTagResponse {
+    pub id: Uuid,
+    pub tag: String,
+    pub assignment_count: i64,
+    pub employee_assignment_count: i64,
+    /// Changes with every write; send it quoted in `If-Match` to change or delete this tag.
+    pub version: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct DeleteTagResponse {}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct CreateTagRequest {
+    pub tag: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct RenameTagRequest {
+    pub tag: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct MergeTagRequest {
+    /// The tag that absorbs every reference to the tag in the path.
+    pub into: Uuid,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct TagResponse {
+    id: Uuid,
+    tag: String,
+    version: i32,
+}
+
+impl From<&CreateTagRequest> for dto::Tag {
+    fn from(value: &CreateTagRequest) -> Self {
+        dto::Tag {
+            id: uuid::Uuid::nil(), // This will get replaced properly on down the line
+            tag: value.tag.clone(),
+            version: 0,
+        }
+    }
+}
+
+impl RenameTagRequest {
+    /// Builds tag `id` to rename if it is still at `version`.
+    pub fn to_dto(&self, id: Uuid, version: i32) -> dto::Tag {
+        dto::Tag {
+            id,
+            tag: self.tag.clone(),
+            version,
+        }
+    }
+}
+
+impl From<&dto::Tag> for TagResponse {
+    fn from(value: &dto::Tag) -> Self {
+        TagResponse {
+            id: value.id,
+            tag: value.tag.clone(),
+            version: value.version,
+        }
+    }
+}
+
+impl From<&dto::TagUsage> for GetTagResponse {
+    fn from(value: &dto::TagUsage) -> Self {
+        GetTagResponse {
+            id: value.tag.id,
+            tag: value.tag.tag.clone(),
+            assignment_count: value.assignment_count,
+            employee_assignment_count: value.employee_assignment_count,
+            version: value.tag.version,
+        }
+    }
+}
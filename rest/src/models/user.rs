@@ -0,0 +1,127 @@
+use domain::dto;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct GetUserResponse {
+    pub id: Uuid,
+    pub first_name: String,
+    pub last_name: String,
+    pub email: String,
+    pub external_id: String,
+    pub external_id_source: String,
+    pub role_id: Option<Uuid>,
+    pub active: bool,
+}
+
+impl From<&dto::User> for GetUserResponse {
+    fn from(value: &dto::User) -> Self {
+        GetUserResponse {
+            id: value.id,
+            first_name: value.first_name.clone(),
+            last_name: value.last_name.clone(),
+            email: value.email.clone(),
+            external_id: value.external_id.clone(),
+            external_id_source: value.external_id_source.clone(),
+            role_id: value.role_id,
+            active: value.active,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct CurrentUserResponse {
+    #[serde(flatten)]
+    pub user: GetUserResponse,
+    #[schema(value_type = Vec<String>)]
+    pub scopes: Vec<&'static str>,
+}
+
+impl CurrentUserResponse {
+    pub fn new(user: &dto::User, scopes: &[dto::Scope]) -> Self {
+        CurrentUserResponse {
+            user: GetUserResponse::from(user),
+            scopes: scopes.iter().map(|x| x.as_str()).collect(),
+        }
+    }
+}
+
+/// Registers someone ahead of their first sign in. `external_id` and `external_id_source` are
+/// the `sub` and `iss` their identity provider puts in tokens.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, ToSchema)]
+pub struct CreateUserRequest {
+    pub first_name: String,
+    pub last_name: String,
+    pub email: String,
+    pub external_id: String,
+    pub external_id_source: String,
+    pub role_id: Option<Uuid>,
+}
+
+impl From<&CreateUserRequest> for dto::User {
+    fn from(value: &CreateUserRequest) -> Self {
+        dto::User {
+            id: Uuid::nil(),
+            first_name: value.first_name.clone(),
+            last_name: value.last_name.clone(),
+            email: value.email.clone(),
+            external_id: value.external_id.clone(),
+            external_id_source: value.external_id_source.clone(),
+            role_id: value.role_id,
+            active: true,
+        }
+    }
+}
+
+/// Assigns a role, or removes it when `role_id` is null.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, ToSchema)]
+pub struct SetUserRoleRequest {
+    pub role_id: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, ToSchema)]
+pub struct CreateRoleRequest {
+    pub name: String,
+    pub scopes: Vec<String>,
+}
+
+impl CreateRoleRequest {
+    /// Parses the scopes, listing every one that is not part of the vocabulary.
+    pub fn to_dto(&self) -> Result<dto::Role, Vec<String>> {
+        let (scopes, unknown): (Vec<_>, Vec<_>) = self
+            .scopes
+            .iter()
+            .map(|x| x.parse::<dto::Scope>().map_err(|_| x.clone()))
+            .partition(Result::is_ok);
+        if !unknown.is_empty() {
+            return Err(unknown.into_iter().filter_map(Result::err).collect());
+        }
+        let mut scopes: Vec<dto::Scope> = scopes.into_iter().filter_map(Result::ok).collect();
+        scopes.sort();
+        scopes.dedup();
+        Ok(dto::Role {
+            id: Uuid::nil(),
+            name: self.name.clone(),
+            scopes,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct RoleResponse {
+    pub id: Uuid,
+    pub name: String,
+    #[schema(value_type = Vec<String>)]
+    pub scopes: Vec<&'static str>,
+}
+
+impl From<&dto::Role> for RoleResponse {
+    fn from(value: &dto::Role) -> Self {
+        RoleResponse {
+            id: value.id,
+            name: value.name.clone(),
+            scopes: value.scopes.iter().map(|x| x.as_str()).collect(),
+        }
+    }
+}
@@ -0,0 +1,209 @@
+use allocations_rest::http::{AppState, AuthConfig, TokenKeySource, router};
+use axum::Router;
+use axum::body::Body;
+use axum::http::header::{AUTHORIZATION, WWW_AUTHENTICATE};
+use axum::http::{Request, StatusCode};
+use domain::dto::{self, Scope};
+use http_body_util::BodyExt;
+use jsonwebtoken::{EncodingKey, Header, encode};
+use repository::in_memory_db::InMemoryDb;
+use repository::role_repo::RoleRepo;
+use repository::user_repo::UserRepo;
+use serde_json::{Value, json};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+const SECRET: &str = "auth-tests-secret";
+const ISSUER: &str = "https://issuer.example";
+
+fn app(keys: TokenKeySource) -> (InMemoryDb, Router) {
+    let db = InMemoryDb::new();
+    let auth = AuthConfig {
+        keys,
+        issuer: Some(ISSUER.to_string()),
+        audience: None,
+    };
+    let router = router(AppState::new(db.clone(), &auth).unwrap());
+    (db, router)
+}
+
+/// Registers `subject` with a role granting `scopes` and returns their user.
+async fn register(db: &InMemoryDb, subject: &str, scopes: &[Scope]) -> dto::User {
+    let role = RoleRepo::create(
+        db,
+        Uuid::nil(),
+        &dto::Role {
+            id: Uuid::nil(),
+            name: format!("role-{}", subject),
+            scopes: scopes.to_vec(),
+        },
+    )
+    .await
+    .unwrap();
+    UserRepo::create(
+        db,
+        Uuid::nil(),
+        &dto::User {
+            id: Uuid::nil(),
+            first_name: "Test".to_string(),
+            last_name: "User".to_string(),
+            email: format!("{}@example.com", subject),
+            external_id: subject.to_string(),
+            external_id_source: ISSUER.to_string(),
+            role_id: Some(role.id),
+            active: true,
+        },
+    )
+    .await
+    .unwrap()
+}
+
+/// A token for `subject` that expires `expires_in` seconds from now, signed with `SECRET`.
+fn token(subject: &str, expires_in: i64) -> String {
+    let claims = json!({
+        "sub": subject,
+        "iss": ISSUER,
+        "exp": chrono::Utc::now().timestamp() + expires_in,
+    });
+    encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(SECRET.as_bytes()),
+    )
+    .unwrap()
+}
+
+async fn get(
+    router: &Router,
+    uri: &str,
+    token: Option<&str>,
+) -> (StatusCode, Option<String>, Value) {
+    let mut request = Request::get(uri);
+    if let Some(token) = token {
+        request = request.header(AUTHORIZATION, format!("Bearer {}", token));
+    }
+    let response = router
+        .clone()
+        .oneshot(request.body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let challenge = response
+        .headers()
+        .get(WWW_AUTHENTICATE)
+        .map(|x| x.to_str().unwrap().to_string());
+    let status = response.status();
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    (status, challenge, serde_json::from_slice(&bytes).unwrap())
+}
+
+/// Asserts the request was turned away before reaching a handler.
+fn assert_unauthenticated(reply: (StatusCode, Option<String>, Value)) {
+    let (status, challenge, body) = reply;
+    assert_eq!(status, StatusCode::UNAUTHORIZED, "{}", body);
+    assert_eq!(challenge.as_deref(), Some("Bearer"));
+    assert_eq!(body["code"], "unauthenticated");
+}
+
+#[tokio::test]
+async fn test_valid_token_resolves_the_user() {
+    let (db, router) = app(TokenKeySource::Secret(SECRET.to_string()));
+    let user = register(&db, "ada", &[Scope::EmployeesRead]).await;
+
+    let (status, _, body) = get(&router, "/api/me", Some(&token("ada", 3600))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["id"], user.id.to_string());
+    assert_eq!(body["data"]["scopes"], json!(["employees:read"]));
+}
+
+#[tokio::test]
+async fn test_missing_token_is_unauthenticated() {
+    let (db, router) = app(TokenKeySource::Secret(SECRET.to_string()));
+    register(&db, "ada", &Scope::ALL).await;
+
+    assert_unauthenticated(get(&router, "/api/me", None).await);
+    assert_unauthenticated(get(&router, "/api/employees", Some("")).await);
+}
+
+#[tokio::test]
+async fn test_invalid_tokens_are_unauthenticated() {
+    let (db, router) = app(TokenKeySource::Secret(SECRET.to_string()));
+    register(&db, "ada", &Scope::ALL).await;
+
+    // Well past the leeway allowed for clock skew.
+    let expired = token("ada", -3600);
+    assert_unauthenticated(get(&router, "/api/me", Some(&expired)).await);
+
+    let forged = encode(
+        &Header::default(),
+        &json!({ "sub": "ada", "iss": ISSUER, "exp": chrono::Utc::now().timestamp() + 3600 }),
+        &EncodingKey::from_secret(b"someone else's secret"),
+    )
+    .unwrap();
+    assert_unauthenticated(get(&router, "/api/me", Some(&forged)).await);
+
+    assert_unauthenticated(get(&router, "/api/me", Some("not-a-jwt")).await);
+}
+
+#[tokio::test]
+async fn test_unknown_subject_is_unauthenticated() {
+    let (db, router) = app(TokenKeySource::Secret(SECRET.to_string()));
+    register(&db, "ada", &Scope::ALL).await;
+
+    assert_unauthenticated(get(&router, "/api/me", Some(&token("grace", 3600))).await);
+}
+
+#[tokio::test]
+async fn test_deactivated_user_is_unauthenticated() {
+    let (db, router) = app(TokenKeySource::Secret(SECRET.to_string()));
+    let user = register(&db, "ada", &Scope::ALL).await;
+    let token = token("ada", 3600);
+    assert_eq!(
+        get(&router, "/api/me", Some(&token)).await.0,
+        StatusCode::OK
+    );
+
+    db.deactivate(Uuid::nil(), user.id).await.unwrap();
+    assert_unauthenticated(get(&router, "/api/me", Some(&token)).await);
+}
+
+#[tokio::test]
+async fn test_missing_scope_is_forbidden() {
+    let (db, router) = app(TokenKeySource::Secret(SECRET.to_string()));
+    register(&db, "ada", &[Scope::AllocationsRead]).await;
+
+    let (status, challenge, body) = get(&router, "/api/employees", Some(&token("ada", 3600))).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(challenge, None);
+    assert_eq!(body["code"], "missing_scope");
+    assert_eq!(body["detail"], "Missing scope employees:read");
+
+    let (status, _, _) = get(&router, "/api/initiatives", Some(&token("ada", 3600))).await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_key_set_picks_a_key_for_the_token_algorithm() {
+    // Neither key names a kid, so the HMAC token must skip past the EC key to the one it fits.
+    let jwks = json!({
+        "keys": [
+            {
+                "kty": "EC",
+                "crv": "P-256",
+                "x": "POARphzHRyDGSl43wqyCWpJ_T5iCfL46w94oSy9xS60",
+                "y": "o9rgdD9SI7ROldENvIRiu5LcRdHqafj-wARPoBSe18Y"
+            },
+            {
+                "kty": "oct",
+                "k": "YXV0aC10ZXN0cy1zZWNyZXQ"
+            }
+        ]
+    });
+    let path = std::env::temp_dir().join(format!("jwks-{}.json", Uuid::now_v7()));
+    std::fs::write(&path, jwks.to_string()).unwrap();
+    let (db, router) = app(TokenKeySource::JwksFile(path.display().to_string()));
+    std::fs::remove_file(&path).unwrap();
+    register(&db, "ada", &Scope::ALL).await;
+
+    let (status, _, body) = get(&router, "/api/me", Some(&token("ada", 3600))).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+}
@@ -0,0 +1,91 @@
+use allocations_rest::models::employee::{csv_line, parse_employees_csv};
+use bigdecimal::BigDecimal;
+
+const HEADER: &str = "first_name,last_name,email,salary\n";
+
+fn lines(body: &str) -> Vec<usize> {
+    parse_employees_csv(body.as_bytes())
+        .unwrap()
+        .iter()
+        .map(|x| x.line)
+        .collect()
+}
+
+#[test]
+fn test_header_only_file_has_no_rows() {
+    assert_eq!(parse_employees_csv(HEADER.as_bytes()).unwrap(), vec![]);
+    assert_eq!(
+        parse_employees_csv(HEADER.trim_end().as_bytes()).unwrap(),
+        vec![]
+    );
+}
+
+#[test]
+fn test_wrong_header_is_rejected() {
+    assert_eq!(
+        parse_employees_csv(b"first_name,last_name,email\nAda,Lovelace,ada@example.com\n"),
+        Err("The header must be first_name,last_name,email,salary".to_string())
+    );
+}
+
+#[test]
+fn test_blank_lines_between_rows_keep_line_numbers() {
+    let body = format!(
+        "{}Ada,Lovelace,ada@example.com,100\n\n\nGrace,Hopper,grace@example.com,200\r\n\r\nAlan,Turing,alan@example.com,300\n\n",
+        HEADER
+    );
+    assert_eq!(lines(&body), vec![2, 5, 7]);
+
+    let rows = parse_employees_csv(body.as_bytes()).unwrap();
+    let grace = rows[1].employee.as_ref().unwrap();
+    assert_eq!(grace.email, "grace@example.com");
+    assert_eq!(grace.salary, "200".parse::<BigDecimal>().unwrap());
+}
+
+#[test]
+fn test_quoted_newline_stays_in_its_field() {
+    let body = format!(
+        "{}\"Ada\nAugusta\",Lovelace,ada@example.com,100\nGrace,Hopper,grace@example.com,200\n",
+        HEADER
+    );
+    let rows = parse_employees_csv(body.as_bytes()).unwrap();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0].line, 2);
+    assert_eq!(
+        rows[0].employee.as_ref().unwrap().first_name,
+        "Ada\nAugusta"
+    );
+    // The second record starts after the line break inside the quotes.
+    assert_eq!(rows[1].line, 4);
+}
+
+#[test]
+fn test_bad_rows_are_reported_on_their_line() {
+    let body = format!(
+        "{}Ada,Lovelace,ada@example.com\n\nGrace,Hopper,grace@example.com,lots\n",
+        HEADER
+    );
+    let rows = parse_employees_csv(body.as_bytes()).unwrap();
+    assert_eq!(rows[0].line, 2);
+    let errors = rows[0].employee.as_ref().unwrap_err();
+    assert_eq!(errors[0].field, "row");
+    assert_eq!(rows[1].line, 4);
+    let errors = rows[1].employee.as_ref().unwrap_err();
+    assert!(errors.iter().any(|x| x.field == "salary"));
+}
+
+#[test]
+fn test_csv_line_counts_from_one() {
+    let body = b"\n\na,b\nc,d\n";
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .from_reader(&body[..]);
+    let records = reader.records().collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(
+        records
+            .iter()
+            .map(|x| csv_line(body, x))
+            .collect::<Vec<_>>(),
+        vec![3, 4]
+    );
+}
@@ -0,0 +1,164 @@
+use allocations_rest::models::export::{
+    Cell, allocation_columns, allocation_row, csv_chunk, employee_columns, employee_row,
+    xlsx_workbook,
+};
+use bigdecimal::BigDecimal;
+use calamine::{Data, DataType, Reader, Xlsx};
+use chrono::NaiveDate;
+use domain::dto;
+use std::io::Cursor;
+use uuid::Uuid;
+
+fn dec(value: &str) -> BigDecimal {
+    value.parse::<BigDecimal>().unwrap()
+}
+
+fn employee(email: &str, terminated_on: Option<NaiveDate>) -> dto::Employee {
+    dto::Employee {
+        id: Uuid::now_v7(),
+        first_name: "Ada".to_string(),
+        last_name: "Lovelace, Countess".to_string(),
+        email: email.to_string(),
+        salary: dec("52000.50"),
+        version: 1,
+        terminated_on,
+    }
+}
+
+fn csv(header: Option<&[&str]>, rows: Vec<Vec<Option<Cell>>>) -> String {
+    String::from_utf8(csv_chunk(header, rows).unwrap()).unwrap()
+}
+
+#[test]
+fn test_employee_csv_has_header_and_rows() {
+    let left = NaiveDate::from_ymd_opt(2025, 3, 31).unwrap();
+    let ada = employee("ada@example.com", None);
+    let grace = employee("grace@example.com", Some(left));
+    let body = csv(
+        Some(&employee_columns(true)),
+        vec![employee_row(&ada, true), employee_row(&grace, true)],
+    );
+    assert_eq!(
+        body,
+        format!(
+            "id,first_name,last_name,email,salary,terminated_on\n\
+             {},Ada,\"Lovelace, Countess\",ada@example.com,52000.5,\n\
+             {},Ada,\"Lovelace, Countess\",grace@example.com,52000.5,2025-03-31\n",
+            ada.id, grace.id
+        )
+    );
+}
+
+#[test]
+fn test_employee_export_leaves_out_salary_without_the_scope() {
+    let ada = employee("ada@example.com", None);
+    assert_eq!(
+        employee_columns(false),
+        vec!["id", "first_name", "last_name", "email", "terminated_on"]
+    );
+    let row = employee_row(&ada, false);
+    assert_eq!(row.len(), employee_columns(false).len());
+    assert!(!row.contains(&Some(Cell::Number(ada.salary.clone()))));
+
+    let body = csv(Some(&employee_columns(false)), vec![row]);
+    assert!(!body.contains("salary"));
+    assert!(!body.contains("52000"));
+}
+
+#[test]
+fn test_csv_chunks_concatenate_into_one_file() {
+    let ada = employee("ada@example.com", None);
+    let grace = employee("grace@example.com", None);
+    let whole = csv(
+        Some(&employee_columns(true)),
+        vec![employee_row(&ada, true), employee_row(&grace, true)],
+    );
+    let head = csv(
+        Some(&employee_columns(true)),
+        vec![employee_row(&ada, true)],
+    );
+    let tail = csv(None, vec![employee_row(&grace, true)]);
+    assert_eq!(head + &tail, whole);
+}
+
+#[test]
+fn test_allocation_csv_has_a_column_per_month() {
+    let ada = employee("ada@example.com", None);
+    let initiative = dto::Initiative {
+        id: Uuid::now_v7(),
+        initiative: "Platform".to_string(),
+        version: 1,
+    };
+    let mut months: [BigDecimal; 12] = std::array::from_fn(|_| dec("0"));
+    months[0] = dec("0.500");
+    months[11] = dec("0.25");
+    let item = dto::PlannedAllocation {
+        employee: ada.clone(),
+        initiative: initiative.clone(),
+        year: 2025,
+        months,
+    };
+    let row = allocation_row(&item);
+    assert_eq!(row.len(), allocation_columns().len());
+    assert_eq!(
+        csv(Some(&allocation_columns()), vec![row]),
+        format!(
+            "employee_id,email,first_name,last_name,initiative_id,initiative,year,\
+             jan,feb,mar,apr,may,jun,jul,aug,sep,oct,nov,dec\n\
+             {},ada@example.com,Ada,\"Lovelace, Countess\",{},Platform,2025,\
+             0.5,0,0,0,0,0,0,0,0,0,0,0.25\n",
+            ada.id, initiative.id
+        )
+    );
+}
+
+#[test]
+fn test_xlsx_workbook_reads_back() {
+    let left = NaiveDate::from_ymd_opt(2025, 3, 31).unwrap();
+    let ada = employee("ada@example.com", None);
+    let grace = employee("grace@example.com", Some(left));
+    let body = xlsx_workbook(
+        "Employees",
+        &employee_columns(true),
+        [employee_row(&ada, true), employee_row(&grace, true)],
+    )
+    .unwrap();
+
+    let mut workbook = Xlsx::new(Cursor::new(body)).unwrap();
+    assert_eq!(workbook.sheet_names(), vec!["Employees"]);
+    let range = workbook.worksheet_range("Employees").unwrap();
+    let rows: Vec<&[Data]> = range.rows().collect();
+    assert_eq!(rows.len(), 3);
+    assert_eq!(
+        rows[0],
+        employee_columns(true)
+            .into_iter()
+            .map(|x| Data::String(x.to_string()))
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(rows[1][0], Data::String(ada.id.to_string()));
+    assert_eq!(rows[1][3], Data::String("ada@example.com".to_string()));
+    // Numbers and dates keep their type rather than becoming text.
+    assert_eq!(rows[1][4], Data::Float(52000.5));
+    assert_eq!(rows[1][5], Data::Empty);
+    assert_eq!(rows[2][0], Data::String(grace.id.to_string()));
+    // Stored as Excel's day number, counted from 1899-12-30.
+    let stored = rows[2][5].get_datetime().unwrap();
+    assert!(stored.is_datetime());
+    let epoch = NaiveDate::from_ymd_opt(1899, 12, 30).unwrap();
+    assert_eq!(stored.as_f64(), (left - epoch).num_days() as f64);
+}
+
+#[test]
+fn test_xlsx_workbook_rejects_oversized_text() {
+    let mut ada = employee("ada@example.com", None);
+    ada.first_name = "a".repeat(40_000);
+    assert!(
+        xlsx_workbook(
+            "Employees",
+            &employee_columns(true),
+            [employee_row(&ada, true)]
+        )
+        .is_err()
+    );
+}
@@ -0,0 +1,19 @@
+use std::process::Command;
+
+const COMMITTED_SPEC: &str = include_str!("../openapi.json");
+
+#[test]
+fn test_committed_openapi_spec_is_current() {
+    let output = Command::new(env!("CARGO_BIN_EXE_allocations-rest"))
+        .arg("openapi")
+        .output()
+        .expect("failed to run allocations-rest");
+    assert!(output.status.success());
+
+    let generated = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        generated.trim() == COMMITTED_SPEC.trim(),
+        "rest/openapi.json is out of date; regenerate it with \
+         `cargo run -p allocations-rest -- openapi > rest/openapi.json`"
+    );
+}
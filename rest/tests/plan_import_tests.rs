@@ -0,0 +1,97 @@
+use allocations_rest::models::export::ExportFormat;
+use allocations_rest::models::plan::parse_plan;
+use bigdecimal::BigDecimal;
+use domain::dto;
+
+const MONTHS: &str = "jan,feb,mar,apr,may,jun,jul,aug,sep,oct,nov,dec";
+
+fn dec(value: &str) -> BigDecimal {
+    value.parse::<BigDecimal>().unwrap()
+}
+
+fn parse(body: &str) -> Result<Vec<(usize, dto::PlanRow)>, String> {
+    Ok(parse_plan(body.as_bytes(), ExportFormat::Csv, 2026)?
+        .into_iter()
+        .map(|x| (x.line, x.row.unwrap()))
+        .collect())
+}
+
+#[test]
+fn test_long_layout_has_one_initiative_per_row() {
+    let body = format!(
+        "employee_id,email,initiative,year,{MONTHS}\n\
+         1,ada@example.com,Platform,2026,0.5,,,,,,,,,,,0.25\n"
+    );
+    let rows = parse(&body).unwrap();
+    assert_eq!(rows.len(), 1);
+    let (line, row) = &rows[0];
+    assert_eq!(*line, 2);
+    assert_eq!(row.email, "ada@example.com");
+    assert_eq!(row.initiative, "Platform");
+    assert_eq!(row.months[0], Some(dec("0.5")));
+    assert_eq!(row.months[1], None);
+    assert_eq!(row.months[11], Some(dec("0.25")));
+}
+
+#[test]
+fn test_wide_layout_has_a_row_per_initiative_planned() {
+    let blank = ",".repeat(11);
+    let body = format!(
+        "first_name,email,Platform{blank},Mobile{blank}\n\
+         ,,{MONTHS},{MONTHS}\n\
+         Ada,ada@example.com,0.5{blank},{blank}0.25\n\
+         Grace,grace@example.com,{blank},{blank}\n\
+         \n\
+         Alan,alan@example.com,1{blank},{blank}\n"
+    );
+    let rows = parse(&body).unwrap();
+    let found: Vec<_> = rows
+        .iter()
+        .map(|(line, x)| (*line, x.email.as_str(), x.initiative.as_str()))
+        .collect();
+    // Grace plans nothing, and Alan is only on Platform.
+    assert_eq!(
+        found,
+        vec![
+            (3, "ada@example.com", "Platform"),
+            (3, "ada@example.com", "Mobile"),
+            (6, "alan@example.com", "Platform"),
+        ]
+    );
+    assert_eq!(rows[0].1.months[0], Some(dec("0.5")));
+    assert_eq!(rows[0].1.months[11], None);
+    assert_eq!(rows[1].1.months[0], None);
+    assert_eq!(rows[1].1.months[11], Some(dec("0.25")));
+}
+
+#[test]
+fn test_wide_layout_reports_bad_months_on_their_line() {
+    let blank = ",".repeat(11);
+    let body = format!(
+        "email,Platform{blank}\n\
+         ,{MONTHS}\n\
+         ada@example.com,lots{blank}\n"
+    );
+    let rows = parse_plan(body.as_bytes(), ExportFormat::Csv, 2026).unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].line, 3);
+    assert_eq!(rows[0].row.as_ref().unwrap_err()[0].field, "jan");
+}
+
+#[test]
+fn test_wide_layout_needs_every_month_under_each_initiative() {
+    let blank = ",".repeat(10);
+    let body = format!(
+        "email,Platform{blank}\n\
+         ,jan,feb,mar,apr,may,jun,jul,aug,sep,oct,nov\n"
+    );
+    assert_eq!(
+        parse(&body),
+        Err("The months under Platform must be jan to dec".to_string())
+    );
+    assert!(
+        parse("email,Platform\nada@example.com,0.5\n")
+            .unwrap_err()
+            .starts_with("The header must name the columns email,initiative,jan")
+    );
+}
@@ -0,0 +1,1174 @@
+use allocations_rest::http::{AppState, AuthConfig, TokenKeySource, router};
+use axum::Router;
+use axum::body::Body;
+use axum::http::header::{AUTHORIZATION, CONTENT_TYPE, ETAG, IF_MATCH};
+use axum::http::{Method, Request, StatusCode};
+use bigdecimal::BigDecimal;
+use domain::dto::{self, Scope};
+use http_body_util::BodyExt;
+use jsonwebtoken::{EncodingKey, Header, encode};
+use repository::employee_assignment_repo::EmployeeAssignmentRepo;
+use repository::employee_repo::EmployeeRepo;
+use repository::in_memory_db::InMemoryDb;
+use repository::role_repo::RoleRepo;
+use repository::user_repo::UserRepo;
+use serde_json::{Value, json};
+use service::allocation_service::MAX_PAGE_SIZE;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+const SECRET: &str = "router-tests-secret";
+const ISSUER: &str = "https://issuer.example";
+
+/// The whole application over an in-memory repository.
+struct TestApp {
+    db: InMemoryDb,
+    router: Router,
+}
+
+/// What the application answered; bodies that are not JSON are kept as a string.
+struct Reply {
+    status: StatusCode,
+    etag: Option<String>,
+    content_type: Option<String>,
+    body: Value,
+}
+
+impl TestApp {
+    fn new() -> Self {
+        let db = InMemoryDb::new();
+        let auth = AuthConfig {
+            keys: TokenKeySource::Secret(SECRET.to_string()),
+            issuer: None,
+            audience: None,
+        };
+        let router = router(AppState::new(db.clone(), &auth).unwrap());
+        TestApp { db, router }
+    }
+
+    /// Registers a user whose role grants `scopes` and returns a bearer token for them.
+    async fn login(&self, scopes: &[Scope]) -> String {
+        let role = RoleRepo::create(
+            &self.db,
+            Uuid::nil(),
+            &dto::Role {
+                id: Uuid::nil(),
+                name: format!("role-{}", Uuid::now_v7()),
+                scopes: scopes.to_vec(),
+            },
+        )
+        .await
+        .unwrap();
+        let subject = Uuid::now_v7().to_string();
+        UserRepo::create(
+            &self.db,
+            Uuid::nil(),
+            &dto::User {
+                id: Uuid::nil(),
+                first_name: "Test".to_string(),
+                last_name: "User".to_string(),
+                email: format!("{}@example.com", subject),
+                external_id: subject.clone(),
+                external_id_source: ISSUER.to_string(),
+                role_id: Some(role.id),
+                active: true,
+            },
+        )
+        .await
+        .unwrap();
+        token(&subject)
+    }
+
+    async fn send(
+        &self,
+        method: Method,
+        uri: &str,
+        token: &str,
+        if_match: Option<i32>,
+        body: Option<Value>,
+    ) -> Reply {
+        let mut request = Request::builder()
+            .method(method.clone())
+            .uri(uri)
+            .header(AUTHORIZATION, format!("Bearer {}", token));
+        if let Some(version) = if_match {
+            request = request.header(IF_MATCH, format!("\"{}\"", version));
+        }
+        let request = match body {
+            Some(body) => {
+                let content_type = if method == Method::PATCH {
+                    "application/merge-patch+json"
+                } else {
+                    "application/json"
+                };
+                request
+                    .header(CONTENT_TYPE, content_type)
+                    .body(Body::from(body.to_string()))
+            }
+            None => request.body(Body::empty()),
+        };
+        self.call(request.unwrap()).await
+    }
+
+    /// Posts a file upload such as a CSV import.
+    async fn upload(&self, uri: &str, token: &str, content_type: &str, body: &str) -> Reply {
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri(uri)
+            .header(AUTHORIZATION, format!("Bearer {}", token))
+            .header(CONTENT_TYPE, content_type)
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        self.call(request).await
+    }
+
+    async fn get(&self, uri: &str, token: &str) -> Reply {
+        self.send(Method::GET, uri, token, None, None).await
+    }
+
+    async fn call(&self, request: Request<Body>) -> Reply {
+        let response = self.router.clone().oneshot(request).await.unwrap();
+        let header = |name| {
+            response
+                .headers()
+                .get(name)
+                .map(|x: &axum::http::HeaderValue| x.to_str().unwrap().to_string())
+        };
+        let etag = header(ETAG);
+        let content_type = header(CONTENT_TYPE);
+        let status = response.status();
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        let body = serde_json::from_slice(&bytes)
+            .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(&bytes).into_owned()));
+        Reply {
+            status,
+            etag,
+            content_type,
+            body,
+        }
+    }
+
+    async fn create_employee(&self, token: &str, email: &str) -> Value {
+        let reply = self
+            .send(
+                Method::POST,
+                "/api/employees",
+                token,
+                None,
+                Some(json!({
+                    "first_name": "Ada",
+                    "last_name": "Lovelace",
+                    "email": email,
+                    "salary": 50000
+                })),
+            )
+            .await;
+        assert_eq!(reply.status, StatusCode::CREATED);
+        reply.body["data"].clone()
+    }
+
+    async fn create_initiative(&self, token: &str, name: &str) -> Value {
+        let reply = self
+            .send(
+                Method::POST,
+                "/api/initiatives",
+                token,
+                None,
+                Some(json!({ "initiative": name })),
+            )
+            .await;
+        assert_eq!(reply.status, StatusCode::CREATED);
+        reply.body["data"].clone()
+    }
+
+    async fn create_curve(&self, token: &str, year: i32) -> Value {
+        let reply = self
+            .send(
+                Method::POST,
+                "/api/assignments",
+                token,
+                None,
+                Some(json!({ "year": year, "months": { "jan": 1.0, "feb": 0.5 } })),
+            )
+            .await;
+        assert_eq!(reply.status, StatusCode::CREATED);
+        reply.body["data"].clone()
+    }
+}
+
+fn token(subject: &str) -> String {
+    let claims = json!({
+        "sub": subject,
+        "iss": ISSUER,
+        "exp": chrono::Utc::now().timestamp() + 3600,
+    });
+    encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(SECRET.as_bytes()),
+    )
+    .unwrap()
+}
+
+fn id(data: &Value) -> &str {
+    data["id"].as_str().unwrap()
+}
+
+fn version(data: &Value) -> i32 {
+    data["version"].as_i64().unwrap() as i32
+}
+
+/// Asserts `reply` is a problem response with `status` and `code`.
+fn assert_problem(reply: &Reply, status: StatusCode, code: &str) {
+    assert_eq!(reply.status, status, "{}", reply.body);
+    assert_eq!(
+        reply.content_type.as_deref(),
+        Some("application/problem+json")
+    );
+    assert_eq!(reply.body["status"], status.as_u16());
+    assert_eq!(reply.body["code"], code);
+}
+
+#[tokio::test]
+async fn test_health_and_openapi_need_no_token() {
+    let app = TestApp::new();
+    for uri in ["/health", "/api/openapi.json"] {
+        let reply = app
+            .call(Request::get(uri).body(Body::empty()).unwrap())
+            .await;
+        assert_eq!(reply.status, StatusCode::OK);
+    }
+}
+
+#[tokio::test]
+async fn test_current_user_lists_their_scopes() {
+    let app = TestApp::new();
+    let token = app.login(&[Scope::EmployeesRead, Scope::AuditRead]).await;
+
+    let reply = app.get("/api/me", &token).await;
+    assert_eq!(reply.status, StatusCode::OK);
+    assert_eq!(
+        reply.body["data"]["scopes"],
+        json!(["employees:read", "audit:read"])
+    );
+}
+
+#[tokio::test]
+async fn test_employee_salary_needs_salary_scopes() {
+    let app = TestApp::new();
+    let finance = app.login(&Scope::ALL).await;
+    let reader = app.login(&[Scope::EmployeesRead]).await;
+    let writer = app
+        .login(&[Scope::EmployeesRead, Scope::EmployeesWrite])
+        .await;
+
+    let created = app.create_employee(&finance, "ada@example.com").await;
+    assert!(created.get("salary").is_some());
+
+    let reply = app
+        .get(&format!("/api/employees/{}", id(&created)), &reader)
+        .await;
+    assert_eq!(reply.status, StatusCode::OK);
+    assert_eq!(reply.etag.as_deref(), Some("\"1\""));
+    assert_eq!(reply.body["data"]["email"], "ada@example.com");
+    assert!(reply.body["data"].get("salary").is_none());
+
+    let reply = app
+        .get(
+            &format!("/api/employees/{}/salaries", id(&created)),
+            &reader,
+        )
+        .await;
+    assert_problem(&reply, StatusCode::FORBIDDEN, "missing_scope");
+
+    let reply = app
+        .send(
+            Method::POST,
+            "/api/employees",
+            &writer,
+            None,
+            Some(json!({
+                "first_name": "Grace",
+                "last_name": "Hopper",
+                "email": "grace@example.com",
+                "salary": 60000
+            })),
+        )
+        .await;
+    assert_problem(&reply, StatusCode::FORBIDDEN, "missing_scope");
+
+    let reply = app
+        .send(
+            Method::PATCH,
+            &format!("/api/employees/{}", id(&created)),
+            &writer,
+            Some(1),
+            Some(json!({ "salary": 1 })),
+        )
+        .await;
+    assert_problem(&reply, StatusCode::FORBIDDEN, "missing_scope");
+}
+
+#[tokio::test]
+async fn test_routes_require_their_scope() {
+    let app = TestApp::new();
+    let nobody = app.login(&[]).await;
+
+    for uri in [
+        "/api/employees",
+        "/api/initiatives",
+        "/api/tags",
+        "/api/assignments",
+        "/api/allocations/export?year=2025",
+        "/api/reports/overallocated?year=2025",
+        "/api/admin/users",
+        "/api/audit?entity=employee",
+    ] {
+        let reply = app.get(uri, &nobody).await;
+        assert_problem(&reply, StatusCode::FORBIDDEN, "missing_scope");
+    }
+}
+
+#[tokio::test]
+async fn test_problem_responses_carry_stable_codes() {
+    let app = TestApp::new();
+    let token = app.login(&Scope::ALL).await;
+    app.create_employee(&token, "ada@example.com").await;
+
+    let reply = app.get("/api/employees/not-a-uuid", &token).await;
+    assert_problem(&reply, StatusCode::BAD_REQUEST, "invalid_id");
+
+    let reply = app
+        .get(&format!("/api/employees/{}", Uuid::now_v7()), &token)
+        .await;
+    assert_problem(&reply, StatusCode::NOT_FOUND, "not_found");
+
+    let reply = app.get("/api/no-such-route", &token).await;
+    assert_problem(&reply, StatusCode::NOT_FOUND, "not_found");
+
+    let reply = app
+        .send(
+            Method::POST,
+            "/api/employees",
+            &token,
+            None,
+            Some(json!({
+                "first_name": "Ada",
+                "last_name": "Byron",
+                "email": "ada@example.com",
+                "salary": 50000
+            })),
+        )
+        .await;
+    assert_problem(&reply, StatusCode::CONFLICT, "duplicate");
+
+    let reply = app
+        .send(
+            Method::POST,
+            "/api/employees",
+            &token,
+            None,
+            Some(json!({ "first_name": "", "last_name": "X", "email": "nope", "salary": -1 })),
+        )
+        .await;
+    assert_problem(
+        &reply,
+        StatusCode::UNPROCESSABLE_ENTITY,
+        "validation_failed",
+    );
+    let fields: Vec<&str> = reply.body["invalid_params"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|x| x["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(fields, ["first_name", "email", "salary"]);
+
+    let reply = app.get("/api/reports/overallocated", &token).await;
+    assert_problem(&reply, StatusCode::BAD_REQUEST, "malformed_request");
+}
+
+#[tokio::test]
+async fn test_internal_errors_do_not_leak_their_cause() {
+    let app = TestApp::new();
+    let token = app.login(&Scope::ALL).await;
+    let employee = app.create_employee(&token, "ada@example.com").await;
+    // Longer than a workbook cell can hold, so rendering the export fails.
+    let initiative = app.create_initiative(&token, &"x".repeat(40_000)).await;
+    let curve = app.create_curve(&token, 2025).await;
+    let reply = app
+        .send(
+            Method::POST,
+            &format!("/api/employees/{}/assignments", id(&employee)),
+            &token,
+            None,
+            Some(json!({
+                "assignment_id": id(&curve),
+                "initiative_id": id(&initiative),
+                "allocation": 0.5
+            })),
+        )
+        .await;
+    assert_eq!(reply.status, StatusCode::CREATED);
+
+    let reply = app
+        .get("/api/allocations/export?year=2025&format=xlsx", &token)
+        .await;
+    assert_problem(&reply, StatusCode::INTERNAL_SERVER_ERROR, "internal");
+    assert_eq!(
+        reply.body["detail"],
+        "The server was unable to complete the request"
+    );
+}
+
+#[tokio::test]
+async fn test_writes_need_a_current_if_match() {
+    let app = TestApp::new();
+    let token = app.login(&Scope::ALL).await;
+    let created = app.create_employee(&token, "ada@example.com").await;
+    let uri = format!("/api/employees/{}", id(&created));
+    let body = json!({ "first_name": "Ada", "last_name": "King", "email": "ada@example.com" });
+
+    let reply = app
+        .send(Method::PUT, &uri, &token, None, Some(body.clone()))
+        .await;
+    assert_problem(
+        &reply,
+        StatusCode::PRECONDITION_REQUIRED,
+        "precondition_required",
+    );
+
+    let reply = app
+        .send(Method::PUT, &uri, &token, Some(1), Some(body.clone()))
+        .await;
+    assert_eq!(reply.status, StatusCode::OK);
+    assert_eq!(reply.etag.as_deref(), Some("\"2\""));
+    assert_eq!(reply.body["data"]["last_name"], "King");
+    assert_eq!(reply.body["data"]["version"], 2);
+
+    let reply = app
+        .send(Method::PUT, &uri, &token, Some(1), Some(body))
+        .await;
+    assert_problem(
+        &reply,
+        StatusCode::PRECONDITION_FAILED,
+        "precondition_failed",
+    );
+
+    let reply = app.send(Method::DELETE, &uri, &token, None, None).await;
+    assert_problem(
+        &reply,
+        StatusCode::PRECONDITION_REQUIRED,
+        "precondition_required",
+    );
+
+    let request = Request::patch(&uri)
+        .header(AUTHORIZATION, format!("Bearer {}", token))
+        .header(IF_MATCH, "W/\"2\"")
+        .header(CONTENT_TYPE, "application/merge-patch+json")
+        .body(Body::from("{}"))
+        .unwrap();
+    let reply = app.call(request).await;
+    assert_problem(&reply, StatusCode::BAD_REQUEST, "malformed_request");
+}
+
+#[tokio::test]
+async fn test_patch_merges_into_the_employee() {
+    let app = TestApp::new();
+    let token = app.login(&Scope::ALL).await;
+    let created = app.create_employee(&token, "ada@example.com").await;
+    let uri = format!("/api/employees/{}", id(&created));
+
+    let reply = app
+        .send(
+            Method::PATCH,
+            &uri,
+            &token,
+            Some(1),
+            Some(json!({ "email": "countess@example.com" })),
+        )
+        .await;
+    assert_eq!(reply.status, StatusCode::OK);
+    assert_eq!(reply.etag.as_deref(), Some("\"2\""));
+    let data = &reply.body["data"];
+    assert_eq!(data["email"], "countess@example.com");
+    assert_eq!(data["first_name"], "Ada");
+    assert_eq!(data["last_name"], "Lovelace");
+    assert_eq!(data["salary"], created["salary"]);
+
+    let reply = app
+        .send(
+            Method::PATCH,
+            &uri,
+            &token,
+            Some(2),
+            Some(json!({ "first_name": null })),
+        )
+        .await;
+    assert_problem(
+        &reply,
+        StatusCode::UNPROCESSABLE_ENTITY,
+        "validation_failed",
+    );
+
+    let reply = app
+        .send(
+            Method::PATCH,
+            &uri,
+            &token,
+            Some(2),
+            Some(json!({ "nickname": "Ada" })),
+        )
+        .await;
+    assert_problem(
+        &reply,
+        StatusCode::UNPROCESSABLE_ENTITY,
+        "validation_failed",
+    );
+
+    let reply = app
+        .send(
+            Method::PATCH,
+            &format!("/api/employees/{}", Uuid::now_v7()),
+            &token,
+            Some(1),
+            Some(json!({ "email": "ghost@example.com" })),
+        )
+        .await;
+    assert_problem(&reply, StatusCode::NOT_FOUND, "not_found");
+}
+
+#[tokio::test]
+async fn test_terminated_employees_are_kept_and_restorable() {
+    let app = TestApp::new();
+    let token = app.login(&Scope::ALL).await;
+    let created = app.create_employee(&token, "ada@example.com").await;
+    let uri = format!("/api/employees/{}", id(&created));
+
+    let reply = app
+        .send(
+            Method::DELETE,
+            &format!("{}?terminated_on=2024-06-30", uri),
+            &token,
+            Some(1),
+            None,
+        )
+        .await;
+    assert_eq!(reply.status, StatusCode::OK);
+    assert_eq!(reply.body["data"]["terminated_on"], "2024-06-30");
+
+    let reply = app.get("/api/employees", &token).await;
+    assert_eq!(reply.body["data"]["items"], json!([]));
+    let reply = app
+        .get("/api/employees?include_terminated=true", &token)
+        .await;
+    assert_eq!(reply.body["data"]["items"][0]["id"], created["id"]);
+    let reply = app.get(&uri, &token).await;
+    assert_eq!(reply.status, StatusCode::OK);
+
+    let reply = app
+        .send(
+            Method::POST,
+            &format!("{}/restore", uri),
+            &token,
+            Some(2),
+            None,
+        )
+        .await;
+    assert_eq!(reply.status, StatusCode::OK);
+    assert_eq!(reply.body["data"]["terminated_on"], Value::Null);
+
+    let reply = app
+        .send(
+            Method::POST,
+            &format!("{}/restore", uri),
+            &token,
+            Some(3),
+            None,
+        )
+        .await;
+    assert_problem(&reply, StatusCode::CONFLICT, "not_terminated");
+
+    let missing = format!("/api/employees/{}", Uuid::now_v7());
+    let reply = app
+        .send(Method::DELETE, &missing, &token, Some(1), None)
+        .await;
+    assert_problem(&reply, StatusCode::NOT_FOUND, "not_found");
+    let reply = app
+        .send(
+            Method::POST,
+            &format!("{}/restore", missing),
+            &token,
+            Some(1),
+            None,
+        )
+        .await;
+    assert_problem(&reply, StatusCode::NOT_FOUND, "not_found");
+}
+
+#[tokio::test]
+async fn test_initiatives_round_trip() {
+    let app = TestApp::new();
+    let token = app
+        .login(&[Scope::AllocationsRead, Scope::AllocationsWrite])
+        .await;
+    let reader = app.login(&[Scope::AllocationsRead]).await;
+
+    let created = app.create_initiative(&token, "Platform").await;
+    let uri = format!("/api/initiatives/{}", id(&created));
+
+    let reply = app
+        .send(
+            Method::POST,
+            "/api/initiatives",
+            &reader,
+            None,
+            Some(json!({ "initiative": "Mobile" })),
+        )
+        .await;
+    assert_problem(&reply, StatusCode::FORBIDDEN, "missing_scope");
+
+    let reply = app
+        .send(
+            Method::PUT,
+            &uri,
+            &token,
+            Some(version(&created)),
+            Some(json!({ "initiative": "Core platform" })),
+        )
+        .await;
+    assert_eq!(reply.status, StatusCode::OK);
+    assert_eq!(reply.body["data"]["initiative"], "Core platform");
+
+    let reply = app
+        .send(
+            Method::PUT,
+            &uri,
+            &token,
+            Some(version(&created)),
+            Some(json!({ "initiative": "Platform again" })),
+        )
+        .await;
+    assert_problem(
+        &reply,
+        StatusCode::PRECONDITION_FAILED,
+        "precondition_failed",
+    );
+
+    let reply = app.get("/api/initiatives", &reader).await;
+    assert_eq!(reply.status, StatusCode::OK);
+    assert_eq!(reply.body["data"].as_array().unwrap().len(), 1);
+
+    let reply = app
+        .send(
+            Method::DELETE,
+            &uri,
+            &token,
+            Some(version(&created) + 1),
+            None,
+        )
+        .await;
+    assert_eq!(reply.status, StatusCode::OK);
+    let reply = app.get(&uri, &reader).await;
+    assert_problem(&reply, StatusCode::NOT_FOUND, "not_found");
+}
+
+#[tokio::test]
+async fn test_tags_round_trip() {
+    let app = TestApp::new();
+    let token = app
+        .login(&[Scope::AllocationsRead, Scope::AllocationsWrite])
+        .await;
+
+    let reply = app
+        .send(
+            Method::POST,
+            "/api/tags",
+            &token,
+            None,
+            Some(json!({ "tag": "backend" })),
+        )
+        .await;
+    assert_eq!(reply.status, StatusCode::CREATED);
+    let tag = reply.body["data"].clone();
+
+    let reply = app.get("/api/tags", &token).await;
+    assert_eq!(reply.status, StatusCode::OK);
+    assert_eq!(reply.body["data"][0]["tag"], "backend");
+    assert_eq!(reply.body["data"][0]["assignment_count"], 0);
+
+    let reply = app
+        .send(
+            Method::PUT,
+            &format!("/api/tags/{}", id(&tag)),
+            &token,
+            None,
+            Some(json!({ "tag": "server" })),
+        )
+        .await;
+    assert_problem(
+        &reply,
+        StatusCode::PRECONDITION_REQUIRED,
+        "precondition_required",
+    );
+
+    let reply = app
+        .get(&format!("/api/tags/{}", Uuid::now_v7()), &token)
+        .await;
+    assert_problem(&reply, StatusCode::NOT_FOUND, "not_found");
+}
+
+#[tokio::test]
+async fn test_tag_merge_moves_usage_to_the_target() {
+    let app = TestApp::new();
+    let token = app.login(&Scope::ALL).await;
+    let mut tags = vec![];
+    for name in ["front-end", "frontend"] {
+        let reply = app
+            .send(
+                Method::POST,
+                "/api/tags",
+                &token,
+                None,
+                Some(json!({ "tag": name })),
+            )
+            .await;
+        tags.push(reply.body["data"].clone());
+    }
+    let source: Uuid = id(&tags[0]).parse().unwrap();
+    let target: Uuid = id(&tags[1]).parse().unwrap();
+    let both: Uuid = id(&app.create_curve(&token, 2025).await).parse().unwrap();
+    let one: Uuid = id(&app.create_curve(&token, 2025).await).parse().unwrap();
+    // The first curve carries both tags, so only one of its rows survives the merge.
+    app.db.tag_assignment(both, source);
+    app.db.tag_assignment(both, target);
+    app.db.tag_assignment(one, source);
+    let uri = format!("/api/tags/{}/merge", source);
+    let body = json!({ "into": target });
+
+    let reply = app
+        .send(Method::POST, &uri, &token, None, Some(body.clone()))
+        .await;
+    assert_problem(
+        &reply,
+        StatusCode::PRECONDITION_REQUIRED,
+        "precondition_required",
+    );
+    let reply = app
+        .send(Method::POST, &uri, &token, Some(2), Some(body.clone()))
+        .await;
+    assert_eq!(reply.status, StatusCode::PRECONDITION_FAILED);
+
+    let reply = app
+        .send(Method::POST, &uri, &token, Some(1), Some(body))
+        .await;
+    assert_eq!(reply.status, StatusCode::OK);
+    assert_eq!(reply.etag.as_deref(), Some("\"2\""));
+    assert_eq!(reply.body["data"]["assignment_count"], 2);
+
+    let reply = app.get("/api/tags", &token).await;
+    let listed = reply.body["data"].as_array().unwrap();
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0]["tag"], "frontend");
+    assert_eq!(listed[0]["assignment_count"], 2);
+
+    // Deleting the curve takes its tag with it.
+    let reply = app
+        .send(
+            Method::DELETE,
+            &format!("/api/assignments/{}", both),
+            &token,
+            Some(1),
+            None,
+        )
+        .await;
+    assert_eq!(reply.status, StatusCode::OK);
+    let reply = app.get(&format!("/api/tags/{}", target), &token).await;
+    assert_eq!(reply.body["data"]["assignment_count"], 1);
+}
+
+#[tokio::test]
+async fn test_allocation_curves_round_trip() {
+    let app = TestApp::new();
+    let token = app
+        .login(&[Scope::AllocationsRead, Scope::AllocationsWrite])
+        .await;
+
+    let curve = app.create_curve(&token, 2025).await;
+    app.create_curve(&token, 2026).await;
+    let uri = format!("/api/assignments/{}", id(&curve));
+
+    let reply = app.get("/api/assignments?year=2025", &token).await;
+    assert_eq!(reply.status, StatusCode::OK);
+    assert_eq!(reply.body["data"].as_array().unwrap().len(), 1);
+    assert_eq!(reply.body["data"][0]["months"]["jan"], 1.0);
+
+    let reply = app
+        .send(
+            Method::PUT,
+            &uri,
+            &token,
+            Some(version(&curve)),
+            Some(json!({ "year": 2025, "months": { "jan": 1.5 } })),
+        )
+        .await;
+    assert_problem(
+        &reply,
+        StatusCode::UNPROCESSABLE_ENTITY,
+        "validation_failed",
+    );
+
+    let reply = app
+        .send(Method::DELETE, &uri, &token, Some(version(&curve)), None)
+        .await;
+    assert_eq!(reply.status, StatusCode::OK);
+    let reply = app.get(&uri, &token).await;
+    assert_problem(&reply, StatusCode::NOT_FOUND, "not_found");
+}
+
+#[tokio::test]
+async fn test_employee_assignments_respect_capacity_and_departures() {
+    let app = TestApp::new();
+    let token = app.login(&Scope::ALL).await;
+    let employee = app.create_employee(&token, "ada@example.com").await;
+    let platform = app.create_initiative(&token, "Platform").await;
+    let mobile = app.create_initiative(&token, "Mobile").await;
+    let curve = app.create_curve(&token, 2025).await;
+    let links = format!("/api/employees/{}/assignments", id(&employee));
+    let link = |initiative: &Value, allocation: f64| {
+        json!({
+            "assignment_id": id(&curve),
+            "initiative_id": id(initiative),
+            "allocation": allocation
+        })
+    };
+
+    let reply = app
+        .send(
+            Method::POST,
+            &links,
+            &token,
+            None,
+            Some(link(&platform, 0.6)),
+        )
+        .await;
+    assert_eq!(reply.status, StatusCode::CREATED);
+    let created = reply.body["data"].clone();
+
+    let reply = app
+        .send(Method::POST, &links, &token, None, Some(link(&mobile, 0.6)))
+        .await;
+    assert_problem(&reply, StatusCode::UNPROCESSABLE_ENTITY, "overallocated");
+
+    let reply = app.get(&links, &token).await;
+    assert_eq!(reply.status, StatusCode::OK);
+    assert_eq!(reply.body["data"].as_array().unwrap().len(), 1);
+    let reply = app
+        .get(&format!("/api/initiatives/{}/staff", id(&platform)), &token)
+        .await;
+    assert_eq!(reply.body["data"][0]["employee_id"], employee["id"]);
+
+    let reply = app
+        .send(
+            Method::PUT,
+            &format!("{}/{}", links, id(&created)),
+            &token,
+            Some(version(&created) + 1),
+            Some(link(&platform, 0.4)),
+        )
+        .await;
+    assert_problem(
+        &reply,
+        StatusCode::PRECONDITION_FAILED,
+        "precondition_failed",
+    );
+
+    let reply = app
+        .send(
+            Method::DELETE,
+            &format!("{}/{}", links, id(&created)),
+            &token,
+            Some(version(&created)),
+            None,
+        )
+        .await;
+    assert_eq!(reply.status, StatusCode::OK);
+
+    let reply = app
+        .send(
+            Method::DELETE,
+            &format!("/api/employees/{}?terminated_on=2024-06-30", id(&employee)),
+            &token,
+            Some(version(&employee)),
+            None,
+        )
+        .await;
+    assert_eq!(reply.status, StatusCode::OK);
+    let reply = app
+        .send(Method::POST, &links, &token, None, Some(link(&mobile, 0.5)))
+        .await;
+    assert_problem(&reply, StatusCode::UNPROCESSABLE_ENTITY, "terminated");
+}
+
+#[tokio::test]
+async fn test_allocation_export_lists_effective_fractions() {
+    let app = TestApp::new();
+    let token = app.login(&Scope::ALL).await;
+    let employee = app.create_employee(&token, "ada@example.com").await;
+    let initiative = app.create_initiative(&token, "Platform").await;
+    let curve = app.create_curve(&token, 2025).await;
+    let reply = app
+        .send(
+            Method::POST,
+            &format!("/api/employees/{}/assignments", id(&employee)),
+            &token,
+            None,
+            Some(json!({
+                "assignment_id": id(&curve),
+                "initiative_id": id(&initiative),
+                "allocation": 0.5
+            })),
+        )
+        .await;
+    assert_eq!(reply.status, StatusCode::CREATED);
+
+    let reply = app.get("/api/allocations/export?year=2025", &token).await;
+    assert_eq!(reply.status, StatusCode::OK);
+    assert_eq!(
+        reply.content_type.as_deref(),
+        Some("text/csv; charset=utf-8")
+    );
+    let csv = reply.body.as_str().unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].starts_with("employee_id,email,"));
+    assert!(lines[1].ends_with(",Platform,2025,0.5,0.25,0,0,0,0,0,0,0,0,0,0"));
+
+    let reply = app.get("/api/allocations/export", &token).await;
+    assert_problem(&reply, StatusCode::BAD_REQUEST, "malformed_request");
+}
+
+#[tokio::test]
+async fn test_allocation_export_pages_through_employees() {
+    let app = TestApp::new();
+    let token = app.login(&Scope::ALL).await;
+    let initiative = app.create_initiative(&token, "Platform").await;
+    let curve = app.create_curve(&token, 2025).await;
+    let mut employees = vec![];
+    for n in 0..=MAX_PAGE_SIZE {
+        let employee = EmployeeRepo::create(
+            &app.db,
+            Uuid::nil(),
+            &dto::Employee {
+                id: Uuid::nil(),
+                first_name: "Ada".to_string(),
+                last_name: "Lovelace".to_string(),
+                email: format!("ada-{:04}@example.com", n),
+                salary: BigDecimal::from(50000),
+                version: 0,
+                terminated_on: None,
+            },
+        )
+        .await
+        .unwrap();
+        employees.push(employee);
+    }
+    // One on the first page, one only on the second.
+    for employee in [&employees[0], &employees[MAX_PAGE_SIZE as usize]] {
+        EmployeeAssignmentRepo::create(
+            &app.db,
+            Uuid::nil(),
+            &dto::EmployeeAssignment {
+                id: Uuid::nil(),
+                employee_id: employee.id,
+                assignment_id: id(&curve).parse().unwrap(),
+                allocation: BigDecimal::from(1),
+                initiative_id: id(&initiative).parse().unwrap(),
+                version: 0,
+            },
+        )
+        .await
+        .unwrap();
+    }
+
+    let reply = app.get("/api/allocations/export?year=2025", &token).await;
+    assert_eq!(reply.status, StatusCode::OK);
+    let csv = reply.body.as_str().unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert!(lines[1].contains(",ada-0000@example.com,"));
+    assert!(lines[2].contains(&format!(",ada-{:04}@example.com,", MAX_PAGE_SIZE)));
+
+    let reply = app
+        .get("/api/allocations/export?year=2025&format=xlsx", &token)
+        .await;
+    assert_eq!(reply.status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_reports_need_reports_read() {
+    let app = TestApp::new();
+    let token = app.login(&[Scope::ReportsRead]).await;
+    let other = app.login(&[Scope::AllocationsRead]).await;
+
+    let reply = app
+        .get("/api/reports/overallocated?year=2025", &token)
+        .await;
+    assert_eq!(reply.status, StatusCode::OK);
+    assert_eq!(reply.body["data"], json!([]));
+
+    let reply = app.get("/api/reports/utilization?year=2025", &other).await;
+    assert_problem(&reply, StatusCode::FORBIDDEN, "missing_scope");
+}
+
+#[tokio::test]
+async fn test_admin_manages_users_and_roles() {
+    let app = TestApp::new();
+    let admin = app.login(&[Scope::UsersAdmin]).await;
+
+    let reply = app
+        .send(
+            Method::POST,
+            "/api/admin/roles",
+            &admin,
+            None,
+            Some(json!({ "name": "planner", "scopes": ["allocations:read"] })),
+        )
+        .await;
+    assert_eq!(reply.status, StatusCode::CREATED);
+    let role = reply.body["data"].clone();
+
+    let user = json!({
+        "first_name": "Grace",
+        "last_name": "Hopper",
+        "email": "grace@example.com",
+        "external_id": "grace",
+        "external_id_source": ISSUER,
+        "role_id": role["id"]
+    });
+    let reply = app
+        .send(
+            Method::POST,
+            "/api/admin/users",
+            &admin,
+            None,
+            Some(user.clone()),
+        )
+        .await;
+    assert_eq!(reply.status, StatusCode::CREATED);
+    let created = reply.body["data"].clone();
+
+    let reply = app
+        .send(Method::POST, "/api/admin/users", &admin, None, Some(user))
+        .await;
+    assert_problem(&reply, StatusCode::CONFLICT, "duplicate");
+
+    let reply = app
+        .send(
+            Method::PUT,
+            &format!("/api/admin/users/{}/role", id(&created)),
+            &admin,
+            None,
+            Some(json!({ "role_id": Uuid::now_v7() })),
+        )
+        .await;
+    assert_problem(
+        &reply,
+        StatusCode::UNPROCESSABLE_ENTITY,
+        "invalid_reference",
+    );
+
+    // The new user signs in with the role they were given.
+    let reply = app.get("/api/me", &token("grace")).await;
+    assert_eq!(reply.status, StatusCode::OK);
+    assert_eq!(reply.body["data"]["scopes"], json!(["allocations:read"]));
+    let reply = app.get("/api/admin/users", &token("grace")).await;
+    assert_problem(&reply, StatusCode::FORBIDDEN, "missing_scope");
+}
+
+#[tokio::test]
+async fn test_audit_needs_audit_read() {
+    let app = TestApp::new();
+    let auditor = app.login(&[Scope::AuditRead]).await;
+
+    let reply = app
+        .get("/api/audit?entity=employee&limit=10", &auditor)
+        .await;
+    assert_eq!(reply.status, StatusCode::OK);
+    assert_eq!(reply.body["data"]["items"], json!([]));
+    assert_eq!(reply.body["data"]["next_cursor"], Value::Null);
+
+    let reply = app.get("/api/audit?entity=payroll", &auditor).await;
+    assert_problem(&reply, StatusCode::BAD_REQUEST, "malformed_request");
+}
+
+#[tokio::test]
+async fn test_employee_import_dry_run_lists_the_rows_without_ids() {
+    let app = TestApp::new();
+    let token = app.login(&Scope::ALL).await;
+    let csv = "first_name,last_name,email,salary\n\
+               Ada,Lovelace,ada@example.com,52000\n\
+               Grace,Hopper,grace@example.com,61000\n";
+
+    let reply = app
+        .upload(
+            "/api/employees/import?dry_run=true",
+            &token,
+            "text/csv",
+            csv,
+        )
+        .await;
+    assert_eq!(reply.status, StatusCode::OK);
+    assert_eq!(reply.body["data"]["count"], 2);
+    let rows = reply.body["data"]["employees"].as_array().unwrap();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[1]["email"], "grace@example.com");
+    assert!(
+        rows.iter()
+            .all(|x| x.get("id").is_none() && x.get("version").is_none())
+    );
+    let reply = app.get("/api/employees", &token).await;
+    assert_eq!(reply.body["data"]["items"], json!([]));
+
+    let reply = app
+        .upload("/api/employees/import", &token, "text/csv", csv)
+        .await;
+    assert_eq!(reply.status, StatusCode::CREATED);
+    let rows = reply.body["data"]["employees"].as_array().unwrap();
+    assert!(
+        rows.iter()
+            .all(|x| x["id"].is_string() && x["version"] == 1)
+    );
+}
+
+#[tokio::test]
+async fn test_plan_import_reads_the_wide_layout() {
+    let app = TestApp::new();
+    let token = app.login(&Scope::ALL).await;
+    app.create_employee(&token, "ada@example.com").await;
+    app.create_initiative(&token, "Platform").await;
+    app.create_initiative(&token, "Mobile").await;
+    let months = "jan,feb,mar,apr,may,jun,jul,aug,sep,oct,nov,dec";
+    let blank = ",".repeat(11);
+    let csv = format!(
+        "email,Platform{blank},Mobile{blank}\n\
+         ,{months},{months}\n\
+         ada@example.com,0.5{blank},0.25{blank}\n"
+    );
+
+    let reply = app
+        .upload(
+            "/api/allocations/import?year=2026",
+            &token,
+            "text/csv",
+            &csv,
+        )
+        .await;
+    assert_eq!(reply.status, StatusCode::OK);
+    assert_eq!(reply.body["data"]["added"], 2);
+
+    let reply = app.get("/api/allocations/export?year=2026", &token).await;
+    let csv = reply.body.as_str().unwrap();
+    assert!(csv.contains(",Platform,2026,0.5,0,0,0,0,0,0,0,0,0,0,0"));
+    assert!(csv.contains(",Mobile,2026,0.25,0,0,0,0,0,0,0,0,0,0,0"));
+}
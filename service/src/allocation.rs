@@ -0,0 +1,146 @@
+use bigdecimal::{BigDecimal, One, RoundingMode, Zero};
+use domain::dto;
+use std::collections::HashMap;
+use std::hash::Hash;
+use uuid::Uuid;
+
+/// Whether `value` is a valid fraction of a person, i.e. within `0..=1`.
+pub fn is_valid_fraction(value: &BigDecimal) -> bool {
+    *value >= BigDecimal::zero() && *value <= BigDecimal::one()
+}
+
+/// Returns the first month (January is 0) whose fraction lies outside `0..=1`.
+pub fn invalid_fraction(assignment: &dto::Assignment) -> Option<(usize, BigDecimal)> {
+    assignment
+        .months()
+        .into_iter()
+        .enumerate()
+        .find_map(|(month, fraction)| {
+            fraction
+                .filter(|fraction| !is_valid_fraction(fraction))
+                .map(|fraction| (month, fraction.clone()))
+        })
+}
+
+/// `lines` with the months after each employee's last day left unplanned, so someone who leaves
+/// during the year is neither costed nor counted as allocated for the rest of it. Lines of
+/// employees missing from `employees` are kept as they are.
+pub fn until_departure(
+    lines: &[dto::ScheduledAssignment],
+    employees: &[dto::Employee],
+) -> Vec<dto::ScheduledAssignment> {
+    let employees: HashMap<Uuid, &dto::Employee> = employees.iter().map(|x| (x.id, x)).collect();
+    lines
+        .iter()
+        .map(|line| {
+            let Some(employee) = employees.get(&line.employee_assignment.employee_id) else {
+                return line.clone();
+            };
+            let year = line.assignment.year;
+            let months = line.assignment.months();
+            dto::ScheduledAssignment {
+                employee_assignment: line.employee_assignment.clone(),
+                assignment: dto::Assignment {
+                    id: line.assignment.id,
+                    version: line.assignment.version,
+                    ..dto::Assignment::from_months(
+                        year,
+                        std::array::from_fn(|month| {
+                            months[month]
+                                .filter(|_| employee.employed_on(year, month))
+                                .cloned()
+                        }),
+                    )
+                },
+            }
+        })
+        .collect()
+}
+
+/// Indices (January is 0) of the months `fractions` of `year` plan some of `employee` after
+/// their last day.
+pub fn planned_after_departure(
+    employee: &dto::Employee,
+    year: i32,
+    fractions: [Option<&BigDecimal>; 12],
+) -> Vec<usize> {
+    (0..12)
+        .filter(|&month| {
+            fractions[month].is_some_and(|x| !x.is_zero()) && !employee.employed_on(year, month)
+        })
+        .collect()
+}
+
+/// Sums `allocation × monthly fraction` per employee and month. Unplanned months count as zero.
+pub fn effective_allocations(
+    lines: &[dto::ScheduledAssignment],
+) -> HashMap<Uuid, [BigDecimal; 12]> {
+    sum_by(lines, |line| line.employee_assignment.employee_id)
+}
+
+/// Like [`effective_allocations`], but keeps each `(employee, initiative)` pair apart.
+pub fn planned_allocations(
+    lines: &[dto::ScheduledAssignment],
+) -> HashMap<(Uuid, Uuid), [BigDecimal; 12]> {
+    sum_by(lines, |line| {
+        (
+            line.employee_assignment.employee_id,
+            line.employee_assignment.initiative_id,
+        )
+    })
+}
+
+fn sum_by<K: Eq + Hash>(
+    lines: &[dto::ScheduledAssignment],
+    key: impl Fn(&dto::ScheduledAssignment) -> K,
+) -> HashMap<K, [BigDecimal; 12]> {
+    let mut totals: HashMap<K, [BigDecimal; 12]> = HashMap::new();
+    for line in lines {
+        let months = totals
+            .entry(key(line))
+            .or_insert_with(|| std::array::from_fn(|_| BigDecimal::zero()));
+        for (total, fraction) in months.iter_mut().zip(line.assignment.months()) {
+            if let Some(fraction) = fraction {
+                *total += &line.employee_assignment.allocation * fraction;
+            }
+        }
+    }
+    totals
+}
+
+/// Indices (January is 0) of the months allocated above 100%.
+pub fn overallocated_months(months: &[BigDecimal; 12]) -> Vec<usize> {
+    months
+        .iter()
+        .enumerate()
+        .filter(|(_, total)| **total > BigDecimal::one())
+        .map(|(month, _)| month)
+        .collect()
+}
+
+/// The capacity left in each month, never below zero.
+pub fn unallocated(months: &[BigDecimal; 12]) -> [BigDecimal; 12] {
+    std::array::from_fn(|month| {
+        let left = BigDecimal::one() - &months[month];
+        if left < BigDecimal::zero() {
+            BigDecimal::zero()
+        } else {
+            left
+        }
+    })
+}
+
+/// The mean allocation over the twelve months, rounded to four decimal places.
+pub fn average_utilization(months: &[BigDecimal; 12]) -> BigDecimal {
+    let total: BigDecimal = months.iter().sum();
+    (total / BigDecimal::from(12)).with_scale_round(4, RoundingMode::HalfEven)
+}
+
+/// Renders month indices as their short names for error messages.
+pub fn month_names(months: &[usize]) -> String {
+    months
+        .iter()
+        .map(|&month| dto::MONTH_NAMES[month])
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -0,0 +1,198 @@
+use crate::allocation::month_names;
+use crate::validation::{FieldError, RowError, describe};
+use bigdecimal::BigDecimal;
+use domain::dto::MONTH_NAMES;
+use repository::assignment_errors::*;
+use repository::employee_assignment_errors::*;
+use repository::employee_errors::*;
+use repository::initiative_errors::*;
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum AddEmployeeError {
+    #[error("Invalid employee: {}", describe(.errors))]
+    Invalid { errors: Vec<FieldError> },
+
+    #[error(transparent)]
+    Create(#[from] CreateEmployeeError),
+}
+
+#[derive(Debug, Error)]
+pub enum ImportEmployeesError {
+    #[error("Invalid employees: {}", describe(.errors))]
+    Invalid { errors: Vec<RowError> },
+
+    #[error(transparent)]
+    Create(#[from] CreateEmployeesError),
+}
+
+#[derive(Debug, Error)]
+pub enum EditEmployeeError {
+    #[error("Invalid employee: {}", describe(.errors))]
+    Invalid { errors: Vec<FieldError> },
+
+    #[error(transparent)]
+    Update(#[from] UpdateEmployeeError),
+}
+
+#[derive(Debug, Error)]
+pub enum ChangeSalaryError {
+    #[error("Invalid salary: {}", describe(.errors))]
+    Invalid { errors: Vec<FieldError> },
+
+    #[error(transparent)]
+    Add(#[from] AddSalaryError),
+}
+
+#[derive(Debug, Error)]
+pub enum AssignEmployeeError {
+    #[error("Allocation {allocation} must be between 0 and 1")]
+    InvalidAllocation { allocation: BigDecimal },
+
+    #[error("Employee {employee_id} would be allocated above 100% in {year} for {}", month_names(.months))]
+    Overallocated {
+        employee_id: Uuid,
+        year: i32,
+        months: Vec<usize>,
+    },
+
+    #[error("Employee {employee_id} would be planned in {year} after their last day for {}", month_names(.months))]
+    Terminated {
+        employee_id: Uuid,
+        year: i32,
+        months: Vec<usize>,
+    },
+
+    #[error("Assignment {id} not found")]
+    AssignmentNotFound { id: Uuid },
+
+    #[error(transparent)]
+    GetAssignment(#[from] GetAssignmentError),
+
+    #[error(transparent)]
+    GetEmployee(#[from] GetEmployeeError),
+
+    #[error(transparent)]
+    Create(#[from] CreateEmployeeAssignmentError),
+}
+
+#[derive(Debug, Error)]
+pub enum ReassignEmployeeError {
+    #[error("Allocation {allocation} must be between 0 and 1")]
+    InvalidAllocation { allocation: BigDecimal },
+
+    #[error("Employee {employee_id} would be allocated above 100% in {year} for {}", month_names(.months))]
+    Overallocated {
+        employee_id: Uuid,
+        year: i32,
+        months: Vec<usize>,
+    },
+
+    #[error("Employee {employee_id} would be planned in {year} after their last day for {}", month_names(.months))]
+    Terminated {
+        employee_id: Uuid,
+        year: i32,
+        months: Vec<usize>,
+    },
+
+    #[error("Assignment {id} not found")]
+    AssignmentNotFound { id: Uuid },
+
+    #[error(transparent)]
+    GetAssignment(#[from] GetAssignmentError),
+
+    #[error(transparent)]
+    GetEmployee(#[from] GetEmployeeError),
+
+    #[error(transparent)]
+    Update(#[from] UpdateEmployeeAssignmentError),
+}
+
+#[derive(Debug, Error)]
+pub enum CreateAllocationCurveError {
+    #[error("{} allocation {fraction} must be between 0 and 1", MONTH_NAMES[*.month])]
+    InvalidFraction { month: usize, fraction: BigDecimal },
+
+    #[error(transparent)]
+    Create(#[from] CreateAssignmentError),
+}
+
+#[derive(Debug, Error)]
+pub enum UpdateAllocationCurveError {
+    #[error("{} allocation {fraction} must be between 0 and 1", MONTH_NAMES[*.month])]
+    InvalidFraction { month: usize, fraction: BigDecimal },
+
+    #[error("Employee {employee_id} would be allocated above 100% in {year} for {}", month_names(.months))]
+    Overallocated {
+        employee_id: Uuid,
+        year: i32,
+        months: Vec<usize>,
+    },
+
+    #[error(transparent)]
+    Update(#[from] UpdateAssignmentError),
+}
+
+#[derive(Debug, Error)]
+pub enum GetOverallocatedError {
+    #[error(transparent)]
+    GetSchedule(#[from] GetEmployeeAssignmentsError),
+
+    #[error(transparent)]
+    GetEmployees(#[from] GetEmployeesError),
+}
+
+#[derive(Debug, Error)]
+pub enum GetUtilizationError {
+    #[error(transparent)]
+    GetSchedule(#[from] GetEmployeeAssignmentsError),
+
+    #[error(transparent)]
+    GetEmployees(#[from] GetEmployeesError),
+}
+
+#[derive(Debug, Error)]
+pub enum GetAllocationPlanError {
+    #[error(transparent)]
+    GetSchedule(#[from] GetEmployeeAssignmentsError),
+
+    #[error(transparent)]
+    GetEmployees(#[from] GetEmployeesError),
+
+    #[error(transparent)]
+    GetInitiatives(#[from] GetInitiativesError),
+}
+
+#[derive(Debug, Error)]
+pub enum ImportAllocationPlanError {
+    #[error("Invalid plan: {}", describe(.errors))]
+    Invalid { errors: Vec<RowError> },
+
+    #[error(transparent)]
+    GetSchedule(#[from] GetEmployeeAssignmentsError),
+
+    #[error(transparent)]
+    GetEmployees(#[from] GetEmployeesError),
+
+    #[error(transparent)]
+    GetInitiatives(#[from] GetInitiativesError),
+
+    #[error(transparent)]
+    Save(#[from] SavePlanError),
+}
+
+#[derive(Debug, Error)]
+pub enum GetInitiativeCostsError {
+    #[error(transparent)]
+    GetSchedule(#[from] GetEmployeeAssignmentsError),
+
+    #[error(transparent)]
+    GetEmployees(#[from] GetEmployeesError),
+
+    #[error(transparent)]
+    GetSalaries(#[from] GetSalariesError),
+
+    #[error(transparent)]
+    GetInitiatives(#[from] GetInitiativesError),
+}
@@ -0,0 +1,652 @@
+use crate::allocation::{
+    effective_allocations, invalid_fraction, is_valid_fraction, overallocated_months,
+    planned_after_departure, planned_allocations, until_departure,
+};
+use crate::allocation_errors::*;
+use crate::cost::{initiative_costs, zero_months};
+use crate::plan::plan_import;
+use crate::validation::{FieldError, RowError, validate_employee, validate_salary};
+use bigdecimal::{BigDecimal, Zero};
+use chrono::NaiveDate;
+use domain::dto;
+use repository::assignment_errors::*;
+use repository::assignment_repo::AssignmentRepo;
+use repository::audit_errors::*;
+use repository::audit_repo::AuditRepo;
+use repository::employee_assignment_errors::*;
+use repository::employee_assignment_repo::EmployeeAssignmentRepo;
+use repository::employee_errors::*;
+use repository::employee_repo::EmployeeRepo;
+use repository::initiative_errors::*;
+use repository::initiative_repo::InitiativeRepo;
+use repository::role_errors::*;
+use repository::role_repo::RoleRepo;
+use repository::tag_errors::*;
+use repository::tag_repo::TagRepo;
+use repository::user_errors::*;
+use repository::user_repo::UserRepo;
+use std::collections::HashMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Page size used when a listing does not ask for one.
+pub const DEFAULT_PAGE_SIZE: i64 = 50;
+
+/// The largest page a single listing request may return.
+pub const MAX_PAGE_SIZE: i64 = 500;
+
+/// Everything the allocation rules need to read and write.
+pub trait AllocationRepo: EmployeeRepo + AssignmentRepo + EmployeeAssignmentRepo {}
+
+impl<T: EmployeeRepo + AssignmentRepo + EmployeeAssignmentRepo> AllocationRepo for T {}
+
+/// The domain service behind every handler. It owns the business rules and is generic over the
+/// repository traits, so each group of operations only requires the repositories it touches.
+#[derive(Debug, Clone)]
+pub struct AllocationService<R> {
+    repo: Arc<R>,
+}
+
+impl<R> AllocationService<R> {
+    pub fn new(repo: Arc<R>) -> Self {
+        Self { repo }
+    }
+}
+
+impl<R: EmployeeRepo> AllocationService<R> {
+    /// Validates and normalizes `ri` before storing it.
+    pub async fn create_employee(
+        &self,
+        actor: Uuid,
+        ri: &dto::Employee,
+    ) -> Result<dto::Employee, AddEmployeeError> {
+        let employee =
+            validate_employee(ri).map_err(|errors| AddEmployeeError::Invalid { errors })?;
+        Ok(self.repo.create(actor, &employee).await?)
+    }
+
+    /// Validates every row and, unless `dry_run`, creates them all in one transaction. Each
+    /// failing field is reported with the index of its row, including emails already taken by
+    /// an employee or an earlier row; with any failure nothing is created.
+    pub async fn import_employees(
+        &self,
+        actor: Uuid,
+        rows: &[dto::Employee],
+        dry_run: bool,
+    ) -> Result<Vec<dto::Employee>, ImportEmployeesError> {
+        let mut errors = Vec::new();
+        let mut valid_rows = Vec::new();
+        let mut employees = Vec::new();
+        for (row, ri) in rows.iter().enumerate() {
+            match validate_employee(ri) {
+                Ok(employee) => {
+                    valid_rows.push(row);
+                    employees.push(employee);
+                }
+                Err(invalid) => {
+                    errors.extend(invalid.into_iter().map(|error| RowError { row, error }));
+                }
+            }
+        }
+        // Rows that did validate are still checked for taken emails, so one pass finds all.
+        let rehearse = dry_run || !errors.is_empty();
+        match self.repo.create_all(actor, &employees, rehearse).await {
+            Ok(created) if errors.is_empty() => return Ok(created),
+            Ok(_) => {}
+            Err(CreateEmployeesError::Duplicate { rows: duplicates }) => {
+                errors.extend(duplicates.into_iter().map(|i| RowError {
+                    row: valid_rows[i],
+                    error: FieldError::new("email", "is already taken"),
+                }));
+            }
+            Err(e) => return Err(e.into()),
+        }
+        errors.sort_by_key(|x| x.row);
+        Err(ImportEmployeesError::Invalid { errors })
+    }
+
+    /// Validates and normalizes `ri` before storing it.
+    pub async fn update_employee(
+        &self,
+        actor: Uuid,
+        ri: &dto::Employee,
+    ) -> Result<dto::Employee, EditEmployeeError> {
+        let employee =
+            validate_employee(ri).map_err(|errors| EditEmployeeError::Invalid { errors })?;
+        Ok(self.repo.update(actor, &employee).await?)
+    }
+
+    pub async fn get_employee(&self, id: Uuid) -> Result<Option<dto::Employee>, GetEmployeeError> {
+        self.repo.get(id).await
+    }
+
+    pub async fn terminate_employee(
+        &self,
+        actor: Uuid,
+        id: Uuid,
+        version: i32,
+        terminated_on: NaiveDate,
+    ) -> Result<dto::Employee, TerminateEmployeeError> {
+        self.repo.terminate(actor, id, version, terminated_on).await
+    }
+
+    pub async fn restore_employee(
+        &self,
+        actor: Uuid,
+        id: Uuid,
+        version: i32,
+    ) -> Result<dto::Employee, RestoreEmployeeError> {
+        self.repo.restore(actor, id, version).await
+    }
+
+    /// Validates `salary` and adds it to the employee's history if they are still at `version`.
+    pub async fn change_salary(
+        &self,
+        actor: Uuid,
+        version: i32,
+        salary: &dto::Salary,
+    ) -> Result<dto::Employee, ChangeSalaryError> {
+        let salary =
+            validate_salary(salary).map_err(|errors| ChangeSalaryError::Invalid { errors })?;
+        Ok(self.repo.add_salary(actor, version, &salary).await?)
+    }
+
+    pub async fn get_salaries(
+        &self,
+        employee_id: Uuid,
+    ) -> Result<Vec<dto::Salary>, GetSalariesError> {
+        self.repo.get_salaries(Some(employee_id)).await
+    }
+
+    /// Lists one page of employees, keeping the page size within `1..=MAX_PAGE_SIZE`.
+    pub async fn get_employees(
+        &self,
+        query: &dto::EmployeeQuery,
+    ) -> Result<dto::Page<dto::Employee>, GetEmployeesError> {
+        let query = dto::EmployeeQuery {
+            limit: query.limit.clamp(1, MAX_PAGE_SIZE),
+            ..query.clone()
+        };
+        self.repo.get_page(&query).await
+    }
+}
+
+impl<R: UserRepo> AllocationService<R> {
+    pub async fn create_user(
+        &self,
+        actor: Uuid,
+        ri: &dto::User,
+    ) -> Result<dto::User, CreateUserError> {
+        self.repo.create(actor, ri).await
+    }
+
+    pub async fn get_user(&self, id: Uuid) -> Result<Option<dto::User>, GetUserError> {
+        self.repo.get(id).await
+    }
+
+    pub async fn get_users(&self) -> Result<Vec<dto::User>, GetUsersError> {
+        self.repo.get_all().await
+    }
+
+    pub async fn set_user_role(
+        &self,
+        actor: Uuid,
+        id: Uuid,
+        role_id: Option<Uuid>,
+    ) -> Result<dto::User, UpdateUserError> {
+        self.repo.set_role(actor, id, role_id).await
+    }
+
+    pub async fn deactivate_user(
+        &self,
+        actor: Uuid,
+        id: Uuid,
+    ) -> Result<dto::User, UpdateUserError> {
+        self.repo.deactivate(actor, id).await
+    }
+
+    pub async fn get_user_by_external_id(
+        &self,
+        external_id: &str,
+        external_id_source: &str,
+    ) -> Result<Option<dto::User>, GetUserError> {
+        self.repo
+            .get_by_external_id(external_id, external_id_source)
+            .await
+    }
+}
+
+impl<R: RoleRepo> AllocationService<R> {
+    pub async fn create_role(
+        &self,
+        actor: Uuid,
+        ri: &dto::Role,
+    ) -> Result<dto::Role, CreateRoleError> {
+        self.repo.create(actor, ri).await
+    }
+
+    pub async fn get_roles(&self) -> Result<Vec<dto::Role>, GetRolesError> {
+        self.repo.get_all().await
+    }
+
+    pub async fn get_role(&self, id: Uuid) -> Result<Option<dto::Role>, GetRoleError> {
+        self.repo.get(id).await
+    }
+}
+
+impl<R: AuditRepo> AllocationService<R> {
+    /// Lists one page of audit entries, keeping the page size within `1..=MAX_PAGE_SIZE`.
+    pub async fn get_audit_entries(
+        &self,
+        query: &dto::AuditQuery,
+    ) -> Result<dto::Page<dto::AuditEntry>, GetAuditEntriesError> {
+        let query = dto::AuditQuery {
+            limit: query.limit.clamp(1, MAX_PAGE_SIZE),
+            ..query.clone()
+        };
+        self.repo.get_entries(&query).await
+    }
+}
+
+impl<R: InitiativeRepo> AllocationService<R> {
+    pub async fn create_initiative(
+        &self,
+        actor: Uuid,
+        ri: &dto::Initiative,
+    ) -> Result<dto::Initiative, CreateInitiativeError> {
+        self.repo.create(actor, ri).await
+    }
+
+    pub async fn update_initiative(
+        &self,
+        actor: Uuid,
+        ri: &dto::Initiative,
+    ) -> Result<dto::Initiative, UpdateInitiativeError> {
+        self.repo.update(actor, ri).await
+    }
+
+    pub async fn get_initiative(
+        &self,
+        id: Uuid,
+    ) -> Result<Option<dto::Initiative>, GetInitiativeError> {
+        self.repo.get(id).await
+    }
+
+    pub async fn delete_initiative(
+        &self,
+        actor: Uuid,
+        id: Uuid,
+        version: i32,
+    ) -> Result<(), DeleteInitiativeError> {
+        self.repo.delete(actor, id, version).await
+    }
+
+    pub async fn get_initiatives(&self) -> Result<Vec<dto::Initiative>, GetInitiativesError> {
+        self.repo.get_all().await
+    }
+}
+
+impl<R: TagRepo> AllocationService<R> {
+    pub async fn create_tag(&self, actor: Uuid, ri: &dto::Tag) -> Result<dto::Tag, CreateTagError> {
+        self.repo.create(actor, ri).await
+    }
+
+    pub async fn rename_tag(&self, actor: Uuid, ri: &dto::Tag) -> Result<dto::Tag, RenameTagError> {
+        self.repo.rename(actor, ri).await
+    }
+
+    pub async fn merge_tags(
+        &self,
+        actor: Uuid,
+        source_id: Uuid,
+        source_version: i32,
+        target_id: Uuid,
+    ) -> Result<dto::TagUsage, MergeTagsError> {
+        self.repo
+            .merge(actor, source_id, source_version, target_id)
+            .await
+    }
+
+    pub async fn get_tag(&self, id: Uuid) -> Result<Option<dto::TagUsage>, GetTagError> {
+        self.repo.get(id).await
+    }
+
+    pub async fn delete_tag(
+        &self,
+        actor: Uuid,
+        id: Uuid,
+        version: i32,
+    ) -> Result<(), DeleteTagError> {
+        self.repo.delete(actor, id, version).await
+    }
+
+    pub async fn get_tags(&self) -> Result<Vec<dto::TagUsage>, GetTagsError> {
+        self.repo.get_all().await
+    }
+}
+
+impl<R: AssignmentRepo> AllocationService<R> {
+    /// Creates a monthly curve after checking every month is a valid fraction.
+    pub async fn create_assignment(
+        &self,
+        actor: Uuid,
+        ri: &dto::Assignment,
+    ) -> Result<dto::Assignment, CreateAllocationCurveError> {
+        if let Some((month, fraction)) = invalid_fraction(ri) {
+            return Err(CreateAllocationCurveError::InvalidFraction { month, fraction });
+        }
+        Ok(self.repo.create(actor, ri).await?)
+    }
+
+    pub async fn get_assignment(
+        &self,
+        id: Uuid,
+    ) -> Result<Option<dto::Assignment>, GetAssignmentError> {
+        self.repo.get(id).await
+    }
+
+    pub async fn delete_assignment(
+        &self,
+        actor: Uuid,
+        id: Uuid,
+        version: i32,
+    ) -> Result<(), DeleteAssignmentError> {
+        self.repo.delete(actor, id, version).await
+    }
+
+    pub async fn get_assignments(
+        &self,
+        year: Option<i32>,
+    ) -> Result<Vec<dto::Assignment>, GetAssignmentsError> {
+        self.repo.get_all(year).await
+    }
+}
+
+impl<R: EmployeeAssignmentRepo> AllocationService<R> {
+    pub async fn unassign(
+        &self,
+        actor: Uuid,
+        employee_id: Uuid,
+        id: Uuid,
+        version: i32,
+    ) -> Result<(), DeleteEmployeeAssignmentError> {
+        self.repo.delete(actor, employee_id, id, version).await
+    }
+
+    pub async fn get_employee_assignments(
+        &self,
+        employee_id: Uuid,
+    ) -> Result<Vec<dto::EmployeeAssignment>, GetEmployeeAssignmentsError> {
+        self.repo.get_for_employee(employee_id).await
+    }
+
+    pub async fn get_initiative_staff(
+        &self,
+        initiative_id: Uuid,
+    ) -> Result<Vec<dto::EmployeeAssignment>, GetEmployeeAssignmentsError> {
+        self.repo.get_for_initiative(initiative_id).await
+    }
+}
+
+impl<R: AllocationRepo> AllocationService<R> {
+    /// Creates the link unless it plans the employee after their last day or it pushes them
+    /// above 100% in any month.
+    pub async fn assign(
+        &self,
+        actor: Uuid,
+        ri: &dto::EmployeeAssignment,
+    ) -> Result<dto::EmployeeAssignment, AssignEmployeeError> {
+        if !is_valid_fraction(&ri.allocation) {
+            return Err(AssignEmployeeError::InvalidAllocation {
+                allocation: ri.allocation.clone(),
+            });
+        }
+        let assignment = AssignmentRepo::get(&*self.repo, ri.assignment_id)
+            .await?
+            .ok_or(AssignEmployeeError::AssignmentNotFound {
+                id: ri.assignment_id,
+            })?;
+        if let Some(employee) = EmployeeRepo::get(&*self.repo, ri.employee_id).await?
+            && !ri.allocation.is_zero()
+        {
+            let months = planned_after_departure(&employee, assignment.year, assignment.months());
+            if !months.is_empty() {
+                return Err(AssignEmployeeError::Terminated {
+                    employee_id: employee.id,
+                    year: assignment.year,
+                    months,
+                });
+            }
+        }
+        // The repository checks capacity under a lock on the employee, in the write's own
+        // transaction.
+        EmployeeAssignmentRepo::create(&*self.repo, actor, ri)
+            .await
+            .map_err(|e| match e {
+                CreateEmployeeAssignmentError::Overallocated {
+                    employee_id,
+                    year,
+                    months,
+                } => AssignEmployeeError::Overallocated {
+                    employee_id,
+                    year,
+                    months,
+                },
+                e => e.into(),
+            })
+    }
+
+    /// Updates the link unless it plans the employee after their last day or it pushes them
+    /// above 100% in any month.
+    pub async fn reassign(
+        &self,
+        actor: Uuid,
+        ri: &dto::EmployeeAssignment,
+    ) -> Result<dto::EmployeeAssignment, ReassignEmployeeError> {
+        if !is_valid_fraction(&ri.allocation) {
+            return Err(ReassignEmployeeError::InvalidAllocation {
+                allocation: ri.allocation.clone(),
+            });
+        }
+        let assignment = AssignmentRepo::get(&*self.repo, ri.assignment_id)
+            .await?
+            .ok_or(ReassignEmployeeError::AssignmentNotFound {
+                id: ri.assignment_id,
+            })?;
+        if let Some(employee) = EmployeeRepo::get(&*self.repo, ri.employee_id).await?
+            && !ri.allocation.is_zero()
+        {
+            let months = planned_after_departure(&employee, assignment.year, assignment.months());
+            if !months.is_empty() {
+                return Err(ReassignEmployeeError::Terminated {
+                    employee_id: employee.id,
+                    year: assignment.year,
+                    months,
+                });
+            }
+        }
+        EmployeeAssignmentRepo::update(&*self.repo, actor, ri)
+            .await
+            .map_err(|e| match e {
+                UpdateEmployeeAssignmentError::Overallocated {
+                    employee_id,
+                    year,
+                    months,
+                } => ReassignEmployeeError::Overallocated {
+                    employee_id,
+                    year,
+                    months,
+                },
+                e => e.into(),
+            })
+    }
+
+    /// Changes a monthly curve unless it pushes anyone following it above 100%.
+    pub async fn update_assignment(
+        &self,
+        actor: Uuid,
+        ri: &dto::Assignment,
+    ) -> Result<dto::Assignment, UpdateAllocationCurveError> {
+        if let Some((month, fraction)) = invalid_fraction(ri) {
+            return Err(UpdateAllocationCurveError::InvalidFraction { month, fraction });
+        }
+        AssignmentRepo::update(&*self.repo, actor, ri)
+            .await
+            .map_err(|e| match e {
+                UpdateAssignmentError::Overallocated {
+                    employee_id,
+                    year,
+                    months,
+                } => UpdateAllocationCurveError::Overallocated {
+                    employee_id,
+                    year,
+                    months,
+                },
+                e => e.into(),
+            })
+    }
+
+    /// Lists every employee allocated above 100% in at least one month of `year`.
+    pub async fn overallocated(
+        &self,
+        year: i32,
+    ) -> Result<Vec<dto::EmployeeAllocation>, GetOverallocatedError> {
+        let lines = self.repo.get_scheduled(year, None).await?;
+        let employees = EmployeeRepo::get_all(&*self.repo).await?;
+        let totals = effective_allocations(&until_departure(&lines, &employees));
+        Ok(employees
+            .into_iter()
+            .filter_map(|employee| {
+                let months = totals.get(&employee.id)?;
+                if overallocated_months(months).is_empty() {
+                    return None;
+                }
+                Some(dto::EmployeeAllocation {
+                    months: months.clone(),
+                    employee,
+                    year,
+                })
+            })
+            .collect())
+    }
+
+    /// Every employee's total allocation per month of `year`, including those with none. People
+    /// who left before `year` are skipped; those who left during it are kept.
+    pub async fn utilization(
+        &self,
+        year: i32,
+    ) -> Result<Vec<dto::EmployeeAllocation>, GetUtilizationError> {
+        let lines = self.repo.get_scheduled(year, None).await?;
+        let employees = EmployeeRepo::get_all(&*self.repo).await?;
+        let mut totals = effective_allocations(&until_departure(&lines, &employees));
+        Ok(employees
+            .into_iter()
+            .filter(|employee| employee.employed_in(year))
+            .map(|employee| dto::EmployeeAllocation {
+                months: totals
+                    .remove(&employee.id)
+                    .unwrap_or_else(|| std::array::from_fn(|_| BigDecimal::zero())),
+                employee,
+                year,
+            })
+            .collect())
+    }
+}
+
+impl<R: AllocationRepo + InitiativeRepo> AllocationService<R> {
+    /// The effective allocation of each of `employees` to each initiative per month of `year`,
+    /// ordered by employee email and then initiative name. Only pairs with a line planned in
+    /// `year` appear, so exports can page through employees and fetch each page's plan in turn.
+    pub async fn allocation_plan(
+        &self,
+        year: i32,
+        employees: &[dto::Employee],
+    ) -> Result<Vec<dto::PlannedAllocation>, GetAllocationPlanError> {
+        let ids: Vec<Uuid> = employees.iter().map(|employee| employee.id).collect();
+        let lines = self.repo.get_scheduled_for(year, &ids).await?;
+        let plan = planned_allocations(&until_departure(&lines, employees));
+        let employees: HashMap<Uuid, &dto::Employee> = employees
+            .iter()
+            .map(|employee| (employee.id, employee))
+            .collect();
+        let initiatives: HashMap<Uuid, dto::Initiative> = InitiativeRepo::get_all(&*self.repo)
+            .await?
+            .into_iter()
+            .map(|initiative| (initiative.id, initiative))
+            .collect();
+        let mut rows: Vec<dto::PlannedAllocation> = plan
+            .into_iter()
+            .filter_map(|((employee_id, initiative_id), months)| {
+                Some(dto::PlannedAllocation {
+                    employee: (*employees.get(&employee_id)?).clone(),
+                    initiative: initiatives.get(&initiative_id)?.clone(),
+                    year,
+                    months,
+                })
+            })
+            .collect();
+        rows.sort_by(|a, b| {
+            (&a.employee.email, &a.initiative.initiative)
+                .cmp(&(&b.employee.email, &b.initiative.initiative))
+        });
+        Ok(rows)
+    }
+
+    /// Works out what importing the plan `rows` for `year` changes and, unless `dry_run`,
+    /// stores it in one transaction. See [plan_import] for how rows are matched and checked; with
+    /// any failing row nothing is stored.
+    pub async fn import_allocation_plan(
+        &self,
+        actor: Uuid,
+        year: i32,
+        rows: &[dto::PlanRow],
+        dry_run: bool,
+    ) -> Result<Vec<dto::PlanChange>, ImportAllocationPlanError> {
+        let employees = EmployeeRepo::get_all(&*self.repo).await?;
+        let initiatives = InitiativeRepo::get_all(&*self.repo).await?;
+        let scheduled = self.repo.get_scheduled(year, None).await?;
+        // A link follows a single curve, so those planned in other years cannot take this one.
+        let elsewhere: HashMap<(Uuid, Uuid), i32> = self
+            .repo
+            .get_planned_elsewhere(year)
+            .await?
+            .into_iter()
+            .map(|(employee_id, initiative_id, other)| ((employee_id, initiative_id), other))
+            .collect();
+        let plan = plan_import(year, rows, &employees, &initiatives, &scheduled, &elsewhere)
+            .map_err(|errors| ImportAllocationPlanError::Invalid { errors })?;
+        if !dry_run {
+            self.repo.save_plan(actor, &plan.links).await?;
+        }
+        Ok(plan.changes)
+    }
+
+    /// Costs every initiative per month of `year` from allocation, monthly curve and the salary
+    /// each employee was paid that month. Months after an employee's last day cost nothing.
+    pub async fn initiative_costs(
+        &self,
+        year: i32,
+    ) -> Result<Vec<dto::InitiativeCost>, GetInitiativeCostsError> {
+        let lines = self.repo.get_scheduled(year, None).await?;
+        let employees = EmployeeRepo::get_all(&*self.repo).await?;
+        let mut salaries: HashMap<Uuid, Vec<dto::Salary>> = HashMap::new();
+        for salary in self.repo.get_salaries(None).await? {
+            salaries.entry(salary.employee_id).or_default().push(salary);
+        }
+        let mut costs = initiative_costs(&until_departure(&lines, &employees), &salaries);
+        let initiatives = InitiativeRepo::get_all(&*self.repo).await?;
+        Ok(initiatives
+            .into_iter()
+            .map(|initiative| {
+                let months = costs.remove(&initiative.id).unwrap_or_else(zero_months);
+                let total = months.iter().sum();
+                dto::InitiativeCost {
+                    initiative,
+                    year,
+                    months,
+                    total,
+                }
+            })
+            .collect())
+    }
+}
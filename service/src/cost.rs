@@ -0,0 +1,64 @@
+use bigdecimal::{BigDecimal, RoundingMode, Zero};
+use chrono::NaiveDate;
+use domain::dto;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Salaries are annual, so a month of full-time work costs a twelfth of it.
+const MONTHS_PER_YEAR: u32 = 12;
+
+/// Costs are reported in whole cents.
+const COST_SCALE: i64 = 2;
+
+/// The cost of `allocation × fraction` of an employee for one month, in exact precision.
+pub fn monthly_cost(
+    salary: &BigDecimal,
+    allocation: &BigDecimal,
+    fraction: &BigDecimal,
+) -> BigDecimal {
+    salary * allocation * fraction / BigDecimal::from(MONTHS_PER_YEAR)
+}
+
+/// The salary `history` of one employee puts in force on the first day of `month` (0-based).
+pub fn salary_for_month(history: &[dto::Salary], year: i32, month: usize) -> Option<&BigDecimal> {
+    let first = NaiveDate::from_ymd_opt(year, u32::try_from(month).ok()? + 1, 1)?;
+    dto::Salary::in_force(history, first).map(|x| &x.salary)
+}
+
+/// Sums the monthly cost of every line per initiative, rounded to cents. Each month uses the
+/// salary in force on its first day, from each employee's history in `salaries`; lines whose
+/// employee has no history are skipped.
+pub fn initiative_costs(
+    lines: &[dto::ScheduledAssignment],
+    salaries: &HashMap<Uuid, Vec<dto::Salary>>,
+) -> HashMap<Uuid, [BigDecimal; 12]> {
+    let mut totals: HashMap<Uuid, [BigDecimal; 12]> = HashMap::new();
+    for line in lines {
+        let Some(history) = salaries.get(&line.employee_assignment.employee_id) else {
+            continue;
+        };
+        let months = totals
+            .entry(line.employee_assignment.initiative_id)
+            .or_insert_with(zero_months);
+        for (month, (total, fraction)) in
+            months.iter_mut().zip(line.assignment.months()).enumerate()
+        {
+            if let Some(fraction) = fraction
+                && let Some(salary) = salary_for_month(history, line.assignment.year, month)
+            {
+                *total += monthly_cost(salary, &line.employee_assignment.allocation, fraction);
+            }
+        }
+    }
+    for months in totals.values_mut() {
+        for total in months.iter_mut() {
+            *total = total.with_scale_round(COST_SCALE, RoundingMode::HalfEven);
+        }
+    }
+    totals
+}
+
+/// Twelve zero-cost months, already at cent precision.
+pub fn zero_months() -> [BigDecimal; 12] {
+    std::array::from_fn(|_| BigDecimal::zero().with_scale(COST_SCALE))
+}
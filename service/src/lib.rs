@@ -0,0 +1,6 @@
+pub mod allocation;
+pub mod allocation_errors;
+pub mod allocation_service;
+pub mod cost;
+pub mod plan;
+pub mod validation;
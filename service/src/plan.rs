@@ -0,0 +1,234 @@
+use crate::allocation::{
+    is_valid_fraction, month_names, overallocated_months, planned_after_departure,
+    planned_allocations, until_departure,
+};
+use crate::validation::{FieldError, RowError};
+use bigdecimal::{BigDecimal, One, Zero};
+use domain::dto;
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+/// Monthly fractions are stored as `numeric(3,2)`.
+const FRACTION_SCALE: i64 = 2;
+
+/// What an import would do: the effect of every row, and the links to store for those that
+/// change anything.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Plan {
+    pub changes: Vec<dto::PlanChange>,
+    pub links: Vec<dto::PlannedLink>,
+}
+
+/// Whether `value` fits the scale fractions are stored with.
+fn fits_scale(value: &BigDecimal) -> bool {
+    value.normalized().as_bigint_and_exponent().1 <= FRACTION_SCALE
+}
+
+fn key(value: &str) -> String {
+    value.trim().to_lowercase()
+}
+
+/// Compares the `rows` of a plan for `year` with the links already `scheduled` that year and
+/// works out what importing them changes. Rows hold effective fractions, so a changed link is
+/// stored with an allocation of 1 and the fractions as its curve, which must then have at most
+/// two decimal places; a row left as exported may keep finer ones. A curve shared with other
+/// links is left alone and a new one is stored instead. Links already following a curve of
+/// another year, listed in `elsewhere` by `(employee, initiative)`, cannot be planned again. Rows
+/// are never deleted: links missing from `rows` stay as they are.
+///
+/// Every failing field is reported with the index of its row, including employees the import
+/// would allocate above 100% in some month.
+pub fn plan_import(
+    year: i32,
+    rows: &[dto::PlanRow],
+    employees: &[dto::Employee],
+    initiatives: &[dto::Initiative],
+    scheduled: &[dto::ScheduledAssignment],
+    elsewhere: &HashMap<(Uuid, Uuid), i32>,
+) -> Result<Plan, Vec<RowError>> {
+    // Existing links only take up capacity until their employee's last day.
+    let mut totals = planned_allocations(&until_departure(scheduled, employees));
+    let employees: HashMap<String, &dto::Employee> =
+        employees.iter().map(|x| (key(&x.email), x)).collect();
+    let mut by_name: HashMap<String, Vec<&dto::Initiative>> = HashMap::new();
+    for initiative in initiatives {
+        by_name
+            .entry(key(&initiative.initiative))
+            .or_default()
+            .push(initiative);
+    }
+    let current: HashMap<(Uuid, Uuid), &dto::ScheduledAssignment> = scheduled
+        .iter()
+        .map(|x| {
+            (
+                (
+                    x.employee_assignment.employee_id,
+                    x.employee_assignment.initiative_id,
+                ),
+                x,
+            )
+        })
+        .collect();
+    let mut followers: HashMap<Uuid, usize> = HashMap::new();
+    for line in scheduled {
+        *followers.entry(line.assignment.id).or_default() += 1;
+    }
+
+    let mut errors = Vec::new();
+    let mut changes = Vec::new();
+    let mut links = Vec::new();
+    let mut seen = HashSet::new();
+    // The first changed row of each employee, which carries any overallocation error.
+    let mut first_changed: HashMap<Uuid, usize> = HashMap::new();
+    for (row, ri) in rows.iter().enumerate() {
+        let mut invalid = Vec::new();
+        let employee = match employees.get(&key(&ri.email)) {
+            None => {
+                invalid.push(FieldError::new("email", "does not match an employee"));
+                None
+            }
+            Some(employee) if !employee.employed_in(year) => {
+                invalid.push(FieldError::new("email", "belongs to someone who has left"));
+                None
+            }
+            Some(employee) => Some(*employee),
+        };
+        let initiative = match by_name.get(&key(&ri.initiative)).map(Vec::as_slice) {
+            Some([initiative]) => Some(*initiative),
+            Some([_, _, ..]) => {
+                invalid.push(FieldError::new(
+                    "initiative",
+                    "matches more than one initiative",
+                ));
+                None
+            }
+            _ => {
+                invalid.push(FieldError::new(
+                    "initiative",
+                    "does not match an initiative",
+                ));
+                None
+            }
+        };
+        for (month, fraction) in ri.months.iter().enumerate() {
+            if let Some(fraction) = fraction
+                && !is_valid_fraction(fraction)
+            {
+                invalid.push(FieldError::new(
+                    dto::MONTH_NAMES[month],
+                    "must be between 0 and 1",
+                ));
+            }
+        }
+        if let Some(employee) = employee {
+            for month in
+                planned_after_departure(employee, year, ri.months.each_ref().map(Option::as_ref))
+            {
+                invalid.push(FieldError::new(
+                    dto::MONTH_NAMES[month],
+                    "falls after the employee's last day",
+                ));
+            }
+        }
+        let (Some(employee), Some(initiative), true) = (employee, initiative, invalid.is_empty())
+        else {
+            errors.extend(invalid.into_iter().map(|error| RowError { row, error }));
+            continue;
+        };
+        let pair = (employee.id, initiative.id);
+        if !seen.insert(pair) {
+            errors.push(RowError {
+                row,
+                error: FieldError::new("initiative", "is listed twice for this employee"),
+            });
+            continue;
+        }
+        if let Some(other) = elsewhere.get(&pair) {
+            errors.push(RowError {
+                row,
+                error: FieldError::new(
+                    "initiative",
+                    format!("is already planned for this employee in {}", other),
+                ),
+            });
+            continue;
+        }
+
+        let fractions = ri.months.clone();
+        let after = fractions
+            .clone()
+            .map(|x| x.unwrap_or_else(BigDecimal::zero));
+        let line = current.get(&pair);
+        let before = line.map(|_| totals[&pair].clone());
+        let kind = match &before {
+            None if after.iter().all(Zero::is_zero) => dto::PlanChangeKind::Unchanged,
+            None => dto::PlanChangeKind::Added,
+            Some(before) if *before == after => dto::PlanChangeKind::Unchanged,
+            Some(_) => dto::PlanChangeKind::Changed,
+        };
+        let too_fine: Vec<usize> = (0..12).filter(|&x| !fits_scale(&after[x])).collect();
+        if kind != dto::PlanChangeKind::Unchanged && !too_fine.is_empty() {
+            errors.extend(too_fine.into_iter().map(|month| RowError {
+                row,
+                error: FieldError::new(
+                    dto::MONTH_NAMES[month],
+                    format!("must have at most {} decimal places", FRACTION_SCALE),
+                ),
+            }));
+            continue;
+        }
+        if kind != dto::PlanChangeKind::Unchanged {
+            let mut assignment = dto::Assignment::from_months(year, fractions);
+            if let Some(line) = line
+                && followers[&line.assignment.id] == 1
+            {
+                assignment.id = line.assignment.id;
+                assignment.version = line.assignment.version;
+            }
+            links.push(dto::PlannedLink {
+                link: line.map(|x| x.employee_assignment.clone()),
+                employee_id: employee.id,
+                initiative_id: initiative.id,
+                allocation: BigDecimal::one(),
+                assignment,
+            });
+            totals.insert(pair, after.clone());
+            first_changed.entry(employee.id).or_insert(row);
+        }
+        changes.push(dto::PlanChange {
+            employee: employee.clone(),
+            initiative: initiative.clone(),
+            year,
+            kind,
+            before,
+            after,
+        });
+    }
+
+    let mut per_employee: HashMap<Uuid, [BigDecimal; 12]> = HashMap::new();
+    for ((employee_id, _), months) in &totals {
+        let sums = per_employee
+            .entry(*employee_id)
+            .or_insert_with(|| std::array::from_fn(|_| BigDecimal::zero()));
+        for (sum, month) in sums.iter_mut().zip(months) {
+            *sum += month;
+        }
+    }
+    for (employee_id, row) in first_changed {
+        let months = overallocated_months(&per_employee[&employee_id]);
+        if !months.is_empty() {
+            errors.push(RowError {
+                row,
+                error: FieldError::new(
+                    "email",
+                    format!("would be allocated above 100% in {}", month_names(&months)),
+                ),
+            });
+        }
+    }
+    if !errors.is_empty() {
+        errors.sort_by_key(|x| x.row);
+        return Err(errors);
+    }
+    Ok(Plan { changes, links })
+}
@@ -0,0 +1,163 @@
+use bigdecimal::{BigDecimal, Zero};
+use domain::dto;
+use std::fmt;
+
+/// The longest first or last name accepted, in characters.
+pub const MAX_NAME_LENGTH: usize = 100;
+
+/// The longest email address SMTP can deliver to.
+pub const MAX_EMAIL_LENGTH: usize = 254;
+
+/// Salaries are stored as `numeric(15,2)`: at most 13 integer digits and 2 decimals.
+const SALARY_INTEGER_DIGITS: u64 = 13;
+const SALARY_SCALE: i64 = 2;
+
+/// One field of a request that failed validation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldError {
+    pub field: &'static str,
+    pub message: String,
+}
+
+impl FieldError {
+    pub(crate) fn new(field: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            field,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.field, self.message)
+    }
+}
+
+/// A field that failed validation in one row of a bulk request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RowError {
+    /// Which row: an index into the request, or a line number once the caller has mapped it
+    /// back to its input.
+    pub row: usize,
+    pub error: FieldError,
+}
+
+impl fmt::Display for RowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "row {}: {}", self.row, self.error)
+    }
+}
+
+/// Joins `errors` into one sentence for logs and error messages.
+pub fn describe<T: fmt::Display>(errors: &[T]) -> String {
+    errors
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Checks every field of `employee` and returns it with names and email trimmed, or every
+/// failure at once so clients can fix them in one round trip.
+pub fn validate_employee(employee: &dto::Employee) -> Result<dto::Employee, Vec<FieldError>> {
+    let mut errors = Vec::new();
+    let first_name = employee.first_name.trim();
+    let last_name = employee.last_name.trim();
+    let email = employee.email.trim();
+    errors.extend(name_error("first_name", first_name));
+    errors.extend(name_error("last_name", last_name));
+    errors.extend(email_error(email));
+    errors.extend(salary_error(&employee.salary));
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    Ok(dto::Employee {
+        id: employee.id,
+        first_name: first_name.to_string(),
+        last_name: last_name.to_string(),
+        email: email.to_string(),
+        salary: employee.salary.clone(),
+        version: employee.version,
+        terminated_on: employee.terminated_on,
+    })
+}
+
+/// Checks a new rate for an employee, reporting failures the way [validate_employee] does.
+pub fn validate_salary(salary: &dto::Salary) -> Result<dto::Salary, Vec<FieldError>> {
+    match salary_error(&salary.salary) {
+        Some(error) => Err(vec![error]),
+        None => Ok(salary.clone()),
+    }
+}
+
+fn name_error(field: &'static str, name: &str) -> Option<FieldError> {
+    if name.is_empty() {
+        Some(FieldError::new(field, "must not be empty"))
+    } else if name.chars().count() > MAX_NAME_LENGTH {
+        Some(FieldError::new(
+            field,
+            format!("must be at most {} characters", MAX_NAME_LENGTH),
+        ))
+    } else if name.chars().any(char::is_control) {
+        Some(FieldError::new(
+            field,
+            "must not contain control characters",
+        ))
+    } else {
+        None
+    }
+}
+
+/// A pragmatic syntax check: one `@` between a local part and a dotted domain, no whitespace.
+/// Whether the mailbox exists is left to whoever sends to it.
+pub fn is_valid_email(email: &str) -> bool {
+    let Some((local, domain)) = email.split_once('@') else {
+        return false;
+    };
+    !local.is_empty()
+        && !domain.contains('@')
+        && !email.chars().any(|c| c.is_whitespace() || c.is_control())
+        && domain.split('.').count() >= 2
+        && domain
+            .split('.')
+            .all(|label| !label.is_empty() && !label.starts_with('-') && !label.ends_with('-'))
+}
+
+fn email_error(email: &str) -> Option<FieldError> {
+    if email.is_empty() {
+        Some(FieldError::new("email", "must not be empty"))
+    } else if email.chars().count() > MAX_EMAIL_LENGTH {
+        Some(FieldError::new(
+            "email",
+            format!("must be at most {} characters", MAX_EMAIL_LENGTH),
+        ))
+    } else if !is_valid_email(email) {
+        Some(FieldError::new("email", "must be a valid email address"))
+    } else {
+        None
+    }
+}
+
+fn salary_error(salary: &BigDecimal) -> Option<FieldError> {
+    let (_, scale) = salary.normalized().as_bigint_and_exponent();
+    let integer_digits = salary.with_scale(0).digits();
+    if salary < &BigDecimal::zero() {
+        Some(FieldError::new("salary", "must not be negative"))
+    } else if scale > SALARY_SCALE {
+        Some(FieldError::new(
+            "salary",
+            format!("must have at most {} decimal places", SALARY_SCALE),
+        ))
+    } else if integer_digits > SALARY_INTEGER_DIGITS {
+        Some(FieldError::new(
+            "salary",
+            format!(
+                "must have at most {} digits before the decimal point",
+                SALARY_INTEGER_DIGITS
+            ),
+        ))
+    } else {
+        None
+    }
+}
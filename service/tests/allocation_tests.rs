@@ -0,0 +1,134 @@
+use bigdecimal::{BigDecimal, Zero};
+use domain::dto;
+use service::allocation::{
+    average_utilization, effective_allocations, invalid_fraction, is_valid_fraction,
+    overallocated_months, planned_allocations, unallocated,
+};
+use uuid::Uuid;
+
+fn dec(value: &str) -> BigDecimal {
+    value.parse::<BigDecimal>().unwrap()
+}
+
+fn line(
+    employee_id: Uuid,
+    allocation: &str,
+    jan: Option<&str>,
+    feb: Option<&str>,
+) -> dto::ScheduledAssignment {
+    let assignment_id = Uuid::now_v7();
+    dto::ScheduledAssignment {
+        employee_assignment: dto::EmployeeAssignment {
+            id: Uuid::now_v7(),
+            employee_id,
+            assignment_id,
+            allocation: dec(allocation),
+            initiative_id: Uuid::now_v7(),
+            version: 0,
+        },
+        assignment: dto::Assignment {
+            id: assignment_id,
+            year: 2025,
+            jan: jan.map(dec),
+            feb: feb.map(dec),
+            mar: None,
+            apr: None,
+            may: None,
+            jun: None,
+            jul: None,
+            aug: None,
+            sep: None,
+            oct: None,
+            nov: None,
+            dec: None,
+            version: 0,
+        },
+    }
+}
+
+#[test]
+fn test_effective_allocation_combines_allocation_and_curve() {
+    let alice = Uuid::now_v7();
+    let bob = Uuid::now_v7();
+    let lines = vec![
+        line(alice, "0.80", Some("1.00"), Some("0.50")),
+        line(alice, "0.80", Some("0.50"), None),
+        line(bob, "0.50", Some("1.00"), Some("1.00")),
+    ];
+
+    let totals = effective_allocations(&lines);
+    assert_eq!(totals.len(), 2);
+    assert_eq!(totals[&alice][0], dec("1.20"));
+    assert_eq!(totals[&alice][1], dec("0.40"));
+    assert!(totals[&alice][2].is_zero());
+    assert_eq!(totals[&bob][0], dec("0.50"));
+}
+
+#[test]
+fn test_planned_allocation_keeps_initiatives_apart() {
+    let alice = Uuid::now_v7();
+    let mut first = line(alice, "0.50", Some("1.00"), Some("0.50"));
+    let mut second = line(alice, "0.50", Some("1.00"), None);
+    let other = line(alice, "0.25", Some("1.00"), None);
+    let initiative = first.employee_assignment.initiative_id;
+    second.employee_assignment.initiative_id = initiative;
+    first.assignment.mar = Some(dec("0.20"));
+
+    let plan = planned_allocations(&[first, second, other.clone()]);
+    assert_eq!(plan.len(), 2);
+    let months = &plan[&(alice, initiative)];
+    assert_eq!(months[0], dec("1.00"));
+    assert_eq!(months[1], dec("0.25"));
+    assert_eq!(months[2], dec("0.10"));
+    assert!(months[3].is_zero());
+    let other_months = &plan[&(alice, other.employee_assignment.initiative_id)];
+    assert_eq!(other_months[0], dec("0.25"));
+}
+
+#[test]
+fn test_overallocated_months() {
+    let alice = Uuid::now_v7();
+    let lines = vec![
+        line(alice, "0.80", Some("1.00"), Some("1.00")),
+        line(alice, "0.20", Some("1.00"), Some("1.00")),
+        line(alice, "0.80", None, Some("0.50")),
+    ];
+
+    let totals = effective_allocations(&lines);
+    // Exactly 100% in January is allowed, February reaches 140%.
+    assert_eq!(overallocated_months(&totals[&alice]), vec![1]);
+}
+
+#[test]
+fn test_fraction_validation() {
+    assert!(is_valid_fraction(&dec("0")));
+    assert!(is_valid_fraction(&dec("1.00")));
+    assert!(!is_valid_fraction(&dec("-0.01")));
+    assert!(!is_valid_fraction(&dec("1.01")));
+
+    let valid = line(Uuid::now_v7(), "1.00", Some("1.00"), None);
+    assert_eq!(invalid_fraction(&valid.assignment), None);
+    let invalid = line(Uuid::now_v7(), "1.00", Some("0.50"), Some("1.50"));
+    assert_eq!(
+        invalid_fraction(&invalid.assignment),
+        Some((1, dec("1.50")))
+    );
+}
+
+#[test]
+fn test_unallocated_and_average() {
+    let alice = Uuid::now_v7();
+    let lines = vec![
+        line(alice, "0.50", Some("1.00"), Some("1.00")),
+        line(alice, "0.80", None, Some("1.00")),
+    ];
+
+    let totals = effective_allocations(&lines);
+    let left = unallocated(&totals[&alice]);
+    assert_eq!(left[0], dec("0.50"));
+    // February is over-allocated, which leaves no capacity rather than negative capacity.
+    assert!(left[1].is_zero());
+    assert_eq!(left[2], dec("1"));
+    // (0.5 + 1.3) / 12
+    assert_eq!(average_utilization(&totals[&alice]), dec("0.15"));
+}
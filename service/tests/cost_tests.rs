@@ -0,0 +1,155 @@
+use bigdecimal::BigDecimal;
+use chrono::NaiveDate;
+use domain::dto;
+use service::allocation::until_departure;
+use service::cost::{initiative_costs, monthly_cost, salary_for_month};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+fn dec(value: &str) -> BigDecimal {
+    value.parse::<BigDecimal>().unwrap()
+}
+
+fn rate(employee_id: Uuid, effective_from: (i32, u32, u32), salary: &str) -> dto::Salary {
+    let (year, month, day) = effective_from;
+    dto::Salary {
+        employee_id,
+        effective_from: NaiveDate::from_ymd_opt(year, month, day).unwrap(),
+        salary: dec(salary),
+    }
+}
+
+fn line(
+    employee_id: Uuid,
+    initiative_id: Uuid,
+    allocation: &str,
+    jan: Option<&str>,
+) -> dto::ScheduledAssignment {
+    let assignment_id = Uuid::now_v7();
+    dto::ScheduledAssignment {
+        employee_assignment: dto::EmployeeAssignment {
+            id: Uuid::now_v7(),
+            employee_id,
+            assignment_id,
+            allocation: dec(allocation),
+            initiative_id,
+            version: 0,
+        },
+        assignment: dto::Assignment {
+            id: assignment_id,
+            year: 2025,
+            jan: jan.map(dec),
+            feb: None,
+            mar: None,
+            apr: None,
+            may: None,
+            jun: None,
+            jul: None,
+            aug: None,
+            sep: None,
+            oct: None,
+            nov: None,
+            dec: None,
+            version: 0,
+        },
+    }
+}
+
+#[test]
+fn test_monthly_cost() {
+    assert_eq!(
+        monthly_cost(&dec("120000"), &dec("0.5"), &dec("1")),
+        dec("5000")
+    );
+    assert_eq!(
+        monthly_cost(&dec("120000"), &dec("1"), &dec("0.25")),
+        dec("2500")
+    );
+}
+
+#[test]
+fn test_initiative_costs() {
+    let alice = Uuid::now_v7();
+    let bob = Uuid::now_v7();
+    let platform = Uuid::now_v7();
+    let mobile = Uuid::now_v7();
+    let salaries = HashMap::from([
+        (alice, vec![rate(alice, (2025, 1, 1), "120000.00")]),
+        (bob, vec![rate(bob, (2025, 1, 1), "100000.00")]),
+    ]);
+    let lines = vec![
+        line(alice, platform, "0.50", Some("1.00")),
+        line(bob, platform, "1.00", Some("0.50")),
+        line(alice, mobile, "0.50", None),
+    ];
+
+    let costs = initiative_costs(&lines, &salaries);
+    // 120000 / 12 * 0.5 + 100000 / 12 * 0.5, rounded to cents.
+    assert_eq!(costs[&platform][0], dec("9166.67"));
+    assert_eq!(costs[&platform][1], dec("0.00"));
+    assert_eq!(costs[&mobile][0], dec("0.00"));
+}
+
+#[test]
+fn test_salary_for_month() {
+    let alice = Uuid::now_v7();
+    let history = vec![
+        rate(alice, (2024, 3, 1), "90000.00"),
+        rate(alice, (2025, 7, 1), "120000.00"),
+        rate(alice, (2025, 9, 15), "150000.00"),
+    ];
+    // The first rate also covers the months before it.
+    assert_eq!(salary_for_month(&history, 2023, 0), Some(&dec("90000.00")));
+    assert_eq!(salary_for_month(&history, 2025, 5), Some(&dec("90000.00")));
+    assert_eq!(salary_for_month(&history, 2025, 6), Some(&dec("120000.00")));
+    // A raise from mid-month applies from the following month.
+    assert_eq!(salary_for_month(&history, 2025, 8), Some(&dec("120000.00")));
+    assert_eq!(salary_for_month(&history, 2025, 9), Some(&dec("150000.00")));
+    assert_eq!(salary_for_month(&[], 2025, 0), None);
+}
+
+#[test]
+fn test_initiative_costs_follow_raises() {
+    let alice = Uuid::now_v7();
+    let platform = Uuid::now_v7();
+    let salaries = HashMap::from([(
+        alice,
+        vec![
+            rate(alice, (2024, 1, 1), "120000.00"),
+            rate(alice, (2025, 7, 1), "240000.00"),
+        ],
+    )]);
+    let mut full_year = line(alice, platform, "1.00", Some("1.00"));
+    full_year.assignment.jun = Some(dec("1.00"));
+    full_year.assignment.jul = Some(dec("1.00"));
+
+    let costs = initiative_costs(&[full_year], &salaries);
+    assert_eq!(costs[&platform][0], dec("10000.00"));
+    assert_eq!(costs[&platform][5], dec("10000.00"));
+    assert_eq!(costs[&platform][6], dec("20000.00"));
+}
+
+#[test]
+fn test_initiative_costs_stop_after_the_last_day() {
+    let alice = Uuid::now_v7();
+    let platform = Uuid::now_v7();
+    let salaries = HashMap::from([(alice, vec![rate(alice, (2024, 1, 1), "120000.00")])]);
+    let mut full_year = line(alice, platform, "1.00", Some("1.00"));
+    full_year.assignment.jun = Some(dec("1.00"));
+    full_year.assignment.jul = Some(dec("1.00"));
+    let employee = dto::Employee {
+        id: alice,
+        first_name: "Alice".to_string(),
+        last_name: "Smith".to_string(),
+        email: "alice@example.com".to_string(),
+        salary: dec("120000.00"),
+        version: 1,
+        terminated_on: NaiveDate::from_ymd_opt(2025, 6, 15),
+    };
+
+    let costs = initiative_costs(&until_departure(&[full_year], &[employee]), &salaries);
+    assert_eq!(costs[&platform][0], dec("10000.00"));
+    // The month of the last day is still paid in full.
+    assert_eq!(costs[&platform][5], dec("10000.00"));
+    assert_eq!(costs[&platform][6], dec("0.00"));
+}
@@ -0,0 +1,155 @@
+use bigdecimal::BigDecimal;
+use domain::dto;
+use repository::employee_errors::CreateEmployeeError;
+use repository::in_memory_db::InMemoryDb;
+use service::allocation_errors::{AddEmployeeError, EditEmployeeError, ImportEmployeesError};
+use service::allocation_service::AllocationService;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Recorded as the author of every write these tests make.
+const ACTOR: Uuid = Uuid::nil();
+
+fn employee(email: &str) -> dto::Employee {
+    dto::Employee {
+        id: Uuid::nil(),
+        first_name: "run".to_string(),
+        last_name: "away".to_string(),
+        email: email.to_string(),
+        salary: "32_000.00".parse::<BigDecimal>().unwrap(),
+        version: 0,
+        terminated_on: None,
+    }
+}
+
+#[tokio::test]
+async fn test_create_and_get_employee() {
+    let service = AllocationService::new(Arc::new(InMemoryDb::new()));
+    let created = service
+        .create_employee(ACTOR, &employee("run@away.com"))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        service.get_employee(created.id).await.unwrap(),
+        Some(created.clone())
+    );
+    let page = service
+        .get_employees(&dto::EmployeeQuery {
+            limit: 10,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(page.items, vec![created]);
+    assert_eq!(page.next, None);
+}
+
+#[tokio::test]
+async fn test_create_duplicate_employee() {
+    let service = AllocationService::new(Arc::new(InMemoryDb::new()));
+    service
+        .create_employee(ACTOR, &employee("run@away.com"))
+        .await
+        .unwrap();
+
+    let duplicate = service
+        .create_employee(ACTOR, &employee("run@away.com"))
+        .await;
+    assert!(matches!(
+        duplicate,
+        Err(AddEmployeeError::Create(
+            CreateEmployeeError::Duplicate { .. }
+        ))
+    ));
+}
+
+#[tokio::test]
+async fn test_invalid_employee_is_not_stored() {
+    let service = AllocationService::new(Arc::new(InMemoryDb::new()));
+    let invalid = dto::Employee {
+        first_name: " ".to_string(),
+        ..employee("not-an-email")
+    };
+
+    let created = service.create_employee(ACTOR, &invalid).await;
+    assert!(matches!(
+        created,
+        Err(AddEmployeeError::Invalid { errors }) if errors.len() == 2
+    ));
+    let all = service
+        .get_employees(&dto::EmployeeQuery {
+            limit: 10,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert!(all.items.is_empty());
+
+    let stored = service
+        .create_employee(ACTOR, &employee(" run@away.com "))
+        .await
+        .unwrap();
+    assert_eq!(stored.email, "run@away.com");
+    let updated = service
+        .update_employee(
+            ACTOR,
+            &dto::Employee {
+                salary: "-1".parse::<BigDecimal>().unwrap(),
+                ..stored
+            },
+        )
+        .await;
+    assert!(matches!(updated, Err(EditEmployeeError::Invalid { .. })));
+}
+
+async fn stored(service: &AllocationService<InMemoryDb>) -> Vec<dto::Employee> {
+    let query = dto::EmployeeQuery {
+        limit: 10,
+        ..Default::default()
+    };
+    service.get_employees(&query).await.unwrap().items
+}
+
+#[tokio::test]
+async fn test_import_employees() {
+    let service = AllocationService::new(Arc::new(InMemoryDb::new()));
+    let rows = vec![employee("run@away.com"), employee(" this@away.com ")];
+
+    let rehearsed = service.import_employees(ACTOR, &rows, true).await.unwrap();
+    assert_eq!(rehearsed.len(), 2);
+    assert!(stored(&service).await.is_empty());
+
+    let created = service.import_employees(ACTOR, &rows, false).await.unwrap();
+    assert_eq!(created[1].email, "this@away.com");
+    assert_eq!(stored(&service).await, created);
+}
+
+#[tokio::test]
+async fn test_import_reports_every_row() {
+    let service = AllocationService::new(Arc::new(InMemoryDb::new()));
+    service
+        .create_employee(ACTOR, &employee("taken@away.com"))
+        .await
+        .unwrap();
+    let rows = vec![
+        employee("run@away.com"),
+        employee("taken@away.com"),
+        dto::Employee {
+            first_name: String::new(),
+            ..employee("not-an-email")
+        },
+        employee("run@away.com"),
+    ];
+
+    let imported = service.import_employees(ACTOR, &rows, false).await;
+    let Err(ImportEmployeesError::Invalid { errors }) = imported else {
+        panic!("expected every row to be checked, got {imported:?}");
+    };
+    let failures: Vec<(usize, &str)> = errors.iter().map(|x| (x.row, x.error.field)).collect();
+    assert_eq!(
+        failures,
+        vec![(1, "email"), (2, "first_name"), (2, "email"), (3, "email")]
+    );
+    assert_eq!(stored(&service).await.len(), 1);
+}
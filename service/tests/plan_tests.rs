@@ -0,0 +1,266 @@
+use bigdecimal::BigDecimal;
+use chrono::NaiveDate;
+use domain::dto;
+use service::plan::plan_import;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+fn dec(value: &str) -> BigDecimal {
+    value.parse::<BigDecimal>().unwrap()
+}
+
+fn employee(email: &str) -> dto::Employee {
+    dto::Employee {
+        id: Uuid::now_v7(),
+        first_name: "first".to_string(),
+        last_name: "last".to_string(),
+        email: email.to_string(),
+        salary: dec("60000"),
+        version: 1,
+        terminated_on: None,
+    }
+}
+
+fn initiative(name: &str) -> dto::Initiative {
+    dto::Initiative {
+        id: Uuid::now_v7(),
+        initiative: name.to_string(),
+        version: 1,
+    }
+}
+
+fn line(
+    employee: &dto::Employee,
+    initiative: &dto::Initiative,
+    allocation: &str,
+    jan: &str,
+) -> dto::ScheduledAssignment {
+    let mut months: [Option<BigDecimal>; 12] = Default::default();
+    months[0] = Some(dec(jan));
+    let assignment = dto::Assignment {
+        id: Uuid::now_v7(),
+        version: 3,
+        ..dto::Assignment::from_months(2026, months)
+    };
+    dto::ScheduledAssignment {
+        employee_assignment: dto::EmployeeAssignment {
+            id: Uuid::now_v7(),
+            employee_id: employee.id,
+            assignment_id: assignment.id,
+            allocation: dec(allocation),
+            initiative_id: initiative.id,
+            version: 2,
+        },
+        assignment,
+    }
+}
+
+fn row(email: &str, initiative: &str, jan: Option<&str>) -> dto::PlanRow {
+    let mut months: [Option<BigDecimal>; 12] = Default::default();
+    months[0] = jan.map(dec);
+    dto::PlanRow {
+        email: email.to_string(),
+        initiative: initiative.to_string(),
+        months,
+    }
+}
+
+#[test]
+fn test_plan_import_classifies_rows() {
+    let alice = employee("alice@example.com");
+    let bob = employee("bob@example.com");
+    let apollo = initiative("Apollo");
+    let zeus = initiative("Zeus");
+    // Alice and Bob share one curve on Apollo; Alice's Zeus curve is her own.
+    let mut shared = line(&bob, &apollo, "0.50", "1.00");
+    let on_apollo = line(&alice, &apollo, "0.50", "1.00");
+    shared.assignment = on_apollo.assignment.clone();
+    shared.employee_assignment.assignment_id = on_apollo.assignment.id;
+    let on_zeus = line(&alice, &zeus, "0.40", "0.50");
+    let scheduled = vec![on_apollo.clone(), shared, on_zeus.clone()];
+
+    let rows = vec![
+        // 0.5 × 1.00, written differently.
+        row(" ALICE@example.com ", "apollo", Some("0.5")),
+        row("bob@example.com", "Zeus", Some("0.3")),
+        row("alice@example.com", "Zeus", Some("0.25")),
+        row("bob@example.com", "Apollo", Some("0.6")),
+    ];
+    let plan = plan_import(
+        2026,
+        &rows,
+        &[alice.clone(), bob.clone()],
+        &[apollo.clone(), zeus.clone()],
+        &scheduled,
+        &HashMap::new(),
+    )
+    .unwrap();
+
+    let kinds: Vec<_> = plan.changes.iter().map(|x| x.kind).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            dto::PlanChangeKind::Unchanged,
+            dto::PlanChangeKind::Added,
+            dto::PlanChangeKind::Changed,
+            dto::PlanChangeKind::Changed,
+        ]
+    );
+    assert_eq!(plan.changes[1].before, None);
+    assert_eq!(plan.changes[2].before.as_ref().unwrap()[0], dec("0.20"));
+    assert_eq!(plan.changes[2].after[0], dec("0.25"));
+
+    assert_eq!(plan.links.len(), 3);
+    let added = &plan.links[0];
+    assert_eq!(added.link, None);
+    assert!(added.assignment.id.is_nil());
+    assert_eq!(added.assignment.jan, Some(dec("0.30")));
+    // Alice's own curve is rewritten in place, at the version it was read.
+    let own = &plan.links[1];
+    assert_eq!(own.link, Some(on_zeus.employee_assignment));
+    assert_eq!(own.assignment.id, on_zeus.assignment.id);
+    assert_eq!(own.assignment.version, 3);
+    assert_eq!(own.allocation, dec("1"));
+    // The curve Bob shares with Alice is left alone.
+    assert!(plan.links[2].assignment.id.is_nil());
+}
+
+#[test]
+fn test_plan_import_reports_every_failing_row() {
+    let alice = employee("alice@example.com");
+    let mut carol = employee("carol@example.com");
+    carol.terminated_on = NaiveDate::from_ymd_opt(2025, 6, 30);
+    let apollo = initiative("Apollo");
+    let twins = [initiative("Gemini"), initiative("gemini")];
+    let zeus = initiative("Zeus");
+    let busy = line(&alice, &zeus, "1.00", "0.80");
+    let elsewhere = HashMap::from([((alice.id, twins[0].id), 2025)]);
+
+    let rows = vec![
+        row("nobody@example.com", "Apollo", Some("0.5")),
+        row("carol@example.com", "Apollo", Some("0.5")),
+        row("alice@example.com", "Gemini", Some("0.5")),
+        row("alice@example.com", "Apollo", Some("1.5")),
+        row("alice@example.com", "Apollo", Some("0.5")),
+        row("alice@example.com", "Apollo", Some("0.5")),
+    ];
+    let mut initiatives = vec![apollo, zeus];
+    initiatives.extend(twins);
+    let errors = plan_import(
+        2026,
+        &rows,
+        &[alice, carol],
+        &initiatives,
+        &[busy],
+        &elsewhere,
+    )
+    .unwrap_err();
+
+    let found: Vec<_> = errors
+        .iter()
+        .map(|x| (x.row, x.error.field, x.error.message.as_str()))
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            (0, "email", "does not match an employee"),
+            (1, "email", "belongs to someone who has left"),
+            (2, "initiative", "matches more than one initiative"),
+            (3, "jan", "must be between 0 and 1"),
+            (4, "email", "would be allocated above 100% in jan"),
+            (5, "initiative", "is listed twice for this employee"),
+        ]
+    );
+}
+
+#[test]
+fn test_plan_import_refuses_links_planned_in_another_year() {
+    let alice = employee("alice@example.com");
+    let apollo = initiative("Apollo");
+    let elsewhere = HashMap::from([((alice.id, apollo.id), 2025)]);
+
+    let errors = plan_import(
+        2026,
+        &[row("alice@example.com", "Apollo", Some("0.5"))],
+        &[alice],
+        &[apollo],
+        &[],
+        &elsewhere,
+    )
+    .unwrap_err();
+    assert_eq!(
+        errors[0].error.message,
+        "is already planned for this employee in 2025"
+    );
+}
+
+#[test]
+fn test_plan_import_refuses_months_after_the_last_day() {
+    let mut alice = employee("alice@example.com");
+    alice.terminated_on = NaiveDate::from_ymd_opt(2026, 3, 15);
+    let apollo = initiative("Apollo");
+    let mut planned = row("alice@example.com", "Apollo", Some("0.5"));
+    planned.months[2] = Some(dec("0.5"));
+    planned.months[3] = Some(dec("0.5"));
+    planned.months[4] = Some(dec("0"));
+    planned.months[5] = Some(dec("0.25"));
+
+    let errors =
+        plan_import(2026, &[planned], &[alice], &[apollo], &[], &HashMap::new()).unwrap_err();
+    let found: Vec<_> = errors
+        .iter()
+        .map(|x| (x.error.field, x.error.message.as_str()))
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            ("apr", "falls after the employee's last day"),
+            ("jun", "falls after the employee's last day"),
+        ]
+    );
+}
+
+#[test]
+fn test_plan_import_refuses_fractions_finer_than_cents() {
+    let alice = employee("alice@example.com");
+    let apollo = initiative("Apollo");
+    let zeus = initiative("Zeus");
+    // Half of a quarter curve exports as 0.125, which a row left alone may keep.
+    let on_apollo = line(&alice, &apollo, "0.50", "0.25");
+
+    let plan = plan_import(
+        2026,
+        &[row("alice@example.com", "Apollo", Some("0.125"))],
+        std::slice::from_ref(&alice),
+        &[apollo.clone(), zeus.clone()],
+        std::slice::from_ref(&on_apollo),
+        &HashMap::new(),
+    )
+    .unwrap();
+    assert_eq!(plan.changes[0].kind, dto::PlanChangeKind::Unchanged);
+    assert!(plan.links.is_empty());
+
+    let errors = plan_import(
+        2026,
+        &[
+            row("alice@example.com", "Apollo", Some("0.135")),
+            row("alice@example.com", "Zeus", Some("0.333")),
+        ],
+        &[alice],
+        &[apollo, zeus],
+        &[on_apollo],
+        &HashMap::new(),
+    )
+    .unwrap_err();
+    let found: Vec<_> = errors
+        .iter()
+        .map(|x| (x.row, x.error.field, x.error.message.as_str()))
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            (0, "jan", "must have at most 2 decimal places"),
+            (1, "jan", "must have at most 2 decimal places"),
+        ]
+    );
+}
@@ -0,0 +1,87 @@
+use bigdecimal::BigDecimal;
+use domain::dto;
+use service::validation::{is_valid_email, validate_employee};
+use uuid::Uuid;
+
+fn employee(first_name: &str, last_name: &str, email: &str, salary: &str) -> dto::Employee {
+    dto::Employee {
+        id: Uuid::nil(),
+        first_name: first_name.to_string(),
+        last_name: last_name.to_string(),
+        email: email.to_string(),
+        salary: salary.parse::<BigDecimal>().unwrap(),
+        version: 0,
+        terminated_on: None,
+    }
+}
+
+fn failing_fields(employee: &dto::Employee) -> Vec<&'static str> {
+    validate_employee(employee)
+        .unwrap_err()
+        .into_iter()
+        .map(|x| x.field)
+        .collect()
+}
+
+#[test]
+fn test_valid_employee_is_trimmed() {
+    let valid = validate_employee(&employee(" run ", "away\t", " run@away.com ", "32000.50"));
+    assert_eq!(
+        valid,
+        Ok(employee("run", "away", "run@away.com", "32000.50"))
+    );
+}
+
+#[test]
+fn test_every_failing_field_is_reported() {
+    assert_eq!(
+        failing_fields(&employee("  ", "", "not-an-email", "-1")),
+        vec!["first_name", "last_name", "email", "salary"]
+    );
+}
+
+#[test]
+fn test_name_length_is_bounded() {
+    let long = "a".repeat(101);
+    assert_eq!(
+        failing_fields(&employee(&long, "away", "run@away.com", "1")),
+        vec!["first_name"]
+    );
+    assert!(validate_employee(&employee(&"a".repeat(100), "away", "run@away.com", "1")).is_ok());
+}
+
+#[test]
+fn test_salary_fits_numeric_15_2() {
+    assert!(
+        validate_employee(&employee("run", "away", "run@away.com", "9999999999999.99")).is_ok()
+    );
+    assert!(validate_employee(&employee("run", "away", "run@away.com", "0")).is_ok());
+    assert!(validate_employee(&employee("run", "away", "run@away.com", "1.10")).is_ok());
+    for salary in ["10000000000000", "1.001", "-0.01"] {
+        assert_eq!(
+            failing_fields(&employee("run", "away", "run@away.com", salary)),
+            vec!["salary"],
+            "{salary}"
+        );
+    }
+}
+
+#[test]
+fn test_email_syntax() {
+    for email in ["run@away.com", "first.last+tag@sub.example.org"] {
+        assert!(is_valid_email(email), "{email}");
+    }
+    for email in [
+        "",
+        "run",
+        "@away.com",
+        "run@",
+        "run@away",
+        "run@@away.com",
+        "run@away..com",
+        "run away@away.com",
+        "run@-away.com",
+    ] {
+        assert!(!is_valid_email(email), "{email}");
+    }
+}